@@ -0,0 +1,273 @@
+use std::{cmp::Reverse, process::ExitCode, time::SystemTime};
+
+use chrono::{Local, TimeZone};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use status::StatusFormat;
+use time_tracker_core::{
+    activities::{split_tags, Activities, Grouping, Rounding},
+    calendar, database_path,
+};
+
+mod status;
+
+/// Track the time spent on activities from the terminal, along with the desktop application
+#[derive(Parser)]
+#[command(name = "tt", version)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Start an activity, the running one is stopped
+    Start {
+        /// The name of the activity, the words starting with `#` are tags
+        ///     Ex : tt start code review #meeting
+        #[arg(required = true)]
+        activity: Vec<String>,
+        /// Start the activity this long ago, Ex : 15m, 1h30m, 90s
+        #[arg(long, value_parser = parse_ago, default_value = "0")]
+        ago: u64,
+    },
+    /// Stop the running activity
+    Stop {
+        /// Stop the activity this long ago, Ex : 15m, 1h30m, 90s
+        #[arg(long, value_parser = parse_ago, default_value = "0")]
+        ago: u64,
+        /// What was done during the activity
+        #[arg(long)]
+        notes: Option<String>,
+    },
+    /// Show the running activity and the time spent today
+    Status {
+        /// How the status is printed
+        #[arg(long, value_enum, default_value_t = StatusFormat::Human)]
+        format: StatusFormat,
+        /// Print the status again each time it changes and each minute, for the status bars
+        #[arg(long)]
+        follow: bool,
+    },
+    /// Show the time spent on each activity
+    Report {
+        #[command(flatten)]
+        period: ReportPeriod,
+        /// What the time is summed by
+        #[arg(long, value_enum, default_value_t = ReportGrouping::Activity)]
+        by: ReportGrouping,
+    },
+}
+
+/// The period of the report, the time since the last clear without one
+#[derive(Args)]
+#[group(multiple = false)]
+struct ReportPeriod {
+    /// Only the current day
+    #[arg(long)]
+    today: bool,
+    /// Only the current week
+    #[arg(long)]
+    week: bool,
+    /// Only the current month
+    #[arg(long)]
+    month: bool,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ReportGrouping {
+    Activity,
+    Project,
+    Client,
+    Tag,
+}
+
+impl From<ReportGrouping> for Grouping {
+    fn from(grouping: ReportGrouping) -> Self {
+        match grouping {
+            ReportGrouping::Activity => Grouping::Activity,
+            ReportGrouping::Project => Grouping::Project,
+            ReportGrouping::Client => Grouping::Client,
+            ReportGrouping::Tag => Grouping::Tag,
+        }
+    }
+}
+
+/// Parse a time ago in seconds, as `1h30m`, `15m`, `90s` or a number of minutes
+fn parse_ago(value: &str) -> Result<u64, String> {
+    let too_long = || format!("\"{}\" is too long ago", value);
+
+    let seconds = if let Ok(minutes) = value.parse::<u64>() {
+        minutes.checked_mul(60).ok_or_else(too_long)?
+    } else {
+        let mut seconds: u64 = 0;
+        let mut number = String::new();
+        for c in value.chars() {
+            match c {
+                '0'..='9' => number.push(c),
+                'h' | 'm' | 's' if !number.is_empty() => {
+                    let unit = match c {
+                        'h' => 3600,
+                        'm' => 60,
+                        _ => 1,
+                    };
+                    let count = number.parse::<u64>().map_err(|_| too_long())?;
+                    seconds = count
+                        .checked_mul(unit)
+                        .and_then(|time| seconds.checked_add(time))
+                        .ok_or_else(too_long)?;
+                    number.clear();
+                }
+                _ => return Err(format!("\"{}\" is not a duration, Ex : 15m, 1h30m, 90s", value)),
+            }
+        }
+        if !number.is_empty() {
+            return Err(format!("\"{}\" is missing a unit, Ex : 15m, 1h30m, 90s", value));
+        }
+        seconds
+    };
+
+    // The time ago is turned into a negative offset, see `offset`
+    i64::try_from(seconds).map_err(|_| too_long())?;
+    Ok(seconds)
+}
+
+/// Turn a time ago in seconds into an offset from now
+fn offset(ago: u64) -> Result<i64, String> {
+    i64::try_from(ago).map(|ago| -ago).map_err(|_| format!("{} seconds is too long ago", ago))
+}
+
+/// Format a duration in seconds as hours and minutes
+fn format_duration(seconds: u64) -> String {
+    format!("{}h{:02}", seconds / 3600, seconds % 3600 / 60)
+}
+
+/// Format a time in seconds since the epoch as a local time
+fn format_time(time: u64) -> String {
+    Local
+        .timestamp_opt(time as i64, 0)
+        .earliest()
+        .expect("A timestamp always has a local time")
+        .format("%H:%M")
+        .to_string()
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+fn start(activities: &Activities, activity: &str, ago: u64) -> Result<(), String> {
+    let (name, tags) = split_tags(activity);
+    activities.start_activity(&name, &tags, offset(ago)?).map_err(|e| e.to_string())?;
+
+    println!("Started {}", activity);
+    Ok(())
+}
+
+fn stop(activities: &Activities, ago: u64, notes: Option<String>) -> Result<(), String> {
+    let Some(session) = activities.current_session().map_err(|e| e.to_string())? else {
+        return Err("No activity is running".to_string());
+    };
+
+    if notes.is_some() {
+        activities.set_current_notes(notes.as_deref()).map_err(|e| e.to_string())?;
+    }
+    activities.stop_activity(offset(ago)?).map_err(|e| e.to_string())?;
+
+    let end_time = now().saturating_sub(ago).max(session.start_time);
+    println!("Stopped {} after {}", session.name, format_duration(session.duration(end_time)));
+    Ok(())
+}
+
+fn status(activities: &Activities, format: StatusFormat, follow: bool) -> Result<(), String> {
+    if follow {
+        return status::follow(activities, format);
+    }
+
+    let status = activities.status(now()).map_err(|e| e.to_string())?;
+    println!("{}", status::format_status(&status, format));
+    Ok(())
+}
+
+fn report(activities: &Activities, period: ReportPeriod, grouping: Grouping) -> Result<(), String> {
+    let now = now();
+    let day_start_hour = activities.day_start_hour().map_err(|e| e.to_string())?;
+    let calendar = calendar::calendar_at(now, day_start_hour);
+    let range = if period.today {
+        Some(calendar.day)
+    } else if period.week {
+        Some(calendar.week)
+    } else if period.month {
+        Some(calendar.month)
+    } else {
+        None
+    };
+
+    // Rounded like the statistics of the desktop application
+    let rounding = activities.rounding().map_err(|e| e.to_string())?;
+    let rounding = if rounding.statistics { rounding } else { Rounding::default() };
+
+    let sessions = activities
+        .sessions_in(range.map(|range| (range.start, range.end)), None)
+        .map_err(|e| e.to_string())?;
+    let mut times: Vec<(String, u64)> = Vec::new();
+    for session in sessions {
+        let duration = rounding.round_session(session.duration(now));
+        for key in grouping.keys(&session) {
+            match times.iter_mut().find(|(name, _)| *name == key) {
+                Some((_, time)) => *time += duration,
+                None => times.push((key, duration)),
+            }
+        }
+    }
+    for (_, time) in times.iter_mut() {
+        *time = rounding.round_total(*time);
+    }
+    times.sort_by_key(|(_, time)| Reverse(*time));
+
+    if times.is_empty() {
+        println!("Nothing was tracked");
+        return Ok(());
+    }
+    for (name, time) in &times {
+        println!("{:>7}  {}", format_duration(*time), name);
+    }
+    // The tags can count a session several times so there is no total by tag
+    if !matches!(grouping, Grouping::Tag) {
+        println!("{:>7}  Total", format_duration(times.iter().map(|(_, time)| time).sum()));
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    // The desktop application uses the same database
+    let activities = database_path()
+        .map_err(|e| e.to_string())
+        .and_then(|path| Activities::open(&path).map_err(|e| e.to_string()));
+    let activities = match activities {
+        Ok(activities) => activities,
+        Err(e) => {
+            eprintln!("Failed to open the database: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let res = match cli.command {
+        Command::Start { activity, ago } => start(&activities, &activity.join(" "), ago),
+        Command::Stop { ago, notes } => stop(&activities, ago, notes),
+        Command::Status { format, follow } => status(&activities, format, follow),
+        Command::Report { period, by } => report(&activities, period, by.into()),
+    };
+
+    match res {
+        Ok(_) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
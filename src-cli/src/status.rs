@@ -0,0 +1,182 @@
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+use clap::ValueEnum;
+use serde_json::json;
+use time_tracker_core::activities::{Activities, Status};
+
+use crate::{format_duration, format_time, now};
+
+/// How often the window is looked for when following the status without it
+const RECONNECT_INTERVAL: Duration = Duration::from_secs(10);
+
+/// How the status is printed
+#[derive(Clone, Copy, ValueEnum)]
+pub enum StatusFormat {
+    /// A sentence for humans
+    Human,
+    /// One short line for polybar, i3blocks and the like
+    Plain,
+    /// One JSON object per line for the custom modules of waybar
+    Waybar,
+}
+
+/// Format the status as a single line
+pub fn format_status(status: &Status, format: StatusFormat) -> String {
+    let today = format_duration(status.today);
+    let tags: String = status.tags.iter().map(|tag| format!(" #{}", tag)).collect();
+    let paused = if status.paused { " (paused)" } else { "" };
+
+    match (format, &status.activity) {
+        (StatusFormat::Human, None) => format!("Nothing is running, {} today", today),
+        (StatusFormat::Human, Some(activity)) => format!(
+            "{}{} since {} ({}{}), {} today",
+            activity,
+            tags,
+            format_time(status.start_time.unwrap_or(status.time)),
+            format_duration(status.elapsed),
+            if status.paused { ", paused" } else { "" },
+            today
+        ),
+        (StatusFormat::Plain, None) => format!("Idle · {}", today),
+        (StatusFormat::Plain, Some(activity)) => {
+            format!("{}{} {} · {}", activity, paused, format_duration(status.elapsed), today)
+        }
+        (StatusFormat::Waybar, activity) => {
+            let (text, tooltip, class) = match activity {
+                None => ("Idle".to_string(), format!("Nothing is running\nToday {}", today), "idle"),
+                Some(activity) => (
+                    format!("{}{} {}", activity, paused, format_duration(status.elapsed)),
+                    format!(
+                        "{}{}\nSince {}\nToday {}",
+                        activity,
+                        tags,
+                        format_time(status.start_time.unwrap_or(status.time)),
+                        today
+                    ),
+                    if status.paused { "paused" } else { "running" },
+                ),
+            };
+            json!({ "text": text, "tooltip": tooltip, "class": class, "alt": class }).to_string()
+        }
+    }
+}
+
+/// How long until the minutes shown change
+fn next_minute(status: &Status) -> Duration {
+    if status.activity.is_some() && !status.paused {
+        Duration::from_secs(60 - status.elapsed % 60)
+    } else {
+        Duration::from_secs(60)
+    }
+}
+
+/// Print a line unless it is the one printed last
+fn print_changed(last: &mut String, line: String) {
+    if *last != line {
+        println!("{}", line);
+        *last = line;
+    }
+}
+
+/**
+Print the status on a new line each time it changes and each minute, until the status can't be printed anymore.
+
+The changes are sent by the window through its socket. When the window isn't running the database is read each time
+the minutes shown change and the socket is tried again in between, until the window is back.
+ */
+pub fn follow(activities: &Activities, format: StatusFormat) -> Result<(), String> {
+    let mut last = String::new();
+    loop {
+        let status = activities.status(now()).map_err(|e| e.to_string())?;
+        print_changed(&mut last, format_status(&status, format));
+
+        let until = Instant::now() + next_minute(&status);
+        while Instant::now() < until {
+            // The database is read again as soon as the window goes away
+            #[cfg(unix)]
+            match socket::subscribe(format, &mut last) {
+                Ok(true) => break,
+                Ok(false) => {}
+                // Waits like when the window isn't running so a failing window isn't asked again and again
+                Err(e) => eprintln!("Lost the window: {}", e),
+            }
+
+            thread::sleep(RECONNECT_INTERVAL.min(until.saturating_duration_since(Instant::now())));
+        }
+    }
+}
+
+#[cfg(unix)]
+mod socket {
+    use std::{
+        io::{self, BufRead, BufReader, Write},
+        os::unix::net::UnixStream,
+        sync::mpsc::{self, RecvTimeoutError},
+        thread,
+    };
+
+    use time_tracker_core::{
+        protocol::{Message, Request},
+        socket_path,
+    };
+
+    use super::{format_status, next_minute, print_changed, StatusFormat};
+    use crate::now;
+
+    /**
+    Print the status sent by the window until it goes away.
+
+    # Arguments
+    format - How the status is printed
+    last - The line printed last, the status is only printed when it changes
+
+    # Returns
+    Whether the window was running, it isn't if the socket can't be connected to
+
+    # Errors
+    Fails if the window answers with an error
+     */
+    pub fn subscribe(format: StatusFormat, last: &mut String) -> io::Result<bool> {
+        let Ok(stream) = UnixStream::connect(socket_path()?) else {
+            return Ok(false);
+        };
+        let mut writer = stream.try_clone()?;
+        let mut request = serde_json::to_string(&Request::Subscribe)?;
+        request.push('\n');
+        writer.write_all(request.as_bytes())?;
+
+        // The messages are read on their own thread so the minutes can go by while waiting for them
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stream).lines() {
+                let Ok(line) = line else { break; };
+                let message = match serde_json::from_str::<Message>(&line) {
+                    Ok(Message::Ok { status } | Message::Changed { status }) => Ok(status),
+                    Ok(Message::Error { message }) => Err(message),
+                    Err(e) => Err(format!("Invalid message: {}", e)),
+                };
+                if tx.send(message).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let mut status = match rx.recv() {
+            Ok(status) => status.map_err(io::Error::other)?,
+            Err(_) => return Ok(true),
+        };
+        loop {
+            let current = status.at(now());
+            print_changed(last, format_status(&current, format));
+
+            match rx.recv_timeout(next_minute(&current)) {
+                Ok(changed) => status = changed.map_err(io::Error::other)?,
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => return Ok(true),
+            }
+        }
+    }
+}
@@ -0,0 +1,814 @@
+use std::{collections::HashMap, fmt::Display, path::Path, time::{Duration, SystemTime}};
+
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+
+use crate::calendar;
+
+mod billing;
+mod breaks;
+mod budgets;
+mod dump;
+mod idle;
+mod import;
+mod integrity;
+mod migrations;
+mod notes;
+mod projects;
+mod rounding;
+mod settings;
+mod status;
+mod tags;
+
+pub use billing::Invoice;
+pub use breaks::Break;
+pub use budgets::{Budget, BudgetUsage};
+pub use dump::{Dump, RestoreMode};
+pub use import::ImportedSession;
+pub use integrity::{Issue, Repair};
+pub use projects::{Client, Grouping, Project};
+pub use rounding::{Rounding, RoundingScope};
+pub use settings::{ApiSettings, WorkingHours};
+pub use status::Status;
+pub use tags::split_tags;
+
+/// The errors that can happen when interacting with the activities database
+#[derive(Debug)]
+pub enum ActivitiesError {
+    /// An error from the underlying database
+    Database(rusqlite::Error),
+    /// The database was created by a newer version of the application and can't be opened safely
+    UnsupportedSchema { found: u32, supported: u32 },
+    /// The dump was made by a newer version of the application and can't be restored safely
+    UnsupportedDump { found: u32, supported: u32 },
+    /// A setting was given a value it can't take
+    InvalidSetting(String),
+    /// A time range ends before it starts
+    InvalidTimeRange { start: u64, end: u64 },
+    /// No session has the given id
+    SessionNotFound(i64),
+    /// A session can't be saved as is
+    InvalidSession(String),
+    /// A client or project can't be saved as is
+    InvalidProject(String),
+}
+
+impl Display for ActivitiesError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ActivitiesError::Database(e) => write!(f, "Database error: {}", e),
+            ActivitiesError::UnsupportedSchema { found, supported } => write!(
+                f,
+                "The database schema (version {}) is newer than the one supported by this application (version {}), please update the application",
+                found, supported
+            ),
+            ActivitiesError::UnsupportedDump { found, supported } => write!(
+                f,
+                "The dump format (version {}) is newer than the one supported by this application (version {}), please update the application",
+                found, supported
+            ),
+            ActivitiesError::InvalidSetting(reason) => write!(f, "Invalid setting: {}", reason),
+            ActivitiesError::InvalidTimeRange { start, end } => {
+                write!(f, "Invalid time range: the end ({}) is before the start ({})", end, start)
+            }
+            ActivitiesError::SessionNotFound(id) => write!(f, "No session with the id {}", id),
+            ActivitiesError::InvalidSession(reason) => write!(f, "Invalid session: {}", reason),
+            ActivitiesError::InvalidProject(reason) => write!(f, "Invalid project: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for ActivitiesError {}
+
+impl From<rusqlite::Error> for ActivitiesError {
+    fn from(e: rusqlite::Error) -> Self {
+        ActivitiesError::Database(e)
+    }
+}
+
+/// A session of an activity as stored in the activities table
+#[derive(Debug, Clone, Serialize)]
+pub struct Session {
+    /// The unique identifier of the session
+    pub id: i64,
+    /// The name of the activity
+    pub name: String,
+    /// The time the session started in seconds since the epoch
+    pub start_time: u64,
+    /// The time the session ended in seconds since the epoch, None if it is still running
+    pub end_time: Option<u64>,
+    /// The name of the project of the activity
+    pub project: String,
+    /// The name of the client of the project if it has one
+    pub client: Option<String>,
+    /// The tags of the session without the `#`, ordered by name
+    pub tags: Vec<String>,
+    /// A free text description of what was done during the session
+    pub notes: Option<String>,
+    /// Whether the session is billed to the client
+    pub billable: bool,
+    /// The hourly rate in cents of the project, or of its client if the project has none
+    pub hourly_rate: Option<i64>,
+    /// The breaks taken during the session ordered by start time
+    pub breaks: Vec<Break>,
+}
+
+impl Session {
+    /// The columns to select from `Session::TABLES` to build a session with `Session::from_row`
+    const COLUMNS: &'static str = "activities.id, activities.name, activities.start_time, activities.end_time, projects.name, clients.name, 
+        (SELECT GROUP_CONCAT(tags.name, ' ') FROM session_tags JOIN tags ON tags.id = session_tags.tag_id WHERE session_tags.session_id = activities.id), 
+        activities.notes, activities.billable, COALESCE(projects.hourly_rate, clients.hourly_rate), 
+        (SELECT GROUP_CONCAT(breaks.start_time || '-' || COALESCE(breaks.end_time, ''), ' ') FROM breaks WHERE breaks.session_id = activities.id)";
+
+    /// The activities joined with their project and client, the activities without a project belong to the unassigned project
+    const TABLES: &'static str = "activities 
+        LEFT JOIN activity_projects ON activity_projects.name = activities.name 
+        JOIN projects ON projects.id = COALESCE(activity_projects.project_id, 1) 
+        LEFT JOIN clients ON clients.id = projects.client_id";
+
+    /// Build a session from a row selecting `Session::COLUMNS`
+    fn from_row(row: &rusqlite::Row) -> Result<Self, rusqlite::Error> {
+        // The tags can't contain spaces so they are concatenated with spaces
+        let tags: Option<String> = row.get(6)?;
+        let mut tags: Vec<String> = tags.unwrap_or_default().split(' ').filter(|tag| !tag.is_empty()).map(str::to_string).collect();
+        tags.sort();
+
+        let breaks: Option<String> = row.get(10)?;
+        let breaks = breaks.as_deref().map(Break::parse_list).unwrap_or_default();
+
+        Ok(Session {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            start_time: row.get(2)?,
+            end_time: row.get(3)?,
+            project: row.get(4)?,
+            client: row.get(5)?,
+            tags,
+            notes: row.get(7)?,
+            billable: row.get(8)?,
+            hourly_rate: row.get(9)?,
+            breaks,
+        })
+    }
+
+    /**
+    Get the time spent on the session without the breaks.
+
+    The breaks are cut to the session since the session can be cut to a range, and the time of the breaks overlapping
+    each other is only removed once.
+
+    # Arguments
+    now - The current time in seconds since the epoch, used as the end of the running session and break
+     */
+    pub fn duration(&self, now: u64) -> u64 {
+        let end_time = self.end_time.unwrap_or(now).max(self.start_time);
+        let mut breaks: Vec<(u64, u64)> = self
+            .breaks
+            .iter()
+            .map(|pause| {
+                let start = pause.start_time.clamp(self.start_time, end_time);
+                let end = pause.end_time.unwrap_or(now).clamp(start, end_time);
+                (start, end)
+            })
+            .collect();
+        breaks.sort_unstable();
+
+        // The end of the breaks counted so far, a break is only counted from there
+        let mut counted = self.start_time;
+        let mut paused = 0;
+        for (start, end) in breaks {
+            let start = start.max(counted);
+            if end > start {
+                paused += end - start;
+                counted = end;
+            }
+        }
+
+        (end_time - self.start_time).saturating_sub(paused)
+    }
+}
+
+/**
+A struct serving as an interface to the activities table in the database.
+
+# Table Structure
+- Activities
+    - id - The unique identifier for the activity
+    - name - The name of the activity it identifies the activity
+    - start_time - The time the activity started in seconds since the epoch
+    - end_time - The time the activity ended in seconds since the epoch if it has ended
+    - notes - A free text description of what was done during the session
+    - billable - Whether the session is billed to the client, true by default
+- Clears
+    - id - The unique identifier for the clear
+    - time - The time the clear was performed in seconds since the epoch
+- Settings
+    - key - The name of the setting
+    - value - The value of the setting
+- Clients
+    - id - The unique identifier for the client
+    - name - The unique name of the client
+    - hourly_rate - The rate of the client in cents per hour if it has one
+- Projects
+    - id - The unique identifier for the project, the project 1 is the unassigned project
+    - name - The name of the project, unique for its client
+    - client_id - The client the project is for if it has one
+    - hourly_rate - The rate of the project in cents per hour, overrides the rate of the client if set
+- Activity projects
+    - name - The name of the activity
+    - project_id - The project the activity belongs to, the activities without a row belong to the unassigned project
+- Tags
+    - id - The unique identifier for the tag
+    - name - The unique name of the tag without the `#`
+- Session tags
+    - session_id - The session tagged
+    - tag_id - The tag of the session
+- Breaks
+    - id - The unique identifier for the break
+    - session_id - The session paused
+    - start_time - The time the session was paused in seconds since the epoch
+    - end_time - The time the session was resumed in seconds since the epoch, None while it is paused
+- Budgets
+    - name - The name of the activity
+    - daily - The time that can be spent on the activity each day in seconds if it has a daily budget
+    - weekly - The time that can be spent on the activity each week in seconds if it has a weekly budget
+    */
+pub struct Activities(Connection);
+
+impl Activities {
+    /**
+    Create a new instance of the Activities struct.
+
+    # Arguments
+    conn - A Connection to the database
+     */
+    pub fn new(conn: Connection) -> Self {
+        Self(conn)
+    }
+
+    /**
+    Open the database at the given path and bring it up to date.
+
+    The desktop application and the command line client can have the database open at the same time, a write waits
+    for the other one to finish instead of failing and the reads don't block the writes.
+
+    # Arguments
+    path - The path of the database file, it is created if it doesn't exist
+     */
+    pub fn open(path: &Path) -> Result<Self, ActivitiesError> {
+        let conn = Connection::open(path)?;
+        conn.busy_timeout(Duration::from_secs(5))?;
+        conn.pragma_update_and_check(None, "journal_mode", "WAL", |row| row.get::<_, String>(0))?;
+
+        let activities = Self::new(conn);
+        activities.init_db()?;
+        Ok(activities)
+    }
+
+    /**
+    Initialize the database by running the pending schema migrations.
+
+    # Errors
+    Fails if the database was created by a newer version of the application
+     */
+    pub fn init_db(&self) -> Result<(), ActivitiesError> {
+        migrations::migrate(&self.0)?;
+        // Add the first clear at UNIX EPOCH to make sure all the activities are counted
+        // Done on every start since a hard clear removes it
+        self.0.execute("INSERT INTO clears(id, time) VALUES (1, 0) ON CONFLICT DO NOTHING;", [])?;
+        Ok(())
+    }
+
+    /**
+    Start an activity with the given name, the running one is stopped in the same transaction.
+
+    # Arguments
+    name - The name of the activity
+    tags - The tags of the new session, with or without the `#`
+    offset - The offset in seconds from the current time
+
+    # Returns
+    The id of the new session
+
+    # Errors
+    Fails if the name is empty or if a tag contains a space, nothing is started or stopped then
+     */
+    pub fn start_activity(&self, name: &str, tags: &[String], offset: i64) -> Result<i64, ActivitiesError> {
+        if name.trim().is_empty() {
+            return Err(ActivitiesError::InvalidSession("The activity name can't be empty".to_string()));
+        }
+        let tags = tags::normalize_tags(tags)?;
+
+        let now = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let mut start_time = now as i64 + offset;
+        
+        if let Some((_, current_start_time)) = self.currrent_activity()? {
+            if start_time < current_start_time as i64 {
+                start_time = current_start_time as i64;
+            }
+        }
+
+        let tx = self.0.unchecked_transaction()?;
+        self.stop_activity(offset)?;
+
+        tx.execute(
+            "INSERT INTO activities (name, start_time) VALUES (?, ?)",
+            params![name, start_time],
+        )?;
+        let id = tx.last_insert_rowid();
+        tags::insert_session_tags(&tx, id, &tags)?;
+        tx.commit()?;
+
+        Ok(id)
+    }
+
+    /**
+    Stop the current activity with the given offset.
+
+    # Arguments
+    offset - The offset in seconds from the current time
+
+    # Returns
+    A Result with the success or error of the operation
+     */
+    pub fn stop_activity(&self, offset: i64) -> Result<(), rusqlite::Error> {
+        let now = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let mut end_time = now as i64 + offset;
+        
+        if let Some((_, start_time)) = self.currrent_activity()? {
+            if end_time < start_time as i64 {
+                end_time = start_time as i64;
+            }
+        }
+
+        // A paused activity is resumed when it stops
+        self.0.execute(
+            "UPDATE breaks SET start_time = MIN(start_time, ?1), end_time = ?1 WHERE end_time IS NULL",
+            params![end_time],
+        )?;
+        self.0.execute(
+            "UPDATE activities SET end_time = ? WHERE end_time IS NULL",
+            params![end_time],
+        )?;
+        Ok(())
+    }
+
+    /**
+    Return the name of the current activity if there is one.
+
+    # Returns
+    The name of the current activity if there is one
+     */
+    pub fn currrent_activity(&self) -> Result<Option<(String, u64)>, rusqlite::Error> {
+        let mut stmt = self.0.prepare(
+            "SELECT name, start_time FROM activities WHERE end_time IS NULL ORDER BY start_time DESC LIMIT 1",
+        )?;
+        let mut rows = stmt.query([])?;
+        if let Some(row) = rows.next()? {
+            let name: String = row.get(0)?;
+            let start_time: u64 = row.get(1)?;
+            Ok(Some((name, start_time)))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Get the running session if there is one
+    pub fn current_session(&self) -> Result<Option<Session>, rusqlite::Error> {
+        self.0
+            .query_row(
+                &format!(
+                    "SELECT {} FROM {} WHERE activities.end_time IS NULL ORDER BY activities.start_time DESC LIMIT 1",
+                    Session::COLUMNS,
+                    Session::TABLES
+                ),
+                [],
+                Session::from_row,
+            )
+            .optional()
+    }
+
+    /// Get the time the last session ended in seconds since the epoch, None if no session ended yet
+    pub fn last_end_time(&self) -> Result<Option<u64>, rusqlite::Error> {
+        self.0.query_row("SELECT MAX(end_time) FROM activities", [], |row| row.get(0))
+    }
+
+    /**
+    List all the activities names. Even if they were cleared.
+    
+    # Returns
+    A list of all the activities names, the most recently started first
+     */
+    pub fn list_activities(&self) -> Result<Vec<String>, rusqlite::Error> {
+        let mut stmt = self.0.prepare("SELECT name FROM activities GROUP BY name ORDER BY MAX(start_time) DESC")?;
+
+        let rows = stmt.query_map([], |row| row.get(0))?;
+        let mut activities = Vec::new();
+
+        for activity in rows {
+            activities.push(activity?);
+        }
+
+        Ok(activities)
+    }
+
+    /**
+    Return the total time of each activity, project, client or tag since the last clear.
+
+    # Arguments
+    grouping - What the time is summed by
+    tag - Only count the sessions with this tag if set
+    rounding - How the sessions or the totals are rounded
+
+    # Returns
+    A HashMap with the name of the group as the key and the total time in seconds as the value
+     */
+    pub fn activities_times(&self, grouping: Grouping, tag: Option<&str>, rounding: Rounding) -> Result<HashMap<String, u64>, rusqlite::Error> {
+        let mut activities = HashMap::new();
+
+        let now = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        for session in self.sessions_since_clear(tag)? {
+            let duration = rounding.round_session(session.duration(now));
+            for key in grouping.keys(&session) {
+                *activities.entry(key).or_insert(0) += duration;
+            }
+        }
+
+        for time in activities.values_mut() {
+            *time = rounding.round_total(*time);
+        }
+
+        Ok(activities)
+        
+    }
+
+    /**
+    Get the sessions started since the last clear.
+
+    # Arguments
+    tag - Only keep the sessions with this tag if set
+     */
+    fn sessions_since_clear(&self, tag: Option<&str>) -> Result<Vec<Session>, rusqlite::Error> {
+        let mut stmt = self.0.prepare(&format!(
+            "SELECT {} FROM {} WHERE activities.start_time >= (SELECT time FROM clears ORDER BY time DESC LIMIT 1)",
+            Session::COLUMNS,
+            Session::TABLES
+        ))?;
+        let rows = stmt.query_map([], Session::from_row)?;
+
+        let mut sessions = Vec::new();
+        for session in rows {
+            let session = session?;
+            if tag.is_some_and(|tag| !session.tags.iter().any(|t| t == tag)) {
+                continue;
+            }
+            sessions.push(session);
+        }
+
+        Ok(sessions)
+    }
+
+    /**
+    Get the sessions overlapping a time range clipped to it, or the sessions since the last clear without a range.
+
+    # Arguments
+    range - The start (included) and the end (excluded) of the range in seconds since the epoch
+    tag - Only keep the sessions with this tag if set
+
+    # Returns
+    A list of sessions ordered by start time
+     */
+    pub fn sessions_in(&self, range: Option<(u64, u64)>, tag: Option<&str>) -> Result<Vec<Session>, ActivitiesError> {
+        let mut sessions = match range {
+            Some((from, to)) => {
+                let mut sessions = self.sessions_between(from, to)?;
+                sessions.retain(|session| tag.is_none_or(|tag| session.tags.iter().any(|t| t == tag)));
+                sessions
+            }
+            None => self.sessions_since_clear(tag)?,
+        };
+        sessions.sort_by_key(|session| session.start_time);
+
+        Ok(sessions)
+    }
+
+    /**
+    Mark the current time as the last time the database was cleared. All the activities before this time are ignored when counting time 
+    but they are still in the database and they contribute to the list of activity.
+
+    # Returns
+    A Result with the success or error of the operation
+     */
+    pub fn clear_activities(&self) -> Result<(), rusqlite::Error> {
+        // Before clearing the activities, we need to stop the current activity
+        self.stop_activity(0)?;
+        let now = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        self.0.execute("INSERT INTO clears (time) VALUES (?)", params![now])?;
+        Ok(())
+    }
+
+    /**
+     * Remove all activities and clears from the database
+     */
+    pub fn hard_clear_activities(&self) -> Result<(), rusqlite::Error> {
+        self.0.execute("DELETE FROM session_tags", [])?;
+        self.0.execute("DELETE FROM breaks", [])?;
+        self.0.execute("DELETE FROM activities", [])?;
+        self.0.execute("DELETE FROM clears", [])?;
+        Ok(())
+    }
+
+    /// Get the logical day, week and month of the current time, honoring the day start hour setting
+    pub fn calendar(&self) -> Result<calendar::Calendar, rusqlite::Error> {
+        let now = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        Ok(calendar::calendar_at(now, self.day_start_hour()?))
+    }
+
+    /// Get the sessions started during the current logical day
+    pub fn todays_activities(&self) -> Result<Vec<Session>, rusqlite::Error>{
+        let today = self.calendar()?.day;
+        let mut stmt = self.0.prepare(&format!(
+            "SELECT {} FROM {} WHERE activities.start_time >= ? AND activities.start_time < ?",
+            Session::COLUMNS,
+            Session::TABLES
+        ))?;
+        let sessions = stmt.query_map(params![today.start, today.end], Session::from_row)?;
+
+        let mut activities = Vec::new();
+        for session in sessions {
+            activities.push(session?);
+        }
+
+        Ok(activities)
+    }
+
+    /**
+    Get every session overlapping a time range, clipped to the range.
+
+    # Arguments
+    from - The start of the range in seconds since the epoch (included)
+    to - The end of the range in seconds since the epoch (excluded)
+
+    # Returns
+    A list of sessions ordered by start time
+    The end time is None if the session is still running and the range ends after now
+     */
+    pub fn sessions_between(&self, from: u64, to: u64) -> Result<Vec<Session>, ActivitiesError> {
+        if to < from {
+            return Err(ActivitiesError::InvalidTimeRange { start: from, end: to });
+        }
+
+        let now = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let mut stmt = self.0.prepare(&format!(
+            "SELECT {} FROM {} 
+            WHERE activities.start_time < ? AND (activities.end_time IS NULL OR activities.end_time > ?) 
+            ORDER BY activities.start_time",
+            Session::COLUMNS,
+            Session::TABLES
+        ))?;
+        let rows = stmt.query_map(params![to, from], Session::from_row)?;
+
+        let mut sessions = Vec::new();
+        for session in rows {
+            let mut session = session?;
+            session.start_time = session.start_time.max(from);
+            session.end_time = match session.end_time {
+                Some(end_time) => Some(end_time.min(to)),
+                // A running session is cut if the range ends before now
+                None if to <= now => Some(to),
+                None => None,
+            };
+            sessions.push(session);
+        }
+
+        Ok(sessions)
+    }
+
+    /**
+    Add a closed session, without touching the running one.
+
+    # Arguments
+    name - The name of the activity
+    tags - The tags of the session without the `#`, added in the same transaction
+    start_time - The start of the session in seconds since the epoch
+    end_time - The end of the session in seconds since the epoch
+
+    # Returns
+    The id of the new session
+
+    # Errors
+    Fails if the name is empty, if a tag contains a space or if the end is before the start
+     */
+    pub fn add_session(&self, name: &str, tags: &[String], start_time: u64, end_time: u64) -> Result<i64, ActivitiesError> {
+        if name.trim().is_empty() {
+            return Err(ActivitiesError::InvalidSession("The activity name can't be empty".to_string()));
+        }
+        let tags = tags::normalize_tags(tags)?;
+
+        if end_time < start_time {
+            return Err(ActivitiesError::InvalidTimeRange { start: start_time, end: end_time });
+        }
+
+        let tx = self.0.unchecked_transaction()?;
+        tx.execute(
+            "INSERT INTO activities (name, start_time, end_time) VALUES (?, ?, ?)",
+            params![name.trim(), start_time, end_time],
+        )?;
+        let id = tx.last_insert_rowid();
+        tags::insert_session_tags(&tx, id, &tags)?;
+        tx.commit()?;
+
+        Ok(id)
+    }
+
+    /**
+    Get a session by its id.
+
+    # Arguments
+    id - The id of the session
+
+    # Returns
+    The session if it exists
+     */
+    pub fn session(&self, id: i64) -> Result<Option<Session>, rusqlite::Error> {
+        self.0
+            .query_row(
+                &format!("SELECT {} FROM {} WHERE activities.id = ?", Session::COLUMNS, Session::TABLES),
+                params![id],
+                Session::from_row,
+            )
+            .optional()
+    }
+
+    /**
+    Replace the name, start time and end time of a session.
+
+    # Arguments
+    id - The id of the session
+    name - The new name of the activity
+    start_time - The new start time in seconds since the epoch
+    end_time - The new end time in seconds since the epoch, None to keep the session running
+        Only the running session can be kept running
+
+    # Errors
+    Fails if the session doesn't exist, if the name is empty or if the end is before the start
+     */
+    pub fn update_session(&self, id: i64, name: &str, start_time: u64, end_time: Option<u64>) -> Result<(), ActivitiesError> {
+        let session = self.session(id)?.ok_or(ActivitiesError::SessionNotFound(id))?;
+
+        if name.trim().is_empty() {
+            return Err(ActivitiesError::InvalidSession("The activity name can't be empty".to_string()));
+        }
+
+        match end_time {
+            Some(end_time) if end_time < start_time => {
+                return Err(ActivitiesError::InvalidTimeRange { start: start_time, end: end_time });
+            }
+            None if session.end_time.is_some() => {
+                return Err(ActivitiesError::InvalidSession("Only the running session can be left without an end".to_string()));
+            }
+            _ => {}
+        }
+
+        let tx = self.0.unchecked_transaction()?;
+        tx.execute(
+            "UPDATE activities SET name = ?, start_time = ?, end_time = ? WHERE id = ?",
+            params![name.trim(), start_time, end_time, id],
+        )?;
+        // Ending the running session ends its break too
+        if let Some(end_time) = end_time {
+            breaks::end_open_break(&tx, id, end_time, None)?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /**
+    Delete a session.
+
+    # Arguments
+    id - The id of the session
+
+    # Errors
+    Fails if the session doesn't exist
+     */
+    pub fn delete_session(&self, id: i64) -> Result<(), ActivitiesError> {
+        // The foreign keys aren't enforced so the tags and breaks are removed by hand
+        self.0.execute("DELETE FROM session_tags WHERE session_id = ?", params![id])?;
+        self.0.execute("DELETE FROM breaks WHERE session_id = ?", params![id])?;
+        let deleted = self.0.execute("DELETE FROM activities WHERE id = ?", params![id])?;
+
+        if deleted == 0 {
+            return Err(ActivitiesError::SessionNotFound(id));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Open an empty database in memory
+    pub(crate) fn memory() -> Activities {
+        let activities = Activities::new(Connection::open_in_memory().unwrap());
+        activities.init_db().unwrap();
+        activities
+    }
+
+    pub(crate) fn now() -> u64 {
+        SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap().as_secs()
+    }
+
+    #[test]
+    fn ending_the_running_session_ends_its_break() {
+        let activities = memory();
+        let id = activities.start_activity("Foo", &[], -3600).unwrap();
+        activities.pause_activity(-1800).unwrap();
+        let now = now();
+        let end = now - 600;
+
+        activities.update_session(id, "Foo", now - 3600, Some(end)).unwrap();
+
+        let session = activities.session(id).unwrap().unwrap();
+        assert_eq!(session.breaks.len(), 1);
+        assert_eq!(session.breaks[0].end_time, Some(end));
+        assert!(activities.current_session().unwrap().is_none());
+    }
+
+    #[test]
+    fn ending_the_running_session_before_its_break_removes_it() {
+        let activities = memory();
+        let id = activities.start_activity("Foo", &[], -3600).unwrap();
+        activities.pause_activity(-600).unwrap();
+
+        let now = now();
+        activities.update_session(id, "Foo", now - 3600, Some(now - 1800)).unwrap();
+
+        assert!(activities.session(id).unwrap().unwrap().breaks.is_empty());
+    }
+
+    #[test]
+    fn adding_a_session_tags_it() {
+        let activities = memory();
+
+        let id = activities.add_session("Foo", &["review".to_string()], 3600, 7200).unwrap();
+
+        assert_eq!(activities.session(id).unwrap().unwrap().tags, vec!["review".to_string()]);
+    }
+
+    #[test]
+    fn adding_a_session_with_a_wrong_tag_adds_nothing() {
+        let activities = memory();
+
+        let res = activities.add_session("Foo", &["code review".to_string()], 3600, 7200);
+
+        assert!(matches!(res, Err(ActivitiesError::InvalidSession(_))));
+        assert!(activities.sessions_between(0, 10000).unwrap().is_empty());
+    }
+
+    #[test]
+    fn overlapping_breaks_are_removed_once() {
+        let session = Session {
+            id: 1,
+            name: "Foo".to_string(),
+            start_time: 1000,
+            end_time: Some(2000),
+            breaks: vec![
+                Break { start_time: 1100, end_time: Some(1500) },
+                Break { start_time: 1200, end_time: Some(1400) },
+                Break { start_time: 1450, end_time: None },
+                Break { start_time: 1800, end_time: Some(1900) },
+            ],
+            project: "Unassigned".to_string(),
+            client: None,
+            tags: Vec::new(),
+            notes: None,
+            billable: true,
+            hourly_rate: None,
+        };
+
+        // 1100 to 2000 is paused, the last break is inside the open one
+        assert_eq!(session.duration(3000), 100);
+        // The open break runs until now, 1100 to 1600 and 1800 to 1900 are paused
+        assert_eq!(session.duration(1600), 400);
+    }
+}
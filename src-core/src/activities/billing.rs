@@ -0,0 +1,190 @@
+use rusqlite::{params, OptionalExtension};
+use serde::Serialize;
+
+use super::{Activities, ActivitiesError};
+
+/// A line of an invoice, the billable time spent on an activity at a given rate
+#[derive(Debug, Clone, Serialize)]
+pub struct InvoiceLine {
+    /// The name of the client of the project if it has one
+    pub client: Option<String>,
+    /// The name of the project
+    pub project: String,
+    /// The name of the activity
+    pub activity: String,
+    /// The billable time in seconds, without the breaks
+    pub seconds: u64,
+    /// The hourly rate in cents, None if neither the project nor the client has a rate
+    pub hourly_rate: Option<i64>,
+    /// The amount in cents, 0 when there is no rate
+    pub amount: i64,
+}
+
+/**
+The billable sessions of a time range turned into invoice lines.
+
+# Fields
+from - The start of the range in seconds since the epoch (included)
+to - The end of the range in seconds since the epoch (excluded)
+client - The client billed, None if the invoice covers every client
+currency - The currency symbol of the amounts
+lines - The lines ordered by client, project and activity
+seconds - The total billable time in seconds
+amount - The total amount in cents
+rounding - The description of the rounding applied to the times
+ */
+#[derive(Debug, Clone, Serialize)]
+pub struct Invoice {
+    pub from: u64,
+    pub to: u64,
+    pub client: Option<String>,
+    pub currency: String,
+    pub lines: Vec<InvoiceLine>,
+    pub seconds: u64,
+    pub amount: i64,
+    pub rounding: String,
+}
+
+/// Get the amount in cents for a time in seconds at an hourly rate in cents, rounded to the nearest cent
+fn amount(seconds: u64, hourly_rate: i64) -> i64 {
+    (seconds as i64 * hourly_rate + 1800).div_euclid(3600)
+}
+
+/// Check that a rate is not negative
+fn check_rate(hourly_rate: Option<i64>) -> Result<(), ActivitiesError> {
+    match hourly_rate {
+        Some(rate) if rate < 0 => Err(ActivitiesError::InvalidProject(format!("The hourly rate can't be negative, got {}", rate))),
+        _ => Ok(()),
+    }
+}
+
+impl Activities {
+    /**
+    Set the hourly rate of a client, used by its projects without a rate.
+
+    # Arguments
+    id - The id of the client
+    hourly_rate - The rate in cents per hour, None to remove it
+     */
+    pub fn set_client_rate(&self, id: i64, hourly_rate: Option<i64>) -> Result<(), ActivitiesError> {
+        check_rate(hourly_rate)?;
+
+        let updated = self.0.execute("UPDATE clients SET hourly_rate = ? WHERE id = ?", params![hourly_rate, id])?;
+        if updated == 0 {
+            return Err(ActivitiesError::InvalidProject(format!("No client with the id {}", id)));
+        }
+        Ok(())
+    }
+
+    /**
+    Set the hourly rate of a project, it overrides the rate of the client.
+
+    # Arguments
+    id - The id of the project
+    hourly_rate - The rate in cents per hour, None to use the rate of the client
+     */
+    pub fn set_project_rate(&self, id: i64, hourly_rate: Option<i64>) -> Result<(), ActivitiesError> {
+        check_rate(hourly_rate)?;
+
+        let updated = self.0.execute("UPDATE projects SET hourly_rate = ? WHERE id = ?", params![hourly_rate, id])?;
+        if updated == 0 {
+            return Err(ActivitiesError::InvalidProject(format!("No project with the id {}", id)));
+        }
+        Ok(())
+    }
+
+    /**
+    Mark a session as billable or not.
+
+    # Arguments
+    id - The id of the session
+    billable - Whether the session is billed to the client
+     */
+    pub fn set_session_billable(&self, id: i64, billable: bool) -> Result<(), ActivitiesError> {
+        let updated = self.0.execute("UPDATE activities SET billable = ? WHERE id = ?", params![billable, id])?;
+        if updated == 0 {
+            return Err(ActivitiesError::SessionNotFound(id));
+        }
+        Ok(())
+    }
+
+    /**
+    Build the invoice of the billable sessions in a time range.
+
+    The sessions are cut to the range and the running session is not billed. The times are rounded with the rounding
+    setting, per session or per line.
+
+    # Arguments
+    from - The start of the range in seconds since the epoch (included)
+    to - The end of the range in seconds since the epoch (excluded)
+    client_id - Only bill the projects of this client if set
+
+    # Errors
+    Fails if the range ends before it starts or if the client doesn't exist
+     */
+    pub fn invoice(&self, from: u64, to: u64, client_id: Option<i64>) -> Result<Invoice, ActivitiesError> {
+        let client = match client_id {
+            Some(client_id) => Some(
+                self.0
+                    .query_row("SELECT name FROM clients WHERE id = ?", params![client_id], |row| row.get::<_, String>(0))
+                    .optional()?
+                    .ok_or_else(|| ActivitiesError::InvalidProject(format!("No client with the id {}", client_id)))?,
+            ),
+            None => None,
+        };
+
+        // The sessions are clipped to the range, the running ones are told apart before that
+        let running = self
+            .0
+            .prepare("SELECT id FROM activities WHERE end_time IS NULL")?
+            .query_map([], |row| row.get::<_, i64>(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let rounding = self.rounding()?;
+        let mut lines: Vec<InvoiceLine> = Vec::new();
+        for session in self.sessions_between(from, to)? {
+            if running.contains(&session.id) {
+                continue;
+            }
+            let Some(end_time) = session.end_time else { continue; };
+            let seconds = rounding.round_session(session.duration(end_time));
+            if !session.billable || (client.is_some() && session.client != client) {
+                continue;
+            }
+
+            let line = lines.iter_mut().find(|line| {
+                line.client == session.client && line.project == session.project && line.activity == session.name
+            });
+            match line {
+                Some(line) => line.seconds += seconds,
+                None => lines.push(InvoiceLine {
+                    client: session.client,
+                    project: session.project,
+                    activity: session.name,
+                    seconds,
+                    hourly_rate: session.hourly_rate,
+                    amount: 0,
+                }),
+            }
+        }
+
+        // The amount is computed on the total time of the line so the rounding happens once per line
+        for line in lines.iter_mut() {
+            line.seconds = rounding.round_total(line.seconds);
+            line.amount = line.hourly_rate.map(|rate| amount(line.seconds, rate)).unwrap_or(0);
+        }
+        lines.retain(|line| line.seconds > 0);
+        lines.sort_by(|a, b| (&a.client, &a.project, &a.activity).cmp(&(&b.client, &b.project, &b.activity)));
+
+        Ok(Invoice {
+            from,
+            to,
+            client,
+            currency: self.currency()?,
+            seconds: lines.iter().map(|line| line.seconds).sum(),
+            amount: lines.iter().map(|line| line.amount).sum(),
+            lines,
+            rounding: rounding.describe(),
+        })
+    }
+}
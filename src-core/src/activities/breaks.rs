@@ -0,0 +1,161 @@
+use std::time::SystemTime;
+
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+use super::{Activities, ActivitiesError};
+
+/// A break taken during a session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Break {
+    /// The time the session was paused in seconds since the epoch
+    pub start_time: u64,
+    /// The time the session was resumed in seconds since the epoch, None while it is paused
+    pub end_time: Option<u64>,
+}
+
+impl Break {
+    /// Parse the breaks concatenated as `start-end` separated by spaces, the end is empty while paused
+    pub(super) fn parse_list(breaks: &str) -> Vec<Break> {
+        let mut breaks: Vec<Break> = breaks
+            .split(' ')
+            .filter_map(|pause| {
+                let (start, end) = pause.split_once('-')?;
+                Some(Break {
+                    start_time: start.parse().ok()?,
+                    end_time: end.parse().ok(),
+                })
+            })
+            .collect();
+        breaks.sort_by_key(|pause| pause.start_time);
+        breaks
+    }
+}
+
+/**
+Close the open break of a session given an end, a break starting after the end is removed.
+
+# Arguments
+conn - The connection or the transaction ending the session
+session_id - The session ending
+end - The new end of the session in seconds since the epoch
+resumed - The session continuing the one ending and the time it starts if there is one, the break goes on in it
+ */
+pub(super) fn end_open_break(conn: &Connection, session_id: i64, end: u64, resumed: Option<(i64, u64)>) -> Result<(), rusqlite::Error> {
+    let paused = conn
+        .query_row(
+            "SELECT 1 FROM breaks WHERE session_id = ? AND end_time IS NULL",
+            params![session_id],
+            |_| Ok(()),
+        )
+        .optional()?
+        .is_some();
+
+    conn.execute(
+        "DELETE FROM breaks WHERE session_id = ? AND end_time IS NULL AND start_time >= ?",
+        params![session_id, end],
+    )?;
+    conn.execute(
+        "UPDATE breaks SET end_time = ? WHERE session_id = ? AND end_time IS NULL",
+        params![end, session_id],
+    )?;
+    if let (true, Some((resumed, start))) = (paused, resumed) {
+        conn.execute("INSERT INTO breaks (session_id, start_time) VALUES (?, ?)", params![resumed, start])?;
+    }
+    Ok(())
+}
+
+impl Activities {
+    /**
+    Pause the running activity with the given offset. The time until it is resumed doesn't count toward the activity.
+
+    # Arguments
+    offset - The offset in seconds from the current time
+
+    # Errors
+    Fails if no activity is running or if it is already paused
+     */
+    pub fn pause_activity(&self, offset: i64) -> Result<(), ActivitiesError> {
+        let Some((id, start_time)) = self.running_session()? else {
+            return Err(ActivitiesError::InvalidSession("No activity is running".to_string()));
+        };
+        if self.is_paused()? {
+            return Err(ActivitiesError::InvalidSession("The activity is already paused".to_string()));
+        }
+
+        let now = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        // The break can't start before the session or the end of the previous break
+        let last_resume: Option<u64> = self.0.query_row(
+            "SELECT MAX(end_time) FROM breaks WHERE session_id = ?",
+            params![id],
+            |row| row.get(0),
+        )?;
+        let pause_time = (now as i64 + offset).max(start_time as i64).max(last_resume.unwrap_or(0) as i64);
+
+        self.0.execute(
+            "INSERT INTO breaks (session_id, start_time) VALUES (?, ?)",
+            params![id, pause_time],
+        )?;
+        Ok(())
+    }
+
+    /**
+    Resume the paused activity with the given offset.
+
+    # Arguments
+    offset - The offset in seconds from the current time
+
+    # Errors
+    Fails if the running activity isn't paused
+     */
+    pub fn resume_activity(&self, offset: i64) -> Result<(), ActivitiesError> {
+        let now = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+
+        let Some((id, _)) = self.running_session()? else {
+            return Err(ActivitiesError::InvalidSession("No activity is running".to_string()));
+        };
+
+        let updated = self.0.execute(
+            "UPDATE breaks SET end_time = MAX(start_time, ?) WHERE session_id = ? AND end_time IS NULL",
+            params![now as i64 + offset, id],
+        )?;
+        if updated == 0 {
+            return Err(ActivitiesError::InvalidSession("The activity isn't paused".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Whether the running activity is paused, the breaks left open on other sessions aside
+    pub fn is_paused(&self) -> Result<bool, rusqlite::Error> {
+        let Some((id, _)) = self.running_session()? else {
+            return Ok(false);
+        };
+
+        Ok(self
+            .0
+            .query_row(
+                "SELECT 1 FROM breaks WHERE session_id = ? AND end_time IS NULL",
+                params![id],
+                |_| Ok(()),
+            )
+            .optional()?
+            .is_some())
+    }
+
+    /// Get the id and start time of the running session if there is one
+    fn running_session(&self) -> Result<Option<(i64, u64)>, rusqlite::Error> {
+        self.0
+            .query_row(
+                "SELECT id, start_time FROM activities WHERE end_time IS NULL ORDER BY start_time DESC LIMIT 1",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()
+    }
+}
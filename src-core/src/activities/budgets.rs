@@ -0,0 +1,117 @@
+use std::collections::HashMap;
+
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+
+use super::{Activities, ActivitiesError};
+use crate::calendar;
+
+/// The time that can be spent on an activity each day and each week
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Budget {
+    /// The name of the activity
+    pub name: String,
+    /// The daily budget in seconds if there is one
+    pub daily: Option<u64>,
+    /// The weekly budget in seconds if there is one
+    pub weekly: Option<u64>,
+}
+
+/// The time spent on an activity with a budget during the current day and week
+#[derive(Debug, Clone, Serialize)]
+pub struct BudgetUsage {
+    #[serde(flatten)]
+    pub budget: Budget,
+    /// The time spent on the activity during the logical day in seconds
+    pub today: u64,
+    /// The time spent on the activity during the logical week in seconds
+    pub this_week: u64,
+}
+
+impl Activities {
+    /// List the budgets ordered by activity name
+    pub fn list_budgets(&self) -> Result<Vec<Budget>, rusqlite::Error> {
+        let mut stmt = self.0.prepare("SELECT name, daily, weekly FROM budgets ORDER BY name")?;
+        let rows = stmt.query_map([], |row| {
+            Ok(Budget {
+                name: row.get(0)?,
+                daily: row.get(1)?,
+                weekly: row.get(2)?,
+            })
+        })?;
+
+        let mut budgets = Vec::new();
+        for budget in rows {
+            budgets.push(budget?);
+        }
+
+        Ok(budgets)
+    }
+
+    /**
+    Set the budgets of an activity, replacing the previous ones.
+
+    # Arguments
+    budget - The activity and its budgets, the activity has no budget anymore when both are None
+
+    # Errors
+    Fails if the activity name is empty or if a budget is 0
+     */
+    pub fn set_budget(&self, budget: &Budget) -> Result<(), ActivitiesError> {
+        if budget.name.trim().is_empty() {
+            return Err(ActivitiesError::InvalidSession("The activity name can't be empty".to_string()));
+        }
+        if budget.daily == Some(0) || budget.weekly == Some(0) {
+            return Err(ActivitiesError::InvalidSetting("A budget can't be empty".to_string()));
+        }
+
+        if budget.daily.is_none() && budget.weekly.is_none() {
+            self.0.execute("DELETE FROM budgets WHERE name = ?", params![budget.name])?;
+            return Ok(());
+        }
+
+        self.0.execute(
+            "INSERT INTO budgets (name, daily, weekly) VALUES (?, ?, ?) 
+            ON CONFLICT(name) DO UPDATE SET daily = excluded.daily, weekly = excluded.weekly",
+            params![budget.name, budget.daily, budget.weekly],
+        )?;
+        Ok(())
+    }
+
+    /// Sum the time spent on each activity between two times, without the breaks
+    fn times_between(&self, from: u64, to: u64, now: u64) -> Result<HashMap<String, u64>, ActivitiesError> {
+        let mut times = HashMap::new();
+        for session in self.sessions_between(from, to)? {
+            *times.entry(session.name.clone()).or_insert(0) += session.duration(now);
+        }
+        Ok(times)
+    }
+
+    /**
+    Get the time spent on each activity with a budget during the logical day and week.
+
+    # Arguments
+    now - The current time in seconds since the epoch
+     */
+    pub fn budget_usage(&self, now: u64) -> Result<Vec<BudgetUsage>, ActivitiesError> {
+        let budgets = self.list_budgets()?;
+        if budgets.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let day_start_hour = self.day_start_hour()?;
+        let day = calendar::day_at(now, day_start_hour);
+        let week = calendar::week_at(now, day_start_hour);
+        let today = self.times_between(day.start, day.end, now)?;
+        let this_week = self.times_between(week.start, week.end, now)?;
+
+        Ok(budgets
+            .into_iter()
+            .map(|budget| BudgetUsage {
+                today: today.get(&budget.name).copied().unwrap_or(0),
+                this_week: this_week.get(&budget.name).copied().unwrap_or(0),
+                budget,
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,562 @@
+use std::{collections::{BTreeMap, HashMap}, time::SystemTime};
+
+use rusqlite::{
+    params,
+    types::{FromSql, Value, ValueRef},
+    OptionalExtension,
+};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    rounding::{ROUNDING_INCREMENT, ROUNDING_MODE, ROUNDING_SCOPE, ROUNDING_STATISTICS},
+    settings::{
+        API_ENABLED, API_PORT, API_TOKEN, ASK_NOTES_ON_STOP, CLOSE_TO_TRAY, CURRENCY, DAY_START_HOUR, IDLE_MINUTES,
+        POMODORO_AUTO_BREAKS, POMODORO_BREAK_MINUTES, REMINDER_MINUTES, WORK_END_HOUR, WORK_START_HOUR,
+    },
+    Activities, ActivitiesError, ApiSettings, Break, Budget, Rounding, WorkingHours,
+};
+
+/// The version of the dump format, increased when a change can't be read by the previous versions
+pub const DUMP_VERSION: u32 = 1;
+
+/// A session as stored in the database
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DumpSession {
+    pub id: i64,
+    pub name: String,
+    pub start_time: u64,
+    pub end_time: Option<u64>,
+    #[serde(default)]
+    pub notes: Option<String>,
+    #[serde(default = "billable_default")]
+    pub billable: bool,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub breaks: Vec<Break>,
+}
+
+fn billable_default() -> bool {
+    true
+}
+
+/// A client as stored in the database
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DumpClient {
+    pub id: i64,
+    pub name: String,
+    #[serde(default)]
+    pub hourly_rate: Option<i64>,
+}
+
+/// A project as stored in the database
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DumpProject {
+    pub id: i64,
+    pub name: String,
+    pub client_id: Option<i64>,
+    #[serde(default)]
+    pub hourly_rate: Option<i64>,
+}
+
+/// The project of an activity
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DumpActivityProject {
+    pub name: String,
+    pub project_id: i64,
+}
+
+/**
+Every table of the database in a portable format.
+
+The tables missing from a dump made by a previous version are read as empty, so older dumps can always be restored.
+
+# Fields
+version - The version of the dump format, see `DUMP_VERSION`
+exported_at - The time the dump was made in seconds since the epoch
+sessions - The sessions with their tags and breaks ordered by start time
+clears - The times of the clears in seconds since the epoch
+clients, projects, activity_projects - The client → project → activity hierarchy
+budgets - The daily and weekly budgets of the activities
+settings - The settings by key, without the token of the API
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Dump {
+    pub version: u32,
+    pub exported_at: u64,
+    #[serde(default)]
+    pub sessions: Vec<DumpSession>,
+    #[serde(default)]
+    pub clears: Vec<u64>,
+    #[serde(default)]
+    pub clients: Vec<DumpClient>,
+    #[serde(default)]
+    pub projects: Vec<DumpProject>,
+    #[serde(default)]
+    pub activity_projects: Vec<DumpActivityProject>,
+    #[serde(default)]
+    pub budgets: Vec<Budget>,
+    #[serde(default)]
+    pub settings: BTreeMap<String, serde_json::Value>,
+}
+
+/// How a dump is restored
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RestoreMode {
+    /// The dump is added to the current data, the current settings and projects of the activities are kept.
+    /// Only the clears older than the latest current one are added, so the current totals stay the same.
+    Merge,
+    /// The current data is deleted and replaced by the dump
+    Replace,
+}
+
+/// Convert a setting to JSON, the settings are only numbers and text
+fn to_json(value: Value) -> serde_json::Value {
+    match value {
+        Value::Integer(value) => value.into(),
+        Value::Real(value) => value.into(),
+        Value::Text(value) => value.into(),
+        Value::Null | Value::Blob(_) => serde_json::Value::Null,
+    }
+}
+
+/// Convert a setting from JSON, None if it can't be stored as a setting
+fn from_json(value: &serde_json::Value) -> Option<Value> {
+    match value {
+        serde_json::Value::Bool(value) => Some(Value::Integer(*value as i64)),
+        serde_json::Value::Number(value) => value.as_i64().map(Value::Integer).or(value.as_f64().map(Value::Real)),
+        serde_json::Value::String(value) => Some(Value::Text(value.clone())),
+        _ => None,
+    }
+}
+
+/// Read a setting of a dump the way the getters read it from the database, None if it is missing or of the wrong type
+fn dump_setting<T: FromSql>(settings: &BTreeMap<String, serde_json::Value>, key: &str) -> Option<T> {
+    let value = from_json(settings.get(key)?)?;
+    T::column_result(ValueRef::from(&value)).ok()
+}
+
+/// Skip a setting of a dump refused by its setter, the current value is kept
+fn skip_invalid(result: Result<(), ActivitiesError>) -> Result<(), ActivitiesError> {
+    match result {
+        Err(ActivitiesError::InvalidSetting(_)) => Ok(()),
+        result => result,
+    }
+}
+
+impl Activities {
+    /// Read every table of the database
+    pub fn dump(&self) -> Result<Dump, rusqlite::Error> {
+        let mut stmt = self.0.prepare(
+            "SELECT id, name, start_time, end_time, notes, billable, 
+            (SELECT GROUP_CONCAT(tags.name, ' ') FROM session_tags JOIN tags ON tags.id = session_tags.tag_id WHERE session_tags.session_id = activities.id), 
+            (SELECT GROUP_CONCAT(breaks.start_time || '-' || COALESCE(breaks.end_time, ''), ' ') FROM breaks WHERE breaks.session_id = activities.id) 
+            FROM activities ORDER BY start_time, id",
+        )?;
+        let sessions = stmt
+            .query_map([], |row| {
+                let tags: Option<String> = row.get(6)?;
+                let breaks: Option<String> = row.get(7)?;
+                let mut tags: Vec<String> = tags.unwrap_or_default().split(' ').filter(|tag| !tag.is_empty()).map(str::to_string).collect();
+                tags.sort();
+                Ok(DumpSession {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    start_time: row.get(2)?,
+                    end_time: row.get(3)?,
+                    notes: row.get(4)?,
+                    billable: row.get(5)?,
+                    tags,
+                    breaks: Break::parse_list(&breaks.unwrap_or_default()),
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut stmt = self.0.prepare("SELECT time FROM clears ORDER BY time")?;
+        let clears = stmt.query_map([], |row| row.get(0))?.collect::<Result<Vec<_>, _>>()?;
+
+        let mut stmt = self.0.prepare("SELECT id, name, hourly_rate FROM clients ORDER BY id")?;
+        let clients = stmt
+            .query_map([], |row| {
+                Ok(DumpClient {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    hourly_rate: row.get(2)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut stmt = self.0.prepare("SELECT id, name, client_id, hourly_rate FROM projects ORDER BY id")?;
+        let projects = stmt
+            .query_map([], |row| {
+                Ok(DumpProject {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    client_id: row.get(2)?,
+                    hourly_rate: row.get(3)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut stmt = self.0.prepare("SELECT name, project_id FROM activity_projects ORDER BY name")?;
+        let activity_projects = stmt
+            .query_map([], |row| {
+                Ok(DumpActivityProject {
+                    name: row.get(0)?,
+                    project_id: row.get(1)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut stmt = self.0.prepare("SELECT key, value FROM settings WHERE key != ?")?;
+        let settings = stmt
+            .query_map(params![API_TOKEN], |row| Ok((row.get::<_, String>(0)?, to_json(row.get(1)?))))?
+            .collect::<Result<BTreeMap<_, _>, _>>()?;
+
+        Ok(Dump {
+            version: DUMP_VERSION,
+            exported_at: SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .unwrap()
+                .as_secs(),
+            sessions,
+            clears,
+            clients,
+            projects,
+            activity_projects,
+            budgets: self.list_budgets()?,
+            settings,
+        })
+    }
+
+    /**
+    Restore a dump in one transaction.
+
+    When merging, the sessions already in the database (same activity and start time) are skipped, the clients and
+    projects are matched by name and the current settings, budgets and projects of the activities win over the dump.
+    A running session of the dump is skipped if an activity is already running.
+    When replacing, the ids of the dump are kept and the token of the API stays.
+
+    # Arguments
+    dump - The dump to restore
+    mode - Whether the dump is merged with the current data or replaces it
+
+    # Returns
+    The number of sessions restored
+
+    # Errors
+    Fails if the dump was made by a newer version of the application or when replacing while an activity is running,
+    nothing is restored then
+     */
+    pub fn restore(&self, dump: &Dump, mode: RestoreMode) -> Result<usize, ActivitiesError> {
+        if dump.version > DUMP_VERSION {
+            return Err(ActivitiesError::UnsupportedDump {
+                found: dump.version,
+                supported: DUMP_VERSION,
+            });
+        }
+
+        // Replacing would drop the running session along with the others
+        if mode == RestoreMode::Replace && self.current_session()?.is_some() {
+            return Err(ActivitiesError::InvalidSession(
+                "Stop the running activity before replacing the data".to_string(),
+            ));
+        }
+
+        let tx = self.0.unchecked_transaction()?;
+        if mode == RestoreMode::Replace {
+            tx.execute_batch(
+                "DELETE FROM session_tags; DELETE FROM breaks; DELETE FROM activities; DELETE FROM tags; DELETE FROM clears; 
+                DELETE FROM activity_projects; DELETE FROM projects; DELETE FROM clients; DELETE FROM budgets;",
+            )?;
+            // The token of the API isn't in the dumps, it is kept
+            tx.execute("DELETE FROM settings WHERE key != ?1", params![API_TOKEN])?;
+        }
+        // The ids of the dump are only kept when the tables are empty
+        let keep_id = |id: i64| (mode == RestoreMode::Replace).then_some(id);
+
+        let mut clients = HashMap::new();
+        for client in &dump.clients {
+            let existing: Option<i64> = tx
+                .query_row("SELECT id FROM clients WHERE name = ?", params![client.name], |row| row.get(0))
+                .optional()?;
+            let id = match existing {
+                Some(id) => {
+                    tx.execute("UPDATE clients SET hourly_rate = COALESCE(hourly_rate, ?) WHERE id = ?", params![client.hourly_rate, id])?;
+                    id
+                }
+                None => {
+                    tx.execute(
+                        "INSERT INTO clients (id, name, hourly_rate) VALUES (?, ?, ?)",
+                        params![keep_id(client.id), client.name, client.hourly_rate],
+                    )?;
+                    tx.last_insert_rowid()
+                }
+            };
+            clients.insert(client.id, id);
+        }
+
+        let mut projects = HashMap::new();
+        for project in &dump.projects {
+            let client_id = project.client_id.and_then(|id| clients.get(&id).copied());
+            let existing: Option<i64> = tx
+                .query_row(
+                    "SELECT id FROM projects WHERE COALESCE(client_id, 0) = COALESCE(?, 0) AND name = ?",
+                    params![client_id, project.name],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            let id = match existing {
+                Some(id) => {
+                    tx.execute("UPDATE projects SET hourly_rate = COALESCE(hourly_rate, ?) WHERE id = ?", params![project.hourly_rate, id])?;
+                    id
+                }
+                None => {
+                    tx.execute(
+                        "INSERT INTO projects (id, name, client_id, hourly_rate) VALUES (?, ?, ?, ?)",
+                        params![keep_id(project.id), project.name, client_id, project.hourly_rate],
+                    )?;
+                    tx.last_insert_rowid()
+                }
+            };
+            projects.insert(project.id, id);
+        }
+        // The activities without a project use the unassigned project
+        tx.execute("INSERT INTO projects (id, name) VALUES (1, 'Unassigned') ON CONFLICT DO NOTHING", [])?;
+
+        for activity in &dump.activity_projects {
+            let Some(project_id) = projects.get(&activity.project_id) else { continue; };
+            tx.execute(
+                "INSERT INTO activity_projects (name, project_id) VALUES (?, ?) ON CONFLICT DO NOTHING",
+                params![activity.name, project_id],
+            )?;
+        }
+
+        let mut restored = 0;
+        for session in &dump.sessions {
+            let exists: bool = tx.query_row(
+                "SELECT EXISTS (SELECT 1 FROM activities WHERE name = ? AND start_time = ?)",
+                params![session.name, session.start_time],
+                |row| row.get(0),
+            )?;
+            let running: bool = tx.query_row("SELECT EXISTS (SELECT 1 FROM activities WHERE end_time IS NULL)", [], |row| row.get(0))?;
+            if exists || (session.end_time.is_none() && running) {
+                continue;
+            }
+
+            tx.execute(
+                "INSERT INTO activities (id, name, start_time, end_time, notes, billable) VALUES (?, ?, ?, ?, ?, ?)",
+                params![keep_id(session.id), session.name, session.start_time, session.end_time, session.notes, session.billable],
+            )?;
+            let id = tx.last_insert_rowid();
+
+            for tag in &session.tags {
+                tx.execute("INSERT INTO tags (name) VALUES (?) ON CONFLICT DO NOTHING", params![tag])?;
+                tx.execute(
+                    "INSERT INTO session_tags (session_id, tag_id) SELECT ?, id FROM tags WHERE name = ? ON CONFLICT DO NOTHING",
+                    params![id, tag],
+                )?;
+            }
+            for pause in &session.breaks {
+                tx.execute(
+                    "INSERT INTO breaks (session_id, start_time, end_time) VALUES (?, ?, ?)",
+                    params![id, pause.start_time, pause.end_time],
+                )?;
+            }
+            restored += 1;
+        }
+
+        // A newer clear would reset the current totals
+        let latest_clear: Option<u64> = match mode {
+            RestoreMode::Merge => tx.query_row("SELECT MAX(time) FROM clears", [], |row| row.get(0))?,
+            RestoreMode::Replace => None,
+        };
+        for time in dump.clears.iter().filter(|time| latest_clear.is_none_or(|latest| **time < latest)) {
+            tx.execute(
+                "INSERT INTO clears (time) SELECT ?1 WHERE NOT EXISTS (SELECT 1 FROM clears WHERE time = ?1)",
+                params![time],
+            )?;
+        }
+        // Same as when the database is opened, all the sessions count if there was no clear
+        tx.execute("INSERT INTO clears (time) SELECT 0 WHERE NOT EXISTS (SELECT 1 FROM clears)", [])?;
+
+        // Same checks as when a budget is set
+        let valid_budget = |budget: &&Budget| {
+            !budget.name.trim().is_empty()
+                && budget.daily != Some(0)
+                && budget.weekly != Some(0)
+                && (budget.daily.is_some() || budget.weekly.is_some())
+        };
+        for budget in dump.budgets.iter().filter(valid_budget) {
+            tx.execute(
+                "INSERT INTO budgets (name, daily, weekly) VALUES (?, ?, ?) ON CONFLICT DO NOTHING",
+                params![budget.name, budget.daily, budget.weekly],
+            )?;
+        }
+
+        let mut settings = BTreeMap::new();
+        for (key, value) in &dump.settings {
+            if key == API_TOKEN || (mode == RestoreMode::Merge && self.setting::<Value>(key)?.is_some()) {
+                continue;
+            }
+            settings.insert(key.clone(), value.clone());
+        }
+        self.restore_settings(&settings)?;
+
+        tx.commit()?;
+        Ok(restored)
+    }
+
+    /// Set the settings of a dump through their setters, the unknown keys and the values the setters refuse are skipped
+    fn restore_settings(&self, settings: &BTreeMap<String, serde_json::Value>) -> Result<(), ActivitiesError> {
+        let has_any = |keys: &[&str]| keys.iter().any(|key| settings.contains_key(*key));
+
+        if let Some(hour) = dump_setting(settings, DAY_START_HOUR) {
+            skip_invalid(self.set_day_start_hour(hour))?;
+        }
+        if let Some(ask) = dump_setting(settings, ASK_NOTES_ON_STOP) {
+            self.set_ask_notes_on_stop(ask)?;
+        }
+        if let Some(currency) = dump_setting::<String>(settings, CURRENCY) {
+            skip_invalid(self.set_currency(&currency))?;
+        }
+        if let Some(minutes) = dump_setting(settings, POMODORO_BREAK_MINUTES) {
+            skip_invalid(self.set_pomodoro_break_minutes(minutes))?;
+        }
+        if let Some(auto_breaks) = dump_setting(settings, POMODORO_AUTO_BREAKS) {
+            self.set_pomodoro_auto_breaks(auto_breaks)?;
+        }
+        if let Some(minutes) = dump_setting(settings, IDLE_MINUTES) {
+            self.set_idle_minutes(minutes)?;
+        }
+        if let Some(minutes) = dump_setting(settings, REMINDER_MINUTES) {
+            self.set_reminder_minutes(minutes)?;
+        }
+        if let Some(close_to_tray) = dump_setting(settings, CLOSE_TO_TRAY) {
+            self.set_close_to_tray(close_to_tray)?;
+        }
+
+        // The settings set together are completed with the current values
+        if has_any(&[WORK_START_HOUR, WORK_END_HOUR]) {
+            let current = self.working_hours()?;
+            skip_invalid(self.set_working_hours(WorkingHours {
+                start: dump_setting(settings, WORK_START_HOUR).unwrap_or(current.start),
+                end: dump_setting(settings, WORK_END_HOUR).unwrap_or(current.end),
+            }))?;
+        }
+        if has_any(&[API_ENABLED, API_PORT]) {
+            let current = self.api_settings()?;
+            skip_invalid(self.set_api_settings(&ApiSettings {
+                enabled: dump_setting(settings, API_ENABLED).unwrap_or(current.enabled),
+                port: dump_setting(settings, API_PORT).unwrap_or(current.port),
+                token: current.token,
+            }))?;
+        }
+        if has_any(&[ROUNDING_MODE, ROUNDING_INCREMENT, ROUNDING_SCOPE, ROUNDING_STATISTICS]) {
+            let current = self.rounding()?;
+            skip_invalid(self.set_rounding(Rounding {
+                mode: dump_setting(settings, ROUNDING_MODE).unwrap_or(current.mode),
+                increment: dump_setting(settings, ROUNDING_INCREMENT).unwrap_or(current.increment),
+                scope: dump_setting(settings, ROUNDING_SCOPE).unwrap_or(current.scope),
+                statistics: dump_setting(settings, ROUNDING_STATISTICS).unwrap_or(current.statistics),
+            }))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rusqlite::{params, types::Value};
+
+    use super::{Dump, RestoreMode, DUMP_VERSION};
+    use crate::activities::{
+        tests::{memory, now},
+        Budget, Rounding,
+    };
+
+    fn empty_dump(now: u64) -> Dump {
+        Dump {
+            version: DUMP_VERSION,
+            exported_at: now,
+            sessions: Vec::new(),
+            clears: Vec::new(),
+            clients: Vec::new(),
+            projects: Vec::new(),
+            activity_projects: Vec::new(),
+            budgets: Vec::new(),
+            settings: Default::default(),
+        }
+    }
+
+    #[test]
+    fn merging_keeps_the_current_totals() {
+        let now = now();
+        let activities = memory();
+        activities.0.execute("INSERT INTO clears (time) VALUES (?)", params![now - 500]).unwrap();
+        let mut dump = empty_dump(now);
+        dump.clears = vec![now - 1000, now - 100];
+
+        activities.restore(&dump, RestoreMode::Merge).unwrap();
+
+        let clears = activities.dump().unwrap().clears;
+        assert!(clears.contains(&(now - 1000)));
+        assert!(!clears.contains(&(now - 100)));
+        assert_eq!(clears.iter().max(), Some(&(now - 500)));
+    }
+
+    #[test]
+    fn replacing_restores_every_clear() {
+        let now = now();
+        let activities = memory();
+        let mut dump = empty_dump(now);
+        dump.clears = vec![now - 1000, now - 100];
+
+        activities.restore(&dump, RestoreMode::Replace).unwrap();
+
+        assert_eq!(activities.dump().unwrap().clears, vec![now - 1000, now - 100]);
+    }
+
+    #[test]
+    fn invalid_settings_are_skipped() {
+        let activities = memory();
+        let mut dump = empty_dump(now());
+        dump.settings.insert("day_start_hour".to_string(), 30.into());
+        dump.settings.insert("currency".to_string(), "$".into());
+        dump.settings.insert("rounding_mode".to_string(), "sideways".into());
+        dump.settings.insert("work_start_hour".to_string(), 20.into());
+        dump.settings.insert("idle_minutes".to_string(), (-5).into());
+        dump.settings.insert("unknown".to_string(), 1.into());
+        dump.budgets = vec![
+            Budget { name: "Foo".to_string(), daily: Some(0), weekly: None },
+            Budget { name: "Bar".to_string(), daily: Some(3600), weekly: None },
+        ];
+
+        activities.restore(&dump, RestoreMode::Replace).unwrap();
+
+        assert_eq!(activities.day_start_hour().unwrap(), 0);
+        assert_eq!(activities.currency().unwrap(), "$");
+        assert_eq!(activities.rounding().unwrap(), Rounding::default());
+        assert_eq!(activities.working_hours().unwrap().start, 8);
+        assert_eq!(activities.idle_minutes().unwrap(), 5);
+        assert_eq!(activities.setting::<Value>("unknown").unwrap(), None);
+        let budgets = activities.dump().unwrap().budgets;
+        assert_eq!(budgets.len(), 1);
+        assert_eq!(budgets[0].name, "Bar");
+    }
+
+    #[test]
+    fn merging_keeps_the_current_settings() {
+        let activities = memory();
+        activities.set_day_start_hour(4).unwrap();
+        let mut dump = empty_dump(now());
+        dump.settings.insert("day_start_hour".to_string(), 6.into());
+        dump.settings.insert("reminder_minutes".to_string(), 30.into());
+
+        activities.restore(&dump, RestoreMode::Merge).unwrap();
+
+        assert_eq!(activities.day_start_hour().unwrap(), 4);
+        assert_eq!(activities.reminder_minutes().unwrap(), 30);
+    }
+}
@@ -0,0 +1,179 @@
+use rusqlite::params;
+
+use super::{breaks, split_tags, tags, Activities, ActivitiesError};
+
+impl Activities {
+    /**
+    Get the part of a time range that is inside a session.
+
+    # Errors
+    Fails if the session doesn't exist or if the range doesn't overlap it
+     */
+    fn idle_range(&self, session_id: i64, start: u64, end: u64) -> Result<(u64, u64), ActivitiesError> {
+        let session = self.session(session_id)?.ok_or(ActivitiesError::SessionNotFound(session_id))?;
+
+        let start = start.max(session.start_time);
+        let end = session.end_time.map_or(end, |end_time| end.min(end_time));
+        if end <= start {
+            return Err(ActivitiesError::InvalidTimeRange { start, end });
+        }
+        Ok((start, end))
+    }
+
+    /**
+    Remove idle time from a session by recording it as breaks.
+
+    Only the parts of the idle time outside of the breaks of the session are recorded, a pause taken while away isn't
+    removed twice.
+
+    # Arguments
+    session_id - The session that was running while idle
+    start - The start of the idle time in seconds since the epoch
+    end - The end of the idle time in seconds since the epoch
+     */
+    pub fn discard_idle(&self, session_id: i64, start: u64, end: u64) -> Result<(), ActivitiesError> {
+        let (start, end) = self.idle_range(session_id, start, end)?;
+        let session = self.session(session_id)?.ok_or(ActivitiesError::SessionNotFound(session_id))?;
+
+        // The breaks are ordered by start time, an open break covers everything after its start
+        let mut gaps = Vec::new();
+        let mut from = start;
+        for pause in &session.breaks {
+            let pause_end = pause.end_time.unwrap_or(u64::MAX);
+            if pause.start_time >= end {
+                break;
+            }
+            if pause.start_time > from {
+                gaps.push((from, pause.start_time));
+            }
+            from = from.max(pause_end);
+        }
+        if from < end {
+            gaps.push((from, end));
+        }
+
+        let tx = self.0.unchecked_transaction()?;
+        for (start, end) in gaps {
+            tx.execute(
+                "INSERT INTO breaks (session_id, start_time, end_time) VALUES (?, ?, ?)",
+                params![session_id, start, end],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /**
+    Move idle time from a session to another activity.
+
+    The session is cut at the start of the idle time, a session of the other activity covers the idle time and the
+    first activity continues in a new session with the same tags after it.
+
+    # Arguments
+    session_id - The session that was running while idle
+    start - The start of the idle time in seconds since the epoch
+    end - The end of the idle time in seconds since the epoch
+    name - The activity the idle time was spent on, the words starting with # are added as tags
+
+    # Returns
+    The id of the session of the other activity
+     */
+    pub fn reassign_idle(&self, session_id: i64, start: u64, end: u64, name: &str) -> Result<i64, ActivitiesError> {
+        let (start, end) = self.idle_range(session_id, start, end)?;
+        let (name, tags) = split_tags(name);
+        if name.trim().is_empty() {
+            return Err(ActivitiesError::InvalidSession("The activity name can't be empty".to_string()));
+        }
+        let tags = tags::normalize_tags(&tags)?;
+        let session = self.session(session_id)?.ok_or(ActivitiesError::SessionNotFound(session_id))?;
+
+        let tx = self.0.unchecked_transaction()?;
+
+        tx.execute("UPDATE activities SET end_time = ? WHERE id = ?", params![start, session_id])?;
+        // The first activity resumes after the idle time, running if it was still running
+        let resumed = if session.end_time.is_none_or(|end_time| end_time > end) {
+            tx.execute(
+                "INSERT INTO activities (name, start_time, end_time, billable, notes) VALUES (?, ?, ?, ?, ?)",
+                params![session.name, end, session.end_time, session.billable, session.notes],
+            )?;
+            let resumed = tx.last_insert_rowid();
+            tx.execute(
+                "INSERT INTO session_tags (session_id, tag_id) SELECT ?, tag_id FROM session_tags WHERE session_id = ?",
+                params![resumed, session_id],
+            )?;
+            tx.execute(
+                "UPDATE breaks SET session_id = ? WHERE session_id = ? AND start_time >= ?",
+                params![resumed, session_id, end],
+            )?;
+            Some(resumed)
+        } else {
+            None
+        };
+
+        // A break still going on is ended with the session and goes on in the resumed part
+        breaks::end_open_break(&tx, session_id, start, resumed.map(|resumed| (resumed, end)))?;
+
+        tx.execute(
+            "INSERT INTO activities (name, start_time, end_time) VALUES (?, ?, ?)",
+            params![name, start, end],
+        )?;
+        let id = tx.last_insert_rowid();
+        tags::insert_session_tags(&tx, id, &tags)?;
+
+        tx.commit()?;
+
+        Ok(id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::activities::tests::{memory, now};
+
+    #[test]
+    fn discarding_idle_time_skips_the_breaks() {
+        let activities = memory();
+        let now = now();
+        let id = activities.start_activity("Foo", &[], -3600).unwrap();
+        activities.pause_activity(-2400).unwrap();
+        activities.resume_activity(-1800).unwrap();
+
+        activities.discard_idle(id, now - 3000, now - 600).unwrap();
+
+        let session = activities.session(id).unwrap().unwrap();
+        let breaks: Vec<_> = session.breaks.iter().map(|pause| (now - pause.start_time, pause.end_time.map(|end| now - end))).collect();
+        assert_eq!(breaks, vec![(3000, Some(2400)), (2400, Some(1800)), (1800, Some(600))]);
+        assert_eq!(session.duration(now), 1200);
+    }
+
+    #[test]
+    fn discarding_idle_time_during_a_pause_adds_nothing_after_it() {
+        let activities = memory();
+        let now = now();
+        let id = activities.start_activity("Foo", &[], -3600).unwrap();
+        activities.pause_activity(-2400).unwrap();
+
+        activities.discard_idle(id, now - 3000, now - 600).unwrap();
+
+        let session = activities.session(id).unwrap().unwrap();
+        assert_eq!(session.breaks.len(), 2);
+        assert_eq!(session.breaks[0].end_time, Some(now - 2400));
+        assert_eq!(session.duration(now), 600);
+    }
+
+    #[test]
+    fn reassigning_idle_time_carries_the_pause_over() {
+        let activities = memory();
+        let now = now();
+        let id = activities.start_activity("Foo", &[], -3600).unwrap();
+        activities.pause_activity(-3000).unwrap();
+
+        activities.reassign_idle(id, now - 2400, now - 600, "Meeting #call").unwrap();
+
+        let session = activities.session(id).unwrap().unwrap();
+        assert_eq!(session.breaks[0].end_time, Some(now - 2400));
+        let resumed = activities.current_session().unwrap().unwrap();
+        assert_eq!((resumed.start_time, resumed.breaks[0].start_time), (now - 600, now - 600));
+        assert!(activities.is_paused().unwrap());
+    }
+}
@@ -0,0 +1,110 @@
+use std::time::SystemTime;
+
+use rusqlite::params;
+use serde::Serialize;
+
+use super::{tags::normalize_tag, Activities, ActivitiesError};
+
+/// A closed session read from another tracker, ready to be inserted
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportedSession {
+    /// The name of the activity
+    pub name: String,
+    /// The start of the session in seconds since the epoch
+    pub start_time: u64,
+    /// The end of the session in seconds since the epoch
+    pub end_time: u64,
+    /// The tags of the session without the `#`
+    pub tags: Vec<String>,
+    /// What was done during the session
+    pub notes: Option<String>,
+}
+
+impl Activities {
+    /**
+    Check if a time range overlaps a session, the running session counts until now.
+
+    # Arguments
+    start - The start of the range in seconds since the epoch
+    end - The end of the range in seconds since the epoch
+     */
+    pub fn overlaps_session(&self, start: u64, end: u64) -> Result<bool, rusqlite::Error> {
+        let now = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+
+        // The time is bound as an integer, a text from strftime would compare above every integer
+        self.0.query_row(
+            "SELECT EXISTS (SELECT 1 FROM activities WHERE start_time < ?2 AND COALESCE(end_time, ?3) > ?1)",
+            params![start, end, now],
+            |row| row.get(0),
+        )
+    }
+
+    /**
+    Insert imported sessions, either all of them are inserted or none is.
+
+    # Arguments
+    sessions - The sessions to insert, they must already be validated
+
+    # Returns
+    The number of sessions inserted
+
+    # Errors
+    Fails if a session has no name or ends before it starts, nothing is inserted then
+     */
+    pub fn import_sessions(&self, sessions: &[ImportedSession]) -> Result<usize, ActivitiesError> {
+        let tx = self.0.unchecked_transaction()?;
+
+        for session in sessions {
+            if session.name.trim().is_empty() {
+                return Err(ActivitiesError::InvalidSession("The activity name can't be empty".to_string()));
+            }
+            if session.end_time < session.start_time {
+                return Err(ActivitiesError::InvalidTimeRange { start: session.start_time, end: session.end_time });
+            }
+
+            tx.execute(
+                "INSERT INTO activities (name, start_time, end_time, notes) VALUES (?, ?, ?, ?)",
+                params![session.name.trim(), session.start_time, session.end_time, session.notes],
+            )?;
+            let id = tx.last_insert_rowid();
+
+            for tag in session.tags.iter().filter_map(|tag| normalize_tag(tag)) {
+                tx.execute("INSERT INTO tags (name) VALUES (?) ON CONFLICT DO NOTHING", params![tag])?;
+                tx.execute(
+                    "INSERT INTO session_tags (session_id, tag_id) SELECT ?, id FROM tags WHERE name = ? ON CONFLICT DO NOTHING",
+                    params![id, tag],
+                )?;
+            }
+        }
+
+        tx.commit()?;
+        Ok(sessions.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::activities::tests::{memory, now};
+
+    #[test]
+    fn older_range_doesnt_overlap_the_running_session() {
+        let activities = memory();
+        let now = now();
+        activities.start_activity("Foo", &[], -600).unwrap();
+
+        assert!(!activities.overlaps_session(now - 7200, now - 3600).unwrap());
+        assert!(activities.overlaps_session(now - 1200, now - 300).unwrap());
+    }
+
+    #[test]
+    fn range_overlaps_a_closed_session() {
+        let activities = memory();
+        activities.add_session("Foo", &[], 3600, 7200).unwrap();
+
+        assert!(activities.overlaps_session(7000, 8000).unwrap());
+        assert!(!activities.overlaps_session(7200, 8000).unwrap());
+    }
+}
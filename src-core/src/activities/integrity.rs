@@ -0,0 +1,198 @@
+use std::time::SystemTime;
+
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+
+use super::{breaks, Activities, ActivitiesError, Session};
+
+/// A problem in the sessions that makes the time counted wrong
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Issue {
+    /// The session ends before it starts
+    NegativeDuration { session: Session },
+    /// The later session starts before the end of the earlier one, the overlapping time is counted twice
+    Overlap { earlier: Session, later: Session },
+}
+
+/// The ways to fix an overlap between two sessions
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Repair {
+    /// End the earlier session when the later one starts
+    TrimEarlier,
+    /// Start the later session when the earlier one ends
+    TrimLater,
+    /// Cut the earlier session around the later one, the earlier activity resumes after the later one ends
+    Split,
+}
+
+impl Activities {
+    /**
+    List the sessions with a negative duration and the sessions overlapping each other.
+
+    A session overlapping several earlier sessions is reported once, against the earlier session ending last.
+
+    # Returns
+    The issues ordered by the start time of the session at fault
+     */
+    pub fn check_integrity(&self) -> Result<Vec<Issue>, rusqlite::Error> {
+        let now = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let mut stmt = self.0.prepare(&format!(
+            "SELECT {} FROM {} ORDER BY activities.start_time, activities.id",
+            Session::COLUMNS,
+            Session::TABLES
+        ))?;
+        let sessions = stmt.query_map([], Session::from_row)?;
+
+        let mut issues = Vec::new();
+        // The session ending last among the ones already seen
+        let mut furthest: Option<(Session, u64)> = None;
+
+        for session in sessions {
+            let session = session?;
+            let end_time = session.end_time.unwrap_or(now);
+
+            if end_time < session.start_time {
+                issues.push(Issue::NegativeDuration { session });
+                continue;
+            }
+
+            match &furthest {
+                Some((earlier, earlier_end)) if session.start_time < *earlier_end => {
+                    issues.push(Issue::Overlap {
+                        earlier: earlier.clone(),
+                        later: session.clone(),
+                    });
+                    if end_time > *earlier_end {
+                        furthest = Some((session, end_time));
+                    }
+                }
+                Some((_, earlier_end)) if end_time <= *earlier_end => {}
+                _ => furthest = Some((session, end_time)),
+            }
+        }
+
+        Ok(issues)
+    }
+
+    /**
+    Fix the overlap between two sessions.
+
+    # Arguments
+    earlier - The id of the session starting first
+    later - The id of the session starting during the earlier one
+    repair - How to fix the overlap
+
+    # Errors
+    Fails if one of the sessions doesn't exist or if they don't overlap
+     */
+    pub fn repair_overlap(&self, earlier: i64, later: i64, repair: Repair) -> Result<(), ActivitiesError> {
+        let now = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let earlier = self.session(earlier)?.ok_or(ActivitiesError::SessionNotFound(earlier))?;
+        let later = self.session(later)?.ok_or(ActivitiesError::SessionNotFound(later))?;
+        let earlier_end = earlier.end_time.unwrap_or(now);
+        let later_end = later.end_time.unwrap_or(now);
+
+        if later.start_time < earlier.start_time || later.start_time >= earlier_end {
+            return Err(ActivitiesError::InvalidSession(format!(
+                "The sessions {} and {} don't overlap",
+                earlier.id, later.id
+            )));
+        }
+
+        let tx = self.0.unchecked_transaction()?;
+        match repair {
+            Repair::TrimEarlier => {
+                tx.execute(
+                    "UPDATE activities SET end_time = ? WHERE id = ?",
+                    params![later.start_time, earlier.id],
+                )?;
+                breaks::end_open_break(&tx, earlier.id, later.start_time, None)?;
+            }
+            Repair::TrimLater => {
+                // A session contained in the earlier one ends up empty
+                tx.execute(
+                    "UPDATE activities SET start_time = ? WHERE id = ?",
+                    params![earlier_end.min(later_end), later.id],
+                )?;
+            }
+            Repair::Split => {
+                tx.execute(
+                    "UPDATE activities SET end_time = ? WHERE id = ?",
+                    params![later.start_time, earlier.id],
+                )?;
+                // The earlier activity resumes after the later one if it was still going on
+                let resumed = if earlier_end > later_end {
+                    tx.execute(
+                        "INSERT INTO activities (name, start_time, end_time, billable, notes) VALUES (?, ?, ?, ?, ?)",
+                        params![earlier.name, later_end, earlier.end_time, earlier.billable, earlier.notes],
+                    )?;
+                    let resumed = tx.last_insert_rowid();
+                    tx.execute(
+                        "INSERT INTO session_tags (session_id, tag_id) SELECT ?, tag_id FROM session_tags WHERE session_id = ?",
+                        params![resumed, earlier.id],
+                    )?;
+                    // The breaks taken after the later activity belong to the resumed part
+                    tx.execute(
+                        "UPDATE breaks SET session_id = ? WHERE session_id = ? AND start_time >= ?",
+                        params![resumed, earlier.id, later_end],
+                    )?;
+                    Some((resumed, later_end))
+                } else {
+                    None
+                };
+                breaks::end_open_break(&tx, earlier.id, later.start_time, resumed)?;
+            }
+        }
+        tx.commit()?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::activities::tests::{memory, now};
+
+    #[test]
+    fn trimming_the_earlier_session_ends_its_break() {
+        let activities = memory();
+        let now = now();
+        let earlier = activities.start_activity("Foo", &[], -3600).unwrap();
+        activities.pause_activity(-3000).unwrap();
+        let later = activities.add_session("Bar", &[], now - 2400, now - 1200).unwrap();
+
+        activities.repair_overlap(earlier, later, Repair::TrimEarlier).unwrap();
+
+        let earlier = activities.session(earlier).unwrap().unwrap();
+        assert_eq!(earlier.end_time, Some(now - 2400));
+        assert_eq!(earlier.breaks[0].end_time, Some(now - 2400));
+        assert!(!activities.is_paused().unwrap());
+    }
+
+    #[test]
+    fn splitting_the_earlier_session_moves_its_break() {
+        let activities = memory();
+        let now = now();
+        let earlier = activities.start_activity("Foo", &[], -3600).unwrap();
+        activities.pause_activity(-3000).unwrap();
+        let later = activities.add_session("Bar", &[], now - 2400, now - 1200).unwrap();
+
+        activities.repair_overlap(earlier, later, Repair::Split).unwrap();
+
+        let earlier = activities.session(earlier).unwrap().unwrap();
+        assert_eq!(earlier.breaks[0].end_time, Some(now - 2400));
+        let resumed = activities.current_session().unwrap().unwrap();
+        assert_eq!(resumed.start_time, now - 1200);
+        assert_eq!(resumed.breaks[0].start_time, now - 1200);
+        assert!(activities.is_paused().unwrap());
+    }
+}
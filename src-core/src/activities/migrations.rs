@@ -0,0 +1,205 @@
+use log::info;
+use rusqlite::{Connection, Transaction, TransactionBehavior};
+
+use super::ActivitiesError;
+
+/**
+Ordered list of the schema migrations.
+
+The migration at index `i` brings the schema from version `i` to version `i + 1`, the version being stored in the
+`user_version` pragma of the database. Migrations must never be edited or reordered once released, new changes to the
+schema are made by appending a new migration.
+ */
+const MIGRATIONS: &[&str] = &[
+    // 1 - Initial schema
+    // Uses IF NOT EXISTS since the databases created before the migrations were introduced already have these tables
+    "
+    CREATE TABLE IF NOT EXISTS activities (
+        id INTEGER PRIMARY KEY,
+        name TEXT NOT NULL,
+        start_time INTEGER NOT NULL,
+        end_time INTEGER
+    );
+    CREATE TABLE IF NOT EXISTS clears (
+        id INTEGER PRIMARY KEY,
+        time INTEGER NOT NULL
+    );
+    ",
+    // 2 - Key-value store for the user settings
+    "
+    CREATE TABLE settings (
+        key TEXT PRIMARY KEY,
+        value NOT NULL
+    );
+    ",
+    // 3 - Client → project → activity hierarchy, the existing activities go to the unassigned project
+    "
+    CREATE TABLE clients (
+        id INTEGER PRIMARY KEY,
+        name TEXT NOT NULL UNIQUE
+    );
+    CREATE TABLE projects (
+        id INTEGER PRIMARY KEY,
+        name TEXT NOT NULL,
+        client_id INTEGER REFERENCES clients(id)
+    );
+    CREATE UNIQUE INDEX projects_client_name ON projects (COALESCE(client_id, 0), name);
+    CREATE TABLE activity_projects (
+        name TEXT PRIMARY KEY,
+        project_id INTEGER NOT NULL REFERENCES projects(id)
+    );
+    INSERT INTO projects (id, name) VALUES (1, 'Unassigned');
+    INSERT INTO activity_projects (name, project_id) SELECT DISTINCT name, 1 FROM activities;
+    ",
+    // 4 - Tags on the sessions
+    "
+    CREATE TABLE tags (
+        id INTEGER PRIMARY KEY,
+        name TEXT NOT NULL UNIQUE
+    );
+    CREATE TABLE session_tags (
+        session_id INTEGER NOT NULL REFERENCES activities(id) ON DELETE CASCADE,
+        tag_id INTEGER NOT NULL REFERENCES tags(id),
+        PRIMARY KEY (session_id, tag_id)
+    );
+    ",
+    // 5 - Notes on the sessions
+    "
+    ALTER TABLE activities ADD COLUMN notes TEXT;
+    ",
+    // 6 - Hourly rates in cents, the rate of a project overrides the one of its client, and billable sessions
+    "
+    ALTER TABLE clients ADD COLUMN hourly_rate INTEGER;
+    ALTER TABLE projects ADD COLUMN hourly_rate INTEGER;
+    ALTER TABLE activities ADD COLUMN billable INTEGER NOT NULL DEFAULT 1;
+    ",
+    // 7 - Breaks inside the sessions
+    "
+    CREATE TABLE breaks (
+        id INTEGER PRIMARY KEY,
+        session_id INTEGER NOT NULL REFERENCES activities(id) ON DELETE CASCADE,
+        start_time INTEGER NOT NULL,
+        end_time INTEGER
+    );
+    CREATE INDEX breaks_session ON breaks (session_id);
+    ",
+    // 8 - Daily and weekly time budgets of the activities in seconds
+    "
+    CREATE TABLE budgets (
+        name TEXT PRIMARY KEY,
+        daily INTEGER,
+        weekly INTEGER
+    );
+    ",
+];
+
+/// The schema version this version of the application works with
+pub const SCHEMA_VERSION: u32 = MIGRATIONS.len() as u32;
+
+/**
+Bring the database schema up to date by running every migration that was not yet applied.
+
+Each migration runs in its own immediate transaction along with the update of the `user_version`, so a failing
+migration leaves the database at the previous version. The version is read again inside each transaction since the
+desktop application and the command line client can open the database at the same time.
+
+# Errors
+Fails with `ActivitiesError::UnsupportedSchema` if the database was created by a newer version of the application
+ */
+pub fn migrate(conn: &Connection) -> Result<(), ActivitiesError> {
+    loop {
+        let tx = Transaction::new_unchecked(conn, TransactionBehavior::Immediate)?;
+        let version: u32 = tx.pragma_query_value(None, "user_version", |row| row.get(0))?;
+
+        if version > SCHEMA_VERSION {
+            return Err(ActivitiesError::UnsupportedSchema {
+                found: version,
+                supported: SCHEMA_VERSION,
+            });
+        }
+        let Some(migration) = MIGRATIONS.get(version as usize) else {
+            return Ok(());
+        };
+
+        let target = version + 1;
+        info!("Migrating the database to version {}", target);
+        tx.execute_batch(migration)?;
+        tx.pragma_update(None, "user_version", target)?;
+        tx.commit()?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version(conn: &Connection) -> u32 {
+        conn.pragma_query_value(None, "user_version", |row| row.get(0)).unwrap()
+    }
+
+    #[test]
+    fn migrates_a_new_database() {
+        let conn = Connection::open_in_memory().unwrap();
+
+        migrate(&conn).unwrap();
+
+        assert_eq!(version(&conn), SCHEMA_VERSION);
+        conn.execute("INSERT INTO budgets (name, daily) VALUES ('Foo', 3600)", []).unwrap();
+    }
+
+    #[test]
+    fn migrates_from_an_older_version() {
+        let conn = Connection::open_in_memory().unwrap();
+        for migration in &MIGRATIONS[..4] {
+            conn.execute_batch(migration).unwrap();
+        }
+        conn.pragma_update(None, "user_version", 4).unwrap();
+        conn.execute("INSERT INTO activities (name, start_time, end_time) VALUES ('Foo', 0, 60)", []).unwrap();
+
+        migrate(&conn).unwrap();
+
+        assert_eq!(version(&conn), SCHEMA_VERSION);
+        let billable: bool = conn.query_row("SELECT billable FROM activities", [], |row| row.get(0)).unwrap();
+        assert!(billable);
+    }
+
+    #[test]
+    fn migrating_twice_does_nothing() {
+        let conn = Connection::open_in_memory().unwrap();
+
+        migrate(&conn).unwrap();
+        migrate(&conn).unwrap();
+
+        assert_eq!(version(&conn), SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn migrates_once_across_connections() {
+        let path = std::env::temp_dir().join(format!("time-tracker-migrations-{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let first = Connection::open(&path).unwrap();
+        let second = Connection::open(&path).unwrap();
+
+        migrate(&first).unwrap();
+        // The second connection sees the version of the first one instead of running the migrations again
+        migrate(&second).unwrap();
+
+        assert_eq!(version(&second), SCHEMA_VERSION);
+        drop((first, second));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn refuses_a_newer_schema() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.pragma_update(None, "user_version", SCHEMA_VERSION + 1).unwrap();
+
+        let res = migrate(&conn);
+
+        assert!(matches!(
+            res,
+            Err(ActivitiesError::UnsupportedSchema { found, supported }) if found == SCHEMA_VERSION + 1 && supported == SCHEMA_VERSION
+        ));
+        assert_eq!(version(&conn), SCHEMA_VERSION + 1);
+    }
+}
@@ -0,0 +1,46 @@
+use rusqlite::params;
+
+use super::{Activities, ActivitiesError};
+
+/// Trim the notes, None if nothing is left
+fn normalize_notes(notes: Option<&str>) -> Option<&str> {
+    notes.map(str::trim).filter(|notes| !notes.is_empty())
+}
+
+impl Activities {
+    /**
+    Replace the notes of a session.
+
+    # Arguments
+    id - The id of the session
+    notes - The new notes, None or blank to remove them
+
+    # Errors
+    Fails if the session doesn't exist
+     */
+    pub fn set_session_notes(&self, id: i64, notes: Option<&str>) -> Result<(), ActivitiesError> {
+        let updated = self.0.execute(
+            "UPDATE activities SET notes = ? WHERE id = ?",
+            params![normalize_notes(notes), id],
+        )?;
+
+        if updated == 0 {
+            return Err(ActivitiesError::SessionNotFound(id));
+        }
+        Ok(())
+    }
+
+    /**
+    Replace the notes of the running session if there is one.
+
+    # Arguments
+    notes - The new notes, None or blank to remove them
+     */
+    pub fn set_current_notes(&self, notes: Option<&str>) -> Result<(), rusqlite::Error> {
+        self.0.execute(
+            "UPDATE activities SET notes = ? WHERE end_time IS NULL",
+            params![normalize_notes(notes)],
+        )?;
+        Ok(())
+    }
+}
@@ -0,0 +1,211 @@
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+use super::{Activities, ActivitiesError, Session};
+
+/// The id of the project the activities belong to until they are assigned to another one
+const UNASSIGNED_PROJECT: i64 = 1;
+
+/// What the time of the sessions is summed by
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Grouping {
+    #[default]
+    Activity,
+    Project,
+    Client,
+    /// A session counts toward each of its tags
+    Tag,
+}
+
+impl Grouping {
+    /// The name of the groups, used as a column header
+    pub fn label(&self) -> &'static str {
+        match self {
+            Grouping::Activity => "Activity",
+            Grouping::Project => "Project",
+            Grouping::Client => "Client",
+            Grouping::Tag => "Tag",
+        }
+    }
+
+    /// Get the names of the groups a session belongs to, only the tags can put a session in several groups
+    pub fn keys(&self, session: &Session) -> Vec<String> {
+        match (self, &session.client) {
+            (Grouping::Activity, _) => vec![session.name.clone()],
+            (Grouping::Project, Some(client)) => vec![format!("{} › {}", client, session.project)],
+            (Grouping::Project, None) => vec![session.project.clone()],
+            (Grouping::Client, Some(client)) => vec![client.clone()],
+            (Grouping::Client, None) => vec!["No client".to_string()],
+            (Grouping::Tag, _) if session.tags.is_empty() => vec!["No tag".to_string()],
+            (Grouping::Tag, _) => session.tags.iter().map(|tag| format!("#{}", tag)).collect(),
+        }
+    }
+}
+
+/// A client the projects are done for
+#[derive(Debug, Clone, Serialize)]
+pub struct Client {
+    pub id: i64,
+    pub name: String,
+    /// The hourly rate in cents if the client has one
+    pub hourly_rate: Option<i64>,
+}
+
+/// A project grouping activities
+#[derive(Debug, Clone, Serialize)]
+pub struct Project {
+    pub id: i64,
+    pub name: String,
+    /// The client the project is for if it has one
+    pub client_id: Option<i64>,
+    /// The name of the client if the project has one
+    pub client: Option<String>,
+    /// The hourly rate in cents if the project has one, overrides the rate of the client
+    pub hourly_rate: Option<i64>,
+}
+
+impl Activities {
+    /// List all the clients ordered by name
+    pub fn list_clients(&self) -> Result<Vec<Client>, rusqlite::Error> {
+        let mut stmt = self.0.prepare("SELECT id, name, hourly_rate FROM clients ORDER BY name")?;
+        let rows = stmt.query_map([], |row| {
+            Ok(Client {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                hourly_rate: row.get(2)?,
+            })
+        })?;
+
+        let mut clients = Vec::new();
+        for client in rows {
+            clients.push(client?);
+        }
+
+        Ok(clients)
+    }
+
+    /**
+    Add a client.
+
+    # Arguments
+    name - The name of the client, it must be unique
+
+    # Returns
+    The id of the new client
+     */
+    pub fn add_client(&self, name: &str) -> Result<i64, ActivitiesError> {
+        if name.trim().is_empty() {
+            return Err(ActivitiesError::InvalidProject("The client name can't be empty".to_string()));
+        }
+
+        self.0.execute("INSERT INTO clients (name) VALUES (?)", params![name.trim()])?;
+        Ok(self.0.last_insert_rowid())
+    }
+
+    /// List all the projects ordered by client and name, the unassigned project first
+    pub fn list_projects(&self) -> Result<Vec<Project>, rusqlite::Error> {
+        let mut stmt = self.0.prepare(
+            "SELECT projects.id, projects.name, projects.client_id, clients.name, projects.hourly_rate 
+            FROM projects LEFT JOIN clients ON clients.id = projects.client_id 
+            ORDER BY projects.id != ?, clients.name, projects.name",
+        )?;
+        let rows = stmt.query_map(params![UNASSIGNED_PROJECT], |row| {
+            Ok(Project {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                client_id: row.get(2)?,
+                client: row.get(3)?,
+                hourly_rate: row.get(4)?,
+            })
+        })?;
+
+        let mut projects = Vec::new();
+        for project in rows {
+            projects.push(project?);
+        }
+
+        Ok(projects)
+    }
+
+    /**
+    Add a project.
+
+    # Arguments
+    name - The name of the project, it must be unique for the client
+    client_id - The client the project is for if it has one
+
+    # Returns
+    The id of the new project
+     */
+    pub fn add_project(&self, name: &str, client_id: Option<i64>) -> Result<i64, ActivitiesError> {
+        if name.trim().is_empty() {
+            return Err(ActivitiesError::InvalidProject("The project name can't be empty".to_string()));
+        }
+
+        if let Some(client_id) = client_id {
+            let exists = self
+                .0
+                .query_row("SELECT 1 FROM clients WHERE id = ?", params![client_id], |_| Ok(()))
+                .optional()?
+                .is_some();
+            if !exists {
+                return Err(ActivitiesError::InvalidProject(format!("No client with the id {}", client_id)));
+            }
+        }
+
+        self.0.execute(
+            "INSERT INTO projects (name, client_id) VALUES (?, ?)",
+            params![name.trim(), client_id],
+        )?;
+        Ok(self.0.last_insert_rowid())
+    }
+
+    /**
+    List every known activity name with the id of its project.
+
+    # Returns
+    A list of activity names and project ids ordered by name
+     */
+    pub fn activity_projects(&self) -> Result<Vec<(String, i64)>, rusqlite::Error> {
+        let mut stmt = self.0.prepare(
+            "SELECT name, project_id FROM activity_projects 
+            UNION 
+            SELECT DISTINCT name, ? FROM activities WHERE name NOT IN (SELECT name FROM activity_projects) 
+            ORDER BY name",
+        )?;
+        let rows = stmt.query_map(params![UNASSIGNED_PROJECT], |row| Ok((row.get(0)?, row.get(1)?)))?;
+
+        let mut activities = Vec::new();
+        for activity in rows {
+            activities.push(activity?);
+        }
+
+        Ok(activities)
+    }
+
+    /**
+    Move an activity to a project. All the sessions of the activity, past and future, count toward the project.
+
+    # Arguments
+    name - The name of the activity
+    project_id - The id of the project
+     */
+    pub fn set_activity_project(&self, name: &str, project_id: i64) -> Result<(), ActivitiesError> {
+        let exists = self
+            .0
+            .query_row("SELECT 1 FROM projects WHERE id = ?", params![project_id], |_| Ok(()))
+            .optional()?
+            .is_some();
+        if !exists {
+            return Err(ActivitiesError::InvalidProject(format!("No project with the id {}", project_id)));
+        }
+
+        self.0.execute(
+            "INSERT INTO activity_projects (name, project_id) VALUES (?, ?) 
+            ON CONFLICT(name) DO UPDATE SET project_id = excluded.project_id",
+            params![name, project_id],
+        )?;
+        Ok(())
+    }
+}
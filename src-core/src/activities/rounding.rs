@@ -0,0 +1,195 @@
+use rusqlite::types::{FromSql, FromSqlError, FromSqlResult, ToSql, ToSqlOutput, ValueRef};
+use serde::{Deserialize, Serialize};
+
+use super::{Activities, ActivitiesError};
+
+/// The key of the setting holding the rounding mode
+pub(super) const ROUNDING_MODE: &str = "rounding_mode";
+/// The key of the setting holding the rounding increment in minutes
+pub(super) const ROUNDING_INCREMENT: &str = "rounding_increment";
+/// The key of the setting telling if each session or only the totals are rounded
+pub(super) const ROUNDING_SCOPE: &str = "rounding_scope";
+/// The key of the setting telling if the statistics are rounded too
+pub(super) const ROUNDING_STATISTICS: &str = "rounding_statistics";
+
+/// How the durations are rounded to the increment
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RoundingMode {
+    /// The durations are kept as they are
+    #[default]
+    None,
+    Nearest,
+    Up,
+    Down,
+}
+
+/// What the rounding is applied to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RoundingScope {
+    /// Each session is rounded before the sessions are summed
+    Session,
+    /// Only the sums are rounded
+    #[default]
+    Total,
+}
+
+impl RoundingMode {
+    fn as_str(&self) -> &'static str {
+        match self {
+            RoundingMode::None => "none",
+            RoundingMode::Nearest => "nearest",
+            RoundingMode::Up => "up",
+            RoundingMode::Down => "down",
+        }
+    }
+}
+
+impl ToSql for RoundingMode {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(self.as_str().into())
+    }
+}
+
+impl FromSql for RoundingMode {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        match value.as_str()? {
+            "none" => Ok(RoundingMode::None),
+            "nearest" => Ok(RoundingMode::Nearest),
+            "up" => Ok(RoundingMode::Up),
+            "down" => Ok(RoundingMode::Down),
+            _ => Err(FromSqlError::InvalidType),
+        }
+    }
+}
+
+impl ToSql for RoundingScope {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(match self {
+            RoundingScope::Session => "session",
+            RoundingScope::Total => "total",
+        }
+        .into())
+    }
+}
+
+impl FromSql for RoundingScope {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        match value.as_str()? {
+            "session" => Ok(RoundingScope::Session),
+            "total" => Ok(RoundingScope::Total),
+            _ => Err(FromSqlError::InvalidType),
+        }
+    }
+}
+
+/**
+The rounding of the durations in the exports, and in the statistics if asked for.
+
+# Fields
+mode - How the durations are rounded, nothing is rounded with RoundingMode::None
+increment - The increment the durations are rounded to in minutes, Ex : 6 or 15
+scope - Whether each session or only the totals are rounded
+statistics - Whether the statistics are rounded too
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Rounding {
+    pub mode: RoundingMode,
+    pub increment: u32,
+    pub scope: RoundingScope,
+    pub statistics: bool,
+}
+
+impl Default for Rounding {
+    fn default() -> Self {
+        Self {
+            mode: RoundingMode::None,
+            increment: 15,
+            scope: RoundingScope::Total,
+            statistics: false,
+        }
+    }
+}
+
+impl Rounding {
+    /// Round a duration in seconds to the increment
+    pub fn round(&self, seconds: u64) -> u64 {
+        let increment = self.increment as u64 * 60;
+        if increment == 0 {
+            return seconds;
+        }
+
+        match self.mode {
+            RoundingMode::None => seconds,
+            RoundingMode::Nearest => (seconds + increment / 2) / increment * increment,
+            RoundingMode::Up => seconds.div_ceil(increment) * increment,
+            RoundingMode::Down => seconds / increment * increment,
+        }
+    }
+
+    /// Round the duration of a session, it is only rounded when each session is
+    pub fn round_session(&self, seconds: u64) -> u64 {
+        match self.scope {
+            RoundingScope::Session => self.round(seconds),
+            RoundingScope::Total => seconds,
+        }
+    }
+
+    /// Round a sum of sessions, it is only rounded when the totals are
+    pub fn round_total(&self, seconds: u64) -> u64 {
+        match self.scope {
+            RoundingScope::Session => seconds,
+            RoundingScope::Total => self.round(seconds),
+        }
+    }
+
+    /// Describe the rounding to state it in the exports, Ex : "Rounded up to 15 minutes per session"
+    pub fn describe(&self) -> String {
+        let mode = match self.mode {
+            RoundingMode::None => return "Not rounded".to_string(),
+            RoundingMode::Nearest => "Rounded to the nearest",
+            RoundingMode::Up => "Rounded up to",
+            RoundingMode::Down => "Rounded down to",
+        };
+        let scope = match self.scope {
+            RoundingScope::Session => "per session",
+            RoundingScope::Total => "on the totals",
+        };
+        format!("{} {} minutes {}", mode, self.increment, scope)
+    }
+}
+
+impl Activities {
+    /// Get the rounding of the durations. Defaults to no rounding.
+    pub fn rounding(&self) -> Result<Rounding, rusqlite::Error> {
+        let default = Rounding::default();
+        Ok(Rounding {
+            mode: self.setting(ROUNDING_MODE)?.unwrap_or(default.mode),
+            increment: self.setting(ROUNDING_INCREMENT)?.unwrap_or(default.increment),
+            scope: self.setting(ROUNDING_SCOPE)?.unwrap_or(default.scope),
+            statistics: self.setting(ROUNDING_STATISTICS)?.unwrap_or(default.statistics),
+        })
+    }
+
+    /**
+    Set the rounding of the durations.
+
+    # Arguments
+    rounding - The rounding, its increment must be between 1 and 1440 minutes
+     */
+    pub fn set_rounding(&self, rounding: Rounding) -> Result<(), ActivitiesError> {
+        if rounding.increment == 0 || rounding.increment > 24 * 60 {
+            return Err(ActivitiesError::InvalidSetting(format!(
+                "The rounding increment must be between 1 and 1440 minutes, got {}",
+                rounding.increment
+            )));
+        }
+
+        self.set_setting(ROUNDING_MODE, rounding.mode)?;
+        self.set_setting(ROUNDING_INCREMENT, rounding.increment)?;
+        self.set_setting(ROUNDING_SCOPE, rounding.scope)?;
+        self.set_setting(ROUNDING_STATISTICS, rounding.statistics)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,259 @@
+use rusqlite::{params, types::FromSql, OptionalExtension, ToSql};
+use serde::{Deserialize, Serialize};
+
+use super::{Activities, ActivitiesError};
+
+/// The key of the setting holding the hour at which the days start
+pub(super) const DAY_START_HOUR: &str = "day_start_hour";
+/// The key of the setting telling if the notes are asked for when stopping an activity
+pub(super) const ASK_NOTES_ON_STOP: &str = "ask_notes_on_stop";
+/// The key of the setting holding the currency symbol of the invoices
+pub(super) const CURRENCY: &str = "currency";
+/// The key of the setting holding the length of the pomodoro breaks in minutes
+pub(super) const POMODORO_BREAK_MINUTES: &str = "pomodoro_break_minutes";
+/// The key of the setting telling if the activity is paused for a break at the end of each timebox
+pub(super) const POMODORO_AUTO_BREAKS: &str = "pomodoro_auto_breaks";
+/// The key of the setting holding the minutes without input after which the user is considered away
+pub(super) const IDLE_MINUTES: &str = "idle_minutes";
+/// The key of the setting holding the local hour at which the working hours start
+pub(super) const WORK_START_HOUR: &str = "work_start_hour";
+/// The key of the setting holding the local hour at which the working hours end
+pub(super) const WORK_END_HOUR: &str = "work_end_hour";
+/// The key of the setting holding the minutes without a running activity before a reminder
+pub(super) const REMINDER_MINUTES: &str = "reminder_minutes";
+/// The key of the setting telling if closing the window hides it in the system tray
+pub(super) const CLOSE_TO_TRAY: &str = "close_to_tray";
+/// The key of the setting telling if the local HTTP API is served
+pub(super) const API_ENABLED: &str = "api_enabled";
+/// The key of the setting holding the port of the local HTTP API
+pub(super) const API_PORT: &str = "api_port";
+/// The key of the setting holding the token the requests to the local HTTP API must carry, it is kept out of the dumps
+pub(super) const API_TOKEN: &str = "api_token";
+
+/// The local hours shown on the timeline and during which the reminders are sent, the end is excluded
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct WorkingHours {
+    pub start: u32,
+    pub end: u32,
+}
+
+/// The local HTTP API, only reachable from this machine with the token as a bearer token
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiSettings {
+    pub enabled: bool,
+    pub port: u16,
+    /// Empty until a token is generated
+    pub token: String,
+}
+
+impl Activities {
+    /**
+    Get the value of a setting.
+
+    # Arguments
+    key - The key of the setting
+
+    # Returns
+    The value of the setting if it was set
+     */
+    pub fn setting<T: FromSql>(&self, key: &str) -> Result<Option<T>, rusqlite::Error> {
+        self.0
+            .query_row("SELECT value FROM settings WHERE key = ?", params![key], |row| row.get(0))
+            .optional()
+    }
+
+    /**
+    Set the value of a setting, replacing the previous one.
+
+    # Arguments
+    key - The key of the setting
+    value - The new value of the setting
+     */
+    pub fn set_setting<T: ToSql>(&self, key: &str, value: T) -> Result<(), rusqlite::Error> {
+        self.0.execute(
+            "INSERT INTO settings (key, value) VALUES (?, ?) ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+            params![key, value],
+        )?;
+        Ok(())
+    }
+
+    /// Get the local hour at which the days start. Defaults to midnight.
+    pub fn day_start_hour(&self) -> Result<u32, rusqlite::Error> {
+        Ok(self.setting(DAY_START_HOUR)?.unwrap_or(0))
+    }
+
+    /**
+    Set the local hour at which the days start. The activities started before this hour count toward the previous day.
+
+    # Arguments
+    hour - The hour the days start at, between 0 and 23
+     */
+    pub fn set_day_start_hour(&self, hour: u32) -> Result<(), ActivitiesError> {
+        if hour >= 24 {
+            return Err(ActivitiesError::InvalidSetting(format!(
+                "The day start hour must be between 0 and 23, got {}",
+                hour
+            )));
+        }
+
+        self.set_setting(DAY_START_HOUR, hour)?;
+        Ok(())
+    }
+
+    /// Whether to ask for notes when stopping an activity. Defaults to true.
+    pub fn ask_notes_on_stop(&self) -> Result<bool, rusqlite::Error> {
+        Ok(self.setting(ASK_NOTES_ON_STOP)?.unwrap_or(true))
+    }
+
+    /// Set whether to ask for notes when stopping an activity
+    pub fn set_ask_notes_on_stop(&self, ask: bool) -> Result<(), rusqlite::Error> {
+        self.set_setting(ASK_NOTES_ON_STOP, ask)
+    }
+
+    /// Get the currency symbol used on the invoices. Defaults to €.
+    pub fn currency(&self) -> Result<String, rusqlite::Error> {
+        Ok(self.setting(CURRENCY)?.unwrap_or_else(|| "€".to_string()))
+    }
+
+    /**
+    Set the currency symbol used on the invoices.
+
+    # Arguments
+    currency - The symbol or code of the currency, Ex : "€", "USD"
+     */
+    pub fn set_currency(&self, currency: &str) -> Result<(), ActivitiesError> {
+        if currency.trim().is_empty() {
+            return Err(ActivitiesError::InvalidSetting("The currency can't be empty".to_string()));
+        }
+
+        self.set_setting(CURRENCY, currency.trim())?;
+        Ok(())
+    }
+
+    /// Get the length of the pomodoro breaks in minutes. Defaults to 5.
+    pub fn pomodoro_break_minutes(&self) -> Result<u32, rusqlite::Error> {
+        Ok(self.setting(POMODORO_BREAK_MINUTES)?.unwrap_or(5))
+    }
+
+    /**
+    Set the length of the pomodoro breaks.
+
+    # Arguments
+    minutes - The length of the breaks in minutes, at least 1
+     */
+    pub fn set_pomodoro_break_minutes(&self, minutes: u32) -> Result<(), ActivitiesError> {
+        if minutes == 0 {
+            return Err(ActivitiesError::InvalidSetting("The breaks must last at least a minute".to_string()));
+        }
+
+        self.set_setting(POMODORO_BREAK_MINUTES, minutes)?;
+        Ok(())
+    }
+
+    /// Whether the activity is paused for a break at the end of each timebox. Defaults to false.
+    pub fn pomodoro_auto_breaks(&self) -> Result<bool, rusqlite::Error> {
+        Ok(self.setting(POMODORO_AUTO_BREAKS)?.unwrap_or(false))
+    }
+
+    /// Set whether the activity is paused for a break at the end of each timebox
+    pub fn set_pomodoro_auto_breaks(&self, auto_breaks: bool) -> Result<(), rusqlite::Error> {
+        self.set_setting(POMODORO_AUTO_BREAKS, auto_breaks)
+    }
+
+    /// Get the minutes without input after which the user is considered away, 0 when the detection is off. Defaults to 5.
+    pub fn idle_minutes(&self) -> Result<u32, rusqlite::Error> {
+        Ok(self.setting(IDLE_MINUTES)?.unwrap_or(5))
+    }
+
+    /**
+    Set the minutes without input after which the user is considered away.
+
+    # Arguments
+    minutes - The idle time in minutes, 0 to turn the detection off
+     */
+    pub fn set_idle_minutes(&self, minutes: u32) -> Result<(), rusqlite::Error> {
+        self.set_setting(IDLE_MINUTES, minutes)
+    }
+
+    /// Get the working hours. Defaults to 8 to 19.
+    pub fn working_hours(&self) -> Result<WorkingHours, rusqlite::Error> {
+        Ok(WorkingHours {
+            start: self.setting(WORK_START_HOUR)?.unwrap_or(8),
+            end: self.setting(WORK_END_HOUR)?.unwrap_or(19),
+        })
+    }
+
+    /**
+    Set the working hours.
+
+    # Arguments
+    hours - The start and end hours, the start must be before the end and the end at most 24
+     */
+    pub fn set_working_hours(&self, hours: WorkingHours) -> Result<(), ActivitiesError> {
+        if hours.start >= hours.end || hours.end > 24 {
+            return Err(ActivitiesError::InvalidSetting(format!(
+                "The working hours must start before they end and end at 24 at most, got {} to {}",
+                hours.start, hours.end
+            )));
+        }
+
+        self.set_setting(WORK_START_HOUR, hours.start)?;
+        self.set_setting(WORK_END_HOUR, hours.end)?;
+        Ok(())
+    }
+
+    /// Get the minutes without a running activity during the working hours before a reminder, 0 when the reminders are off. Defaults to 15.
+    pub fn reminder_minutes(&self) -> Result<u32, rusqlite::Error> {
+        Ok(self.setting(REMINDER_MINUTES)?.unwrap_or(15))
+    }
+
+    /**
+    Set the minutes without a running activity during the working hours before a reminder.
+
+    # Arguments
+    minutes - The time in minutes, 0 to turn the reminders off
+     */
+    pub fn set_reminder_minutes(&self, minutes: u32) -> Result<(), rusqlite::Error> {
+        self.set_setting(REMINDER_MINUTES, minutes)
+    }
+
+    /// Whether closing the window hides it in the system tray instead of quitting. Defaults to true.
+    pub fn close_to_tray(&self) -> Result<bool, rusqlite::Error> {
+        Ok(self.setting(CLOSE_TO_TRAY)?.unwrap_or(true))
+    }
+
+    /// Set whether closing the window hides it in the system tray instead of quitting
+    pub fn set_close_to_tray(&self, close_to_tray: bool) -> Result<(), rusqlite::Error> {
+        self.set_setting(CLOSE_TO_TRAY, close_to_tray)
+    }
+
+    /// Get the settings of the local HTTP API. Defaults to off on port 7463 without a token.
+    pub fn api_settings(&self) -> Result<ApiSettings, rusqlite::Error> {
+        Ok(ApiSettings {
+            enabled: self.setting(API_ENABLED)?.unwrap_or(false),
+            port: self.setting(API_PORT)?.unwrap_or(7463),
+            token: self.setting(API_TOKEN)?.unwrap_or_default(),
+        })
+    }
+
+    /**
+    Set the settings of the local HTTP API.
+
+    # Arguments
+    settings - Whether the API is served, its port, not 0, and its token, at least 16 characters while it is served
+     */
+    pub fn set_api_settings(&self, settings: &ApiSettings) -> Result<(), ActivitiesError> {
+        if settings.port == 0 {
+            return Err(ActivitiesError::InvalidSetting("The port of the API can't be 0".to_string()));
+        }
+        // The token can be empty until the API is turned on
+        if settings.enabled && settings.token.chars().count() < 16 {
+            return Err(ActivitiesError::InvalidSetting("The token of the API must be at least 16 characters long".to_string()));
+        }
+
+        self.set_setting(API_ENABLED, settings.enabled)?;
+        self.set_setting(API_PORT, settings.port)?;
+        self.set_setting(API_TOKEN, &settings.token)?;
+        Ok(())
+    }
+}
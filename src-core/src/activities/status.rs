@@ -0,0 +1,88 @@
+use serde::{Deserialize, Serialize};
+
+use super::{Activities, ActivitiesError};
+
+/**
+The state of the running activity, as sent to the local tools and shown in the status bars.
+
+# Fields
+activity - The name of the running activity if there is one
+tags - The tags of the running session without the `#`
+start_time - The time the running session started in seconds since the epoch
+elapsed - The time spent on the running session in seconds, without the breaks
+paused - Whether the running session is on a break
+today - The time spent on the sessions during the day in seconds
+time - The time of the status in seconds since the epoch, the times grow from there while the session runs
+ */
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Status {
+    pub activity: Option<String>,
+    pub tags: Vec<String>,
+    pub start_time: Option<u64>,
+    pub elapsed: u64,
+    pub paused: bool,
+    pub today: u64,
+    pub time: u64,
+}
+
+impl Status {
+    /// Whether the two statuses are of the same session in the same state, the times aside
+    pub fn same_session(&self, other: &Status) -> bool {
+        self.activity == other.activity
+            && self.tags == other.tags
+            && self.start_time == other.start_time
+            && self.paused == other.paused
+    }
+
+    /**
+    Get the status later on without reading the database again, the times grow while the session runs.
+
+    # Arguments
+    now - The current time in seconds since the epoch
+     */
+    pub fn at(&self, now: u64) -> Status {
+        let running = if self.activity.is_some() && !self.paused {
+            now.saturating_sub(self.time)
+        } else {
+            0
+        };
+
+        Status {
+            elapsed: self.elapsed + running,
+            today: self.today + running,
+            time: now.max(self.time),
+            ..self.clone()
+        }
+    }
+}
+
+impl Activities {
+    /**
+    Get the state of the running activity.
+
+    # Arguments
+    now - The current time in seconds since the epoch
+     */
+    pub fn status(&self, now: u64) -> Result<Status, ActivitiesError> {
+        // A session started before the day only counts from the start of the day
+        let day = self.calendar()?.day;
+        let today = self.sessions_between(day.start, day.end)?.iter().map(|session| session.duration(now)).sum();
+        let Some(session) = self.current_session()? else {
+            return Ok(Status {
+                today,
+                time: now,
+                ..Default::default()
+            });
+        };
+
+        Ok(Status {
+            activity: Some(session.name.clone()),
+            elapsed: session.duration(now),
+            start_time: Some(session.start_time),
+            tags: session.tags,
+            paused: self.is_paused()?,
+            today,
+            time: now,
+        })
+    }
+}
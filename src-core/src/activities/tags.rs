@@ -0,0 +1,111 @@
+use rusqlite::{params, Connection};
+
+use super::{Activities, ActivitiesError};
+
+/**
+Split the inline tags from an activity name.
+
+# Arguments
+input - The text typed by the user, the words starting with `#` are tags
+    Ex : "code review #meeting #billable"
+
+# Returns
+The name of the activity without the tags and the tags without the `#`
+    Ex : ("code review", ["meeting", "billable"])
+ */
+pub fn split_tags(input: &str) -> (String, Vec<String>) {
+    let mut name = Vec::new();
+    let mut tags = Vec::new();
+
+    for word in input.split_whitespace() {
+        match normalize_tag(word) {
+            Some(tag) if word.starts_with('#') => {
+                if !tags.contains(&tag) {
+                    tags.push(tag);
+                }
+            }
+            _ => name.push(word),
+        }
+    }
+
+    (name.join(" "), tags)
+}
+
+/// Remove the `#` and the spaces around a tag, None if nothing is left
+pub(super) fn normalize_tag(tag: &str) -> Option<String> {
+    let tag = tag.trim().trim_start_matches('#').trim();
+    if tag.is_empty() {
+        None
+    } else {
+        Some(tag.to_string())
+    }
+}
+
+impl Activities {
+    /// List all the tags used at least once, ordered by name
+    pub fn list_tags(&self) -> Result<Vec<String>, rusqlite::Error> {
+        let mut stmt = self.0.prepare(
+            "SELECT name FROM tags WHERE id IN (SELECT tag_id FROM session_tags) ORDER BY name",
+        )?;
+        let rows = stmt.query_map([], |row| row.get(0))?;
+
+        let mut tags = Vec::new();
+        for tag in rows {
+            tags.push(tag?);
+        }
+
+        Ok(tags)
+    }
+
+    /**
+    Replace the tags of a session.
+
+    # Arguments
+    id - The id of the session
+    tags - The new tags of the session, with or without the `#`
+
+    # Errors
+    Fails if the session doesn't exist or if a tag contains a space
+     */
+    pub fn set_session_tags(&self, id: i64, tags: &[String]) -> Result<(), ActivitiesError> {
+        self.session(id)?.ok_or(ActivitiesError::SessionNotFound(id))?;
+        let tags = normalize_tags(tags)?;
+
+        let tx = self.0.unchecked_transaction()?;
+        tx.execute("DELETE FROM session_tags WHERE session_id = ?", params![id])?;
+        insert_session_tags(&tx, id, &tags)?;
+        tx.commit()?;
+
+        Ok(())
+    }
+}
+
+/**
+Normalize the tags of a session.
+
+# Errors
+Fails if a tag contains a space
+ */
+pub(super) fn normalize_tags(tags: &[String]) -> Result<Vec<String>, ActivitiesError> {
+    let mut normalized = Vec::new();
+    for tag in tags {
+        let Some(tag) = normalize_tag(tag) else { continue; };
+        if tag.contains(char::is_whitespace) {
+            return Err(ActivitiesError::InvalidSession(format!("The tag \"{}\" contains a space", tag)));
+        }
+        normalized.push(tag);
+    }
+    Ok(normalized)
+}
+
+/// Add normalized tags to a session, inside the transaction of the caller
+pub(super) fn insert_session_tags(conn: &Connection, id: i64, tags: &[String]) -> Result<(), rusqlite::Error> {
+    for tag in tags {
+        conn.execute("INSERT INTO tags (name) VALUES (?) ON CONFLICT DO NOTHING", params![tag])?;
+        conn.execute(
+            "INSERT INTO session_tags (session_id, tag_id) SELECT ?, id FROM tags WHERE name = ? ON CONFLICT DO NOTHING",
+            params![id, tag],
+        )?;
+    }
+    Ok(())
+}
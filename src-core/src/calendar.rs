@@ -0,0 +1,220 @@
+use chrono::{Datelike, Days, Duration, Local, LocalResult, Months, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Timelike};
+use serde::Serialize;
+
+/// A span of time in seconds since the epoch, the start is included and the end is excluded
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct Period {
+    pub start: u64,
+    pub end: u64,
+}
+
+/**
+The logical day, week and month containing a point in time.
+
+The periods follow the local timezone and start at the configured day start hour instead of midnight, so a session
+at 01:30 can belong to the previous day.
+
+# Fields
+day - The logical day
+week - The logical week, starting on monday
+month - The logical month
+hours - The start of each local hour from 00:00 of the date of the day to the end of the day included, 25 values and
+    one more per day start hour
+    The hours are not always 3600 seconds apart on the days where the clocks change
+ */
+#[derive(Debug, Clone, Serialize)]
+pub struct Calendar {
+    pub day: Period,
+    pub week: Period,
+    pub month: Period,
+    pub hours: Vec<u64>,
+}
+
+/// Get the local date and time of a time in seconds since the epoch
+fn naive_local(timestamp: i64) -> Option<NaiveDateTime> {
+    Local.timestamp_opt(timestamp, 0).single().map(|local| local.naive_local())
+}
+
+/**
+Convert a local date and time to seconds since the epoch.
+
+When the clocks go back the time exists twice and the earliest is used.
+When the clocks go forward the time doesn't exist and the first existing time after it is used.
+ */
+pub fn local_timestamp(datetime: NaiveDateTime) -> u64 {
+    let mut datetime = datetime;
+    loop {
+        let guesses = match Local.from_local_datetime(&datetime) {
+            LocalResult::Single(local) => vec![local.timestamp()],
+            LocalResult::Ambiguous(first, second) => vec![first.timestamp(), second.timestamp()],
+            LocalResult::None => vec![],
+        };
+        // chrono can be an hour off at the time the clocks change, the guesses are moved by the difference with the
+        // local time they really have and kept if they land on the time asked for
+        let timestamp = guesses
+            .into_iter()
+            .filter_map(|guess| {
+                let timestamp = guess + (datetime - naive_local(guess)?).num_seconds();
+                (naive_local(timestamp)? == datetime).then_some(timestamp)
+            })
+            .min();
+        if let Some(timestamp) = timestamp {
+            return timestamp.max(0) as u64;
+        }
+        // The clock changes are done in minute increments, so we will land on an existing time
+        datetime += Duration::minutes(1);
+    }
+}
+
+/**
+Get the date of the logical day containing a time in seconds since the epoch.
+
+# Arguments
+time - The time in seconds since the epoch
+day_start_hour - The local hour at which the days start, the times before it belong to the previous day
+ */
+fn logical_date(time: u64, day_start_hour: u32) -> NaiveDate {
+    let local = Local
+        .timestamp_opt(time as i64, 0)
+        .earliest()
+        .expect("A timestamp always has a local time");
+    let date = local.date_naive();
+
+    if local.hour() < day_start_hour {
+        date - Days::new(1)
+    } else {
+        date
+    }
+}
+
+/// Get the period between the start of the logical days of two dates
+fn period_between(start: NaiveDate, end: NaiveDate, day_start_hour: u32) -> Period {
+    let day_start = NaiveTime::from_hms_opt(day_start_hour, 0, 0).expect("The day start hour is a valid hour");
+    Period {
+        start: local_timestamp(start.and_time(day_start)),
+        end: local_timestamp(end.and_time(day_start)),
+    }
+}
+
+/// Get the logical day containing the time in seconds since the epoch
+pub fn day_at(time: u64, day_start_hour: u32) -> Period {
+    let date = logical_date(time, day_start_hour);
+    period_between(date, date + Days::new(1), day_start_hour)
+}
+
+/// Get the logical week, starting on monday, containing the time in seconds since the epoch
+pub fn week_at(time: u64, day_start_hour: u32) -> Period {
+    let date = logical_date(time, day_start_hour);
+    let monday = date - Days::new(date.weekday().num_days_from_monday() as u64);
+    period_between(monday, monday + Days::new(7), day_start_hour)
+}
+
+/// Get the logical month containing the time in seconds since the epoch
+pub fn month_at(time: u64, day_start_hour: u32) -> Period {
+    let date = logical_date(time, day_start_hour);
+    let first = date.with_day(1).expect("Every month has a first day");
+    period_between(first, first + Months::new(1), day_start_hour)
+}
+
+/**
+Get the logical day, week and month containing the time in seconds since the epoch.
+
+# Arguments
+time - The time in seconds since the epoch
+day_start_hour - The local hour at which the days start, the times before it belong to the previous day
+ */
+pub fn calendar_at(time: u64, day_start_hour: u32) -> Calendar {
+    let midnight = logical_date(time, day_start_hour).and_time(NaiveTime::MIN);
+    // The day runs past midnight when it starts later
+    let hours = (0..=24 + day_start_hour as i64)
+        .map(|hour| local_timestamp(midnight + Duration::hours(hour)))
+        .collect();
+
+    Calendar {
+        day: day_at(time, day_start_hour),
+        week: week_at(time, day_start_hour),
+        month: month_at(time, day_start_hour),
+        hours,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Use the timezone of Paris, written as a rule so it doesn't depend on the timezone database of the system
+    ///
+    /// Every test sets the same timezone so they can run at the same time
+    fn paris() {
+        std::env::set_var("TZ", "CET-1CEST,M3.5.0,M10.5.0/3");
+    }
+
+    fn datetime(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap().and_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn dst_gap_day() {
+        paris();
+
+        // 02:30 doesn't exist on the 31st of March 2024, the clocks go from 02:00 to 03:00
+        assert_eq!(local_timestamp(datetime(2024, 3, 31, 2, 30)), 1711846800);
+
+        let calendar = calendar_at(1711846800, 0);
+        assert_eq!((calendar.day.start, calendar.day.end), (1711839600, 1711922400));
+        assert_eq!(calendar.day.end - calendar.day.start, 23 * 3600);
+        assert_eq!(calendar.hours.len(), 25);
+        assert_eq!(calendar.hours[2], calendar.hours[3]);
+        assert_eq!(calendar.hours[24], calendar.day.end);
+    }
+
+    #[test]
+    fn dst_overlap_day() {
+        paris();
+
+        // 02:30 happens twice on the 27th of October 2024, the earliest is used
+        assert_eq!(local_timestamp(datetime(2024, 10, 27, 2, 30)), 1729989000);
+
+        let calendar = calendar_at(1729989000, 0);
+        assert_eq!((calendar.day.start, calendar.day.end), (1729980000, 1730070000));
+        assert_eq!(calendar.day.end - calendar.day.start, 25 * 3600);
+        // 02:00 to 03:00 lasts two hours
+        assert_eq!(calendar.hours[3] - calendar.hours[2], 2 * 3600);
+    }
+
+    #[test]
+    fn period_between_with_day_start_hour() {
+        paris();
+
+        let period = period_between(date(2024, 1, 15), date(2024, 1, 16), 4);
+        assert_eq!((period.start, period.end), (1705287600, 1705374000));
+
+        // The day starting at 04:00 is 23 hours long when the clocks go forward during the night before
+        let period = period_between(date(2024, 3, 30), date(2024, 3, 31), 4);
+        assert_eq!((period.start, period.end), (1711767600, 1711850400));
+        assert_eq!(period.end - period.start, 23 * 3600);
+    }
+
+    #[test]
+    fn calendar_at_with_day_start_hour() {
+        paris();
+
+        // 01:30 on tuesday the 16th of January 2024 belongs to monday with days starting at 04:00
+        let calendar = calendar_at(1705365000, 4);
+        assert_eq!((calendar.day.start, calendar.day.end), (1705287600, 1705374000));
+        assert_eq!((calendar.week.start, calendar.week.end), (1705287600, 1705892400));
+        assert_eq!((calendar.month.start, calendar.month.end), (1704078000, 1706756400));
+        // The hours are the ones of the date of the logical day, from its midnight to its end
+        assert_eq!(calendar.hours[4], calendar.day.start);
+        assert_eq!(calendar.hours.len(), 29);
+        assert_eq!(calendar.hours[28], calendar.day.end);
+
+        // With days starting at midnight it is tuesday
+        let calendar = calendar_at(1705365000, 0);
+        assert_eq!(calendar.day.start, 1705359600);
+    }
+}
@@ -0,0 +1,42 @@
+/*!
+The activities store shared by the desktop application and the command line client.
+
+Both open the same database with `activities::Activities::open(&database_path()?)` so they work on one history, the
+desktop application also answers the local tools on `socket_path()`.
+ */
+use std::path::PathBuf;
+
+pub mod activities;
+pub mod calendar;
+pub mod protocol;
+
+/// Get the directories of the application
+fn project_dirs() -> std::io::Result<directories::ProjectDirs> {
+    directories::ProjectDirs::from("fr", "cideco", "activity-tracker")
+        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "No home directory"))
+}
+
+/// Get the path of the database in the local data directory of the user, the directory is created if needed
+pub fn database_path() -> std::io::Result<PathBuf> {
+    let mut data = project_dirs()?.data_local_dir().to_owned();
+    if !data.exists() {
+        std::fs::create_dir_all(&data)?;
+    }
+    data.push("activity-tracker.db");
+    Ok(data)
+}
+
+/**
+Get the path of the socket the desktop application listens on for the local tools.
+
+It is in the runtime directory of the user when there is one, next to the database otherwise.
+ */
+pub fn socket_path() -> std::io::Result<PathBuf> {
+    let dirs = project_dirs()?;
+    let mut path = dirs.runtime_dir().unwrap_or(dirs.data_local_dir()).to_owned();
+    if !path.exists() {
+        std::fs::create_dir_all(&path)?;
+    }
+    path.push("activity-tracker.sock");
+    Ok(path)
+}
@@ -0,0 +1,52 @@
+/*!
+The protocol of the socket the desktop application listens on, see `socket_path()`.
+
+Each line sent to the socket is a JSON `Request` and each line received is a JSON `Message`, Ex :
+```text
+> {"command": "start", "activity": "code review #meeting", "offset": -900}
+< {"kind": "ok", "status": {"activity": "code review", "tags": ["meeting"], ...}}
+> {"command": "subscribe"}
+< {"kind": "ok", "status": {...}}
+< {"kind": "changed", "status": {...}}
+```
+ */
+use serde::{Deserialize, Serialize};
+
+use crate::activities::Status;
+
+/// A request to the desktop application, one per line
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum Request {
+    /// Start an activity, the words starting with `#` are tags, the running one is stopped
+    Start {
+        activity: String,
+        /// The offset of the start in seconds from now
+        #[serde(default)]
+        offset: i64,
+    },
+    /// Stop the running activity
+    Stop {
+        /// The offset of the stop in seconds from now
+        #[serde(default)]
+        offset: i64,
+        #[serde(default)]
+        notes: Option<String>,
+    },
+    /// Get the status of the running activity
+    Status,
+    /// Get the status and be sent a `changed` message each time the running activity changes
+    Subscribe,
+}
+
+/// A message from the desktop application, one per line
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Message {
+    /// The request succeeded, with the status after it
+    Ok { status: Status },
+    /// The request failed
+    Error { message: String },
+    /// The running activity changed, only sent to the subscribers
+    Changed { status: Status },
+}
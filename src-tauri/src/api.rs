@@ -0,0 +1,285 @@
+use std::{error::Error, io::Read, sync::{Arc, Mutex}, thread};
+
+use log::{error, info};
+use rand::{distributions::Alphanumeric, Rng};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tauri::{AppHandle, Manager};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use crate::activities::{split_tags, Activities, ActivitiesError, ApiSettings, Grouping, Rounding};
+
+/// The largest request body read, the requests only carry a few fields
+const MAX_BODY: u64 = 64 * 1024;
+
+/// The body of `POST /start`, the same arguments as the `start_activity` command
+#[derive(Debug, Deserialize)]
+struct StartRequest {
+    activity: String,
+    #[serde(default)]
+    offset: i64,
+}
+
+/// The body of `POST /stop`, the same arguments as the `stop_activity` command
+#[derive(Debug, Default, Deserialize)]
+struct StopRequest {
+    #[serde(default)]
+    offset: i64,
+    notes: Option<String>,
+}
+
+/// A failed request with its HTTP status, sent as `{"error": message}`
+#[derive(Debug)]
+struct ApiError {
+    status: u16,
+    message: String,
+}
+
+impl ApiError {
+    fn new(status: u16, message: impl Into<String>) -> Self {
+        ApiError {
+            status,
+            message: message.into(),
+        }
+    }
+}
+
+/// The errors of the activities are internal errors
+impl<E: Error> From<E> for ApiError {
+    fn from(e: E) -> Self {
+        ApiError::new(500, e.to_string())
+    }
+}
+
+/**
+The local HTTP API, serving the operations of the window as JSON so scripts and other tools can start and stop the
+activities.
+
+The server only listens on the loopback interface and every request must carry the token of the settings as
+`Authorization: Bearer <token>`. The endpoints are :
+- `POST /start` with `{"activity": "code review #meeting", "offset": 0}`
+- `POST /stop` with `{"offset": 0, "notes": "..."}`, the body is optional
+- `GET /current`, the name of the running activity or an empty string
+- `GET /times?grouping=tag&tag=billable`, the time of each activity, the query is optional
+- `GET /today`, the sessions of the day
+
+The window is told about the starts and stops with an `activity` event carrying the name of the running activity.
+
+# Fields
+server - The port and the server while it runs
+ */
+#[derive(Default)]
+pub struct ApiServer {
+    server: Option<(u16, Arc<Server>)>,
+}
+
+impl ApiServer {
+    /**
+    Start, stop or move the server to follow the settings. The token is read for each request so changing it doesn't
+    restart the server.
+
+    # Arguments
+    app - The application the requests are served for
+    settings - The settings of the API
+
+    # Errors
+    Fails if the port can't be listened on, the server is stopped then
+     */
+    pub fn apply(&mut self, app: &AppHandle, settings: &ApiSettings) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let port = settings.enabled.then_some(settings.port);
+        if self.server.as_ref().map(|(port, _)| *port) == port {
+            return Ok(());
+        }
+
+        // The thread stops and closes the socket once unblocked
+        if let Some((_, server)) = self.server.take() {
+            server.unblock();
+        }
+        let Some(port) = port else {
+            info!("HTTP API stopped");
+            return Ok(());
+        };
+
+        let server = Arc::new(Server::http(("127.0.0.1", port))?);
+        self.server = Some((port, server.clone()));
+        info!("HTTP API listening on 127.0.0.1:{}", port);
+
+        let app = app.clone();
+        thread::spawn(move || {
+            for request in server.incoming_requests() {
+                handle(&app, request);
+            }
+        });
+        Ok(())
+    }
+}
+
+/// Generate a random token for the API
+pub fn generate_token() -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(32)
+        .map(char::from)
+        .collect()
+}
+
+/**
+Get the settings of the API, a token is generated the first time so there is always one to show.
+
+# Arguments
+activities - The activities holding the settings
+ */
+pub fn settings(activities: &Activities) -> Result<ApiSettings, ActivitiesError> {
+    let mut settings = activities.api_settings()?;
+    if settings.token.is_empty() {
+        settings.token = generate_token();
+        activities.set_api_settings(&settings)?;
+    }
+    Ok(settings)
+}
+
+/// Start the server if it is enabled in the settings
+pub fn spawn(app: AppHandle) {
+    // The activities are always locked before the server
+    let activities = app.state::<Mutex<Activities>>();
+    let activities = activities.lock().unwrap();
+    let settings = match settings(&activities) {
+        Ok(settings) => settings,
+        Err(e) => {
+            error!("Failed to read the settings of the HTTP API: {}", e);
+            return;
+        }
+    };
+    drop(activities);
+
+    let server = app.state::<Mutex<ApiServer>>();
+    let res = server.lock().unwrap().apply(&app, &settings);
+    if let Err(e) = res {
+        error!("Failed to start the HTTP API: {}", e);
+    }
+}
+
+/// Answer a request, the errors are sent back as JSON
+fn handle(app: &AppHandle, mut request: Request) {
+    let (status, body) = match respond(app, &mut request) {
+        Ok(body) => (200, body),
+        Err(e) => (e.status, json!({ "error": e.message })),
+    };
+
+    let header = Header::from_bytes("Content-Type", "application/json").expect("The header is valid");
+    let response = Response::from_string(body.to_string())
+        .with_status_code(status)
+        .with_header(header);
+    if let Err(e) = request.respond(response) {
+        error!("Failed to answer an HTTP API request: {}", e);
+    }
+}
+
+/// Compare the tokens in a time independent of where they differ
+fn same_token(a: &str, b: &str) -> bool {
+    a.len() == b.len() && a.bytes().zip(b.bytes()).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+/// Read the JSON body of a request, an empty body is read as `null`
+fn read_body<T: for<'de> Deserialize<'de>>(request: &mut Request) -> Result<T, ApiError> {
+    let mut body = String::new();
+    request
+        .as_reader()
+        .take(MAX_BODY)
+        .read_to_string(&mut body)
+        .map_err(|e| ApiError::new(400, e.to_string()))?;
+    let body = if body.trim().is_empty() { "null" } else { &body };
+
+    serde_json::from_str(body).map_err(|e| ApiError::new(400, format!("Invalid body: {}", e)))
+}
+
+fn respond(app: &AppHandle, request: &mut Request) -> Result<Value, ApiError> {
+    let activities = app.state::<Mutex<Activities>>();
+
+    let authorization = request
+        .headers()
+        .iter()
+        .find(|header| header.field.equiv("Authorization"))
+        .map(|header| header.value.as_str().to_string());
+    let token = activities.lock().unwrap().api_settings()?.token;
+    let authorized = authorization
+        .as_deref()
+        .and_then(|authorization| authorization.strip_prefix("Bearer "))
+        .is_some_and(|given| !token.is_empty() && same_token(given, &token));
+    if !authorized {
+        return Err(ApiError::new(401, "Missing or wrong token"));
+    }
+
+    let url = request.url().to_string();
+    let (path, query) = url.split_once('?').unwrap_or((&url, ""));
+    match (request.method(), path) {
+        (Method::Post, "/start") => {
+            let start: StartRequest = read_body(request)?;
+            let activities = activities.lock().unwrap();
+
+            let (name, tags) = split_tags(&start.activity);
+            activities.start_activity(&name, &tags, start.offset).map_err(|e| match e {
+                ActivitiesError::InvalidSession(_) => ApiError::new(400, e.to_string()),
+                e => e.into(),
+            })?;
+            drop(activities);
+
+            notify(app, name);
+            Ok(Value::Null)
+        }
+        (Method::Post, "/stop") => {
+            let stop: Option<StopRequest> = read_body(request)?;
+            let stop = stop.unwrap_or_default();
+            let activities = activities.lock().unwrap();
+
+            if stop.notes.is_some() {
+                activities.set_current_notes(stop.notes.as_deref())?;
+            }
+            activities.stop_activity(stop.offset)?;
+            drop(activities);
+
+            notify(app, String::new());
+            Ok(Value::Null)
+        }
+        (Method::Get, "/current") => {
+            let activities = activities.lock().unwrap();
+
+            let current = activities.currrent_activity()?.map(|(name, _)| name).unwrap_or_default();
+            Ok(json!(current))
+        }
+        (Method::Get, "/times") => {
+            let mut grouping = Grouping::default();
+            let mut tag = None;
+            for (key, value) in form_urlencoded::parse(query.as_bytes()) {
+                match &*key {
+                    "grouping" => {
+                        grouping = serde_json::from_value(json!(value))
+                            .map_err(|_| ApiError::new(400, format!("Unknown grouping \"{}\"", value)))?;
+                    }
+                    "tag" => tag = Some(value.into_owned()),
+                    _ => {}
+                }
+            }
+            let activities = activities.lock().unwrap();
+
+            let rounding = activities.rounding()?;
+            let rounding = if rounding.statistics { rounding } else { Rounding::default() };
+            let times: Vec<(String, u64)> = activities.activities_times(grouping, tag.as_deref(), rounding)?.into_iter().collect();
+            Ok(json!(times))
+        }
+        (Method::Get, "/today") => {
+            let activities = activities.lock().unwrap();
+
+            Ok(json!(activities.todays_activities()?))
+        }
+        (_, "/start" | "/stop" | "/current" | "/times" | "/today") => Err(ApiError::new(405, "Method not allowed")),
+        _ => Err(ApiError::new(404, format!("Unknown endpoint {}", path))),
+    }
+}
+
+/// Tell the window the running activity changed
+fn notify(app: &AppHandle, activity: String) {
+    if let Err(e) = app.emit_all("activity", activity) {
+        error!("Failed to send the activity change: {}", e);
+    }
+}
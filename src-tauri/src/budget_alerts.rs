@@ -0,0 +1,165 @@
+use std::{
+    collections::HashSet,
+    sync::Mutex,
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use log::error;
+use serde::Serialize;
+use tauri::{api::notification::Notification, AppHandle, Manager};
+
+use crate::{
+    activities::{Activities, ActivitiesError},
+    calendar,
+};
+
+/// How often the budgets are checked
+const POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+/// The part of a budget after which it is about to be exceeded
+const WARNING_RATIO: f64 = 0.9;
+
+/// The period a budget is for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetPeriod {
+    Daily,
+    Weekly,
+}
+
+/// Sent when a budget is about to be exceeded or is exceeded
+#[derive(Debug, Clone, Serialize)]
+pub struct BudgetAlert {
+    /// The name of the activity
+    pub name: String,
+    pub period: BudgetPeriod,
+    /// The budget in seconds
+    pub budget: u64,
+    /// The time spent in seconds
+    pub used: u64,
+    /// Whether the budget is exceeded, otherwise it is about to be
+    pub exceeded: bool,
+}
+
+impl BudgetAlert {
+    fn message(&self) -> String {
+        let period = match self.period {
+            BudgetPeriod::Daily => "daily",
+            BudgetPeriod::Weekly => "weekly",
+        };
+        let state = if self.exceeded { "exceeded" } else { "almost exceeded" };
+        format!(
+            "The {} budget of {} is {} ({}h{:02} of {}h{:02})",
+            period,
+            self.name,
+            state,
+            self.used / 3600,
+            self.used % 3600 / 60,
+            self.budget / 3600,
+            self.budget % 3600 / 60
+        )
+    }
+}
+
+/**
+Alerts the user when the budget of an activity is about to be exceeded and when it is exceeded.
+
+# Fields
+sent - The alerts already sent, by activity, period, start of the period and whether the budget was exceeded
+ */
+#[derive(Debug, Default)]
+pub struct BudgetAlerts {
+    sent: HashSet<(String, BudgetPeriod, u64, bool)>,
+}
+
+impl BudgetAlerts {
+    /**
+    Check the budgets and get the alerts that were not sent yet during the current day and week.
+
+    # Arguments
+    now - The current time in seconds since the epoch
+     */
+    pub fn check(&mut self, activities: &Activities, now: u64) -> Result<Vec<BudgetAlert>, ActivitiesError> {
+        let day_start_hour = activities.day_start_hour()?;
+        let day = calendar::day_at(now, day_start_hour);
+        let week = calendar::week_at(now, day_start_hour);
+
+        // Forget the alerts of the previous periods
+        self.sent.retain(|(_, period, start, _)| match period {
+            BudgetPeriod::Daily => *start == day.start,
+            BudgetPeriod::Weekly => *start == week.start,
+        });
+
+        let mut alerts = Vec::new();
+        for usage in activities.budget_usage(now)? {
+            let periods = [
+                (BudgetPeriod::Daily, usage.budget.daily, usage.today, day.start),
+                (BudgetPeriod::Weekly, usage.budget.weekly, usage.this_week, week.start),
+            ];
+            for (period, budget, used, start) in periods {
+                let Some(budget) = budget else { continue; };
+                if (used as f64) < budget as f64 * WARNING_RATIO {
+                    continue;
+                }
+
+                let exceeded = used >= budget;
+                if self.sent.insert((usage.budget.name.clone(), period, start, exceeded)) {
+                    alerts.push(BudgetAlert {
+                        name: usage.budget.name.clone(),
+                        period,
+                        budget,
+                        used,
+                        exceeded,
+                    });
+                }
+            }
+        }
+
+        Ok(alerts)
+    }
+}
+
+/**
+Start the thread checking the budgets.
+
+Each alert is a desktop notification along with a `budget` event carrying the `BudgetAlert`, so the UI can show it too.
+ */
+pub fn spawn(app: AppHandle) {
+    thread::spawn(move || loop {
+        thread::sleep(POLL_INTERVAL);
+
+        let now = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+
+        // The activities are always locked before the budget alerts
+        let activities = app.state::<Mutex<Activities>>();
+        let activities = activities.lock().unwrap();
+        let budget_alerts = app.state::<Mutex<BudgetAlerts>>();
+        let alerts = budget_alerts.lock().unwrap().check(&activities, now);
+        drop(activities);
+
+        let alerts = match alerts {
+            Ok(alerts) => alerts,
+            Err(e) => {
+                error!("Budget alerts error: {}", e);
+                continue;
+            }
+        };
+
+        for alert in alerts {
+            let notification = Notification::new(&app.config().tauri.bundle.identifier)
+                .title("Time budget")
+                .body(alert.message())
+                .show();
+            if let Err(e) = notification {
+                error!("Failed to show the budget alert: {}", e);
+            }
+            if let Err(e) = app.emit_all("budget", alert) {
+                error!("Failed to send the budget alert: {}", e);
+            }
+        }
+    });
+}
@@ -0,0 +1,205 @@
+use std::collections::HashMap;
+
+use chrono::{Local, TimeZone};
+use serde::Deserialize;
+
+use crate::activities::{Grouping, Rounding, RoundingScope, Session};
+
+/// What each row of the export is
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportLayout {
+    /// A row per session
+    Sessions,
+    /// A row per activity, project, client or tag with the total time
+    #[default]
+    Totals,
+}
+
+/// How the durations are written
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DurationFormat {
+    /// Hours and minutes, Ex : 1:30
+    #[default]
+    HoursMinutes,
+    /// Hours with two decimals, Ex : 1.50
+    Decimal,
+}
+
+/// A column of the sessions layout
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionColumn {
+    Activity,
+    Project,
+    Client,
+    Tags,
+    Start,
+    End,
+    Duration,
+    Notes,
+    Billable,
+}
+
+impl SessionColumn {
+    /// The columns exported when none are chosen
+    const DEFAULT: [SessionColumn; 5] = [
+        SessionColumn::Activity,
+        SessionColumn::Start,
+        SessionColumn::End,
+        SessionColumn::Duration,
+        SessionColumn::Notes,
+    ];
+}
+
+/**
+What to export and how.
+
+# Fields
+layout - A row per session or per group
+grouping - What the totals are summed by, unused by the sessions layout
+tag - Only export the sessions with this tag if set
+from - The start of the exported range in seconds since the epoch, the sessions since the last clear are exported without a range
+to - The end of the exported range in seconds since the epoch (excluded)
+duration_format - How the durations are written
+columns - The columns of the sessions layout in order, unused by the totals layout
+ */
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ExportOptions {
+    pub layout: ExportLayout,
+    pub grouping: Grouping,
+    pub tag: Option<String>,
+    pub from: Option<u64>,
+    pub to: Option<u64>,
+    pub duration_format: DurationFormat,
+    pub columns: Vec<SessionColumn>,
+}
+
+impl ExportOptions {
+    /// The range of the export, None when the sessions since the last clear are exported
+    pub fn range(&self) -> Option<(u64, u64)> {
+        Some((self.from?, self.to?))
+    }
+}
+
+/// Format a time in seconds since the epoch as a local date and time
+fn datetime(time: u64) -> String {
+    Local
+        .timestamp_opt(time as i64, 0)
+        .earliest()
+        .expect("A timestamp always has a local time")
+        .format("%Y-%m-%d %H:%M")
+        .to_string()
+}
+
+/// Format a duration in seconds
+fn duration(seconds: u64, format: DurationFormat) -> String {
+    match format {
+        DurationFormat::HoursMinutes => format!("{}:{:02}", seconds / 3600, seconds % 3600 / 60),
+        DurationFormat::Decimal => format!("{:.2}", seconds as f64 / 3600.0),
+    }
+}
+
+/// The header of a duration column, it states the format and the rounding of the durations
+fn duration_header(name: &str, format: DurationFormat, rounding: &Rounding) -> String {
+    let unit = match format {
+        DurationFormat::HoursMinutes => "h:mm",
+        DurationFormat::Decimal => "hours",
+    };
+    format!("{} ({}, {})", name, unit, rounding.describe().to_lowercase())
+}
+
+/**
+Write sessions as a CSV file.
+
+# Arguments
+sessions - The sessions to export, already filtered by tag and range
+options - The layout and formats of the export
+rounding - How the durations are rounded
+now - The current time in seconds since the epoch, used for the running session
+
+# Returns
+The content of the CSV file
+ */
+pub fn render_csv(sessions: Vec<Session>, options: &ExportOptions, rounding: Rounding, now: u64) -> Result<Vec<u8>, csv::Error> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+
+    match options.layout {
+        ExportLayout::Sessions => {
+            // Rounding the totals doesn't apply to the sessions one by one
+            let rounding = match rounding.scope {
+                RoundingScope::Session => rounding,
+                RoundingScope::Total => Rounding::default(),
+            };
+            let columns = if options.columns.is_empty() { &SessionColumn::DEFAULT[..] } else { &options.columns[..] };
+
+            let header: Vec<String> = columns
+                .iter()
+                .map(|column| match column {
+                    SessionColumn::Activity => "Activity".to_string(),
+                    SessionColumn::Project => "Project".to_string(),
+                    SessionColumn::Client => "Client".to_string(),
+                    SessionColumn::Tags => "Tags".to_string(),
+                    SessionColumn::Start => "Start".to_string(),
+                    SessionColumn::End => "End".to_string(),
+                    SessionColumn::Duration => duration_header("Duration", options.duration_format, &rounding),
+                    SessionColumn::Notes => "Notes".to_string(),
+                    SessionColumn::Billable => "Billable".to_string(),
+                })
+                .collect();
+            writer.write_record(&header)?;
+
+            for session in sessions {
+                let seconds = rounding.round(session.duration(now));
+                let row: Vec<String> = columns
+                    .iter()
+                    .map(|column| match column {
+                        SessionColumn::Activity => session.name.clone(),
+                        SessionColumn::Project => session.project.clone(),
+                        SessionColumn::Client => session.client.clone().unwrap_or_default(),
+                        SessionColumn::Tags => session.tags.iter().map(|tag| format!("#{}", tag)).collect::<Vec<_>>().join(" "),
+                        SessionColumn::Start => datetime(session.start_time),
+                        // The running session has no end yet
+                        SessionColumn::End => session.end_time.map(datetime).unwrap_or_default(),
+                        SessionColumn::Duration => duration(seconds, options.duration_format),
+                        SessionColumn::Notes => session.notes.clone().unwrap_or_default(),
+                        SessionColumn::Billable => if session.billable { "yes" } else { "no" }.to_string(),
+                    })
+                    .collect();
+                writer.write_record(&row)?;
+            }
+        }
+        ExportLayout::Totals => {
+            let mut totals: Vec<(String, u64, Vec<String>)> = Vec::new();
+            let mut indices: HashMap<String, usize> = HashMap::new();
+            for session in sessions {
+                let seconds = rounding.round_session(session.duration(now));
+                for key in options.grouping.keys(&session) {
+                    let index = *indices.entry(key.clone()).or_insert_with(|| {
+                        totals.push((key, 0, Vec::new()));
+                        totals.len() - 1
+                    });
+                    totals[index].1 += seconds;
+                    totals[index].2.extend(session.notes.clone());
+                }
+            }
+            for total in totals.iter_mut() {
+                total.1 = rounding.round_total(total.1);
+            }
+            totals.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+            writer.write_record([
+                options.grouping.label().to_string(),
+                duration_header("Time", options.duration_format, &rounding),
+                "Notes".to_string(),
+            ])?;
+            for (name, seconds, notes) in totals {
+                writer.write_record([name, duration(seconds, options.duration_format), notes.join("; ")])?;
+            }
+        }
+    }
+
+    writer.into_inner().map_err(|e| e.into_error().into())
+}
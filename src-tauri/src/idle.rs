@@ -0,0 +1,338 @@
+use std::{
+    process::Command,
+    sync::Mutex,
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use log::error;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::activities::Activities;
+
+/// How often the idle time is checked
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Gives the time since the last input of the user
+pub trait IdleSource: Send + 'static {
+    /// The time since the last keyboard or mouse input in seconds, None if it can't be known
+    fn idle_time(&self) -> Option<u64>;
+}
+
+/**
+The idle time of the desktop session.
+
+- Linux - `xprintidle` on X11, the Mutter idle monitor on GNOME
+- macOS - The HID idle time given by `ioreg`
+- Windows - `GetLastInputInfo`
+ */
+pub struct SystemIdle;
+
+/// Run a command and get its standard output if it succeeded
+#[allow(dead_code)]
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8(output.stdout).ok()
+}
+
+impl IdleSource for SystemIdle {
+    #[cfg(target_os = "linux")]
+    fn idle_time(&self) -> Option<u64> {
+        // Both give milliseconds, the Mutter one is formatted as "(uint64 1234,)"
+        let milliseconds = command_output("xprintidle", &[])
+            .and_then(|output| output.trim().parse::<u64>().ok())
+            .or_else(|| {
+                let output = command_output(
+                    "gdbus",
+                    &[
+                        "call",
+                        "--session",
+                        "--dest",
+                        "org.gnome.Mutter.IdleMonitor",
+                        "--object-path",
+                        "/org/gnome/Mutter/IdleMonitor/Core",
+                        "--method",
+                        "org.gnome.Mutter.IdleMonitor.GetIdletime",
+                    ],
+                )?;
+                output.trim().trim_start_matches("(uint64 ").trim_end_matches(",)").parse().ok()
+            })?;
+        Some(milliseconds / 1000)
+    }
+
+    #[cfg(target_os = "macos")]
+    fn idle_time(&self) -> Option<u64> {
+        // The line looks like `"HIDIdleTime" = 1234567890` in nanoseconds
+        let output = command_output("ioreg", &["-c", "IOHIDSystem", "-d", "4"])?;
+        let line = output.lines().find(|line| line.contains("\"HIDIdleTime\""))?;
+        let nanoseconds: u64 = line.split('=').nth(1)?.trim().parse().ok()?;
+        Some(nanoseconds / 1_000_000_000)
+    }
+
+    #[cfg(windows)]
+    fn idle_time(&self) -> Option<u64> {
+        use windows_sys::Win32::{
+            System::SystemInformation::GetTickCount,
+            UI::Input::KeyboardAndMouse::{GetLastInputInfo, LASTINPUTINFO},
+        };
+
+        let mut info = LASTINPUTINFO {
+            cbSize: std::mem::size_of::<LASTINPUTINFO>() as u32,
+            dwTime: 0,
+        };
+        // SAFETY: info is a valid LASTINPUTINFO with its size set
+        if unsafe { GetLastInputInfo(&mut info) } == 0 {
+            return None;
+        }
+        // SAFETY: GetTickCount has no preconditions
+        let now = unsafe { GetTickCount() };
+        // The tick count wraps around every 49 days
+        Some(now.wrapping_sub(info.dwTime) as u64 / 1000)
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+    fn idle_time(&self) -> Option<u64> {
+        None
+    }
+}
+
+/// A time the user was away while a session was running
+#[derive(Debug, Clone, Serialize)]
+pub struct IdlePeriod {
+    /// The session that was running
+    pub session_id: i64,
+    /// The name of the activity of the session
+    pub activity: String,
+    /// The time of the last input before leaving in seconds since the epoch
+    pub start: u64,
+    /// The time of the first input after coming back in seconds since the epoch
+    pub end: u64,
+}
+
+/// What to do with the idle time
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum IdleResolution {
+    /// Count the idle time toward the session
+    Keep,
+    /// Remove the idle time from the session
+    Discard,
+    /// Move the idle time to another activity
+    Reassign { name: String },
+}
+
+/**
+Notices when the user leaves and comes back while a session is running.
+
+# Fields
+away - The session running and the time the user left if they are away
+pending - The last idle time waiting for the user to decide what to do with it
+ */
+#[derive(Debug, Default)]
+pub struct IdleDetector {
+    away: Option<(i64, String, u64)>,
+    pending: Option<IdlePeriod>,
+}
+
+impl IdleDetector {
+    /**
+    Update the detector with the current idle time.
+
+    # Arguments
+    now - The current time in seconds since the epoch
+    idle_time - The time since the last input in seconds
+    threshold - The idle time in seconds after which the user is away, 0 to turn the detection off
+    session - The id and name of the running session if it isn't paused
+
+    # Returns
+    The idle time when the user comes back after being away
+     */
+    pub fn update(&mut self, now: u64, idle_time: u64, threshold: u64, session: Option<(i64, String)>) -> Option<IdlePeriod> {
+        if threshold == 0 {
+            self.away = None;
+            return None;
+        }
+
+        match self.away.take() {
+            None => {
+                if idle_time >= threshold {
+                    self.away = session.map(|(id, name)| (id, name, now.saturating_sub(idle_time)));
+                }
+                None
+            }
+            Some(away) if idle_time >= threshold => {
+                self.away = Some(away);
+                None
+            }
+            Some((session_id, activity, start)) => {
+                let period = IdlePeriod {
+                    session_id,
+                    activity,
+                    start,
+                    end: now.saturating_sub(idle_time),
+                };
+                self.pending = Some(period.clone());
+                Some(period)
+            }
+        }
+    }
+
+    /// The last idle time waiting for a decision
+    pub fn pending(&self) -> Option<IdlePeriod> {
+        self.pending.clone()
+    }
+
+    /**
+    Apply the decision of the user on the pending idle time.
+
+    # Errors
+    Fails if there is no pending idle time or if the session changed since
+     */
+    pub fn resolve(&mut self, activities: &Activities, resolution: IdleResolution) -> Result<(), String> {
+        let Some(period) = self.pending.take() else {
+            return Err("No idle time to resolve".to_string());
+        };
+
+        let res = match resolution {
+            IdleResolution::Keep => Ok(()),
+            IdleResolution::Discard => activities.discard_idle(period.session_id, period.start, period.end),
+            IdleResolution::Reassign { name } => activities
+                .reassign_idle(period.session_id, period.start, period.end, &name)
+                .map(|_| ()),
+        };
+
+        // Keep the idle time to let the user try again
+        if res.is_err() {
+            self.pending = Some(period);
+        }
+        res.map_err(|e| e.to_string())
+    }
+}
+
+/**
+Start the thread checking the idle time of the user.
+
+An `idle` event with the `IdlePeriod` is sent to the UI when the user comes back after being away for longer than the
+`idle_minutes` setting while a session was running.
+ */
+pub fn spawn<S: IdleSource>(app: AppHandle, source: S) {
+    thread::spawn(move || loop {
+        thread::sleep(POLL_INTERVAL);
+
+        let Some(idle_time) = source.idle_time() else {
+            continue;
+        };
+        let now = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+
+        // The activities are always locked before the idle detector
+        let activities = app.state::<Mutex<Activities>>();
+        let activities = activities.lock().unwrap();
+        let state = activities
+            .idle_minutes()
+            .and_then(|minutes| Ok((minutes, activities.current_session()?, activities.is_paused()?)));
+        let (minutes, session, paused) = match state {
+            Ok(state) => state,
+            Err(e) => {
+                error!("Idle detection error: {}", e);
+                continue;
+            }
+        };
+        let session = session.filter(|_| !paused).map(|session| (session.id, session.name));
+
+        let detector = app.state::<Mutex<IdleDetector>>();
+        let period = detector.lock().unwrap().update(now, idle_time, minutes as u64 * 60, session);
+        drop(activities);
+
+        if let Some(period) = period {
+            if let Err(e) = app.emit_all("idle", period) {
+                error!("Failed to send the idle time: {}", e);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The threshold of the tests, 5 minutes
+    const THRESHOLD: u64 = 300;
+
+    /// An idle time set by the test
+    struct FakeIdle(Option<u64>);
+
+    impl IdleSource for FakeIdle {
+        fn idle_time(&self) -> Option<u64> {
+            self.0
+        }
+    }
+
+    /// Update the detector with the idle time of the source at `now`
+    fn poll(detector: &mut IdleDetector, source: &impl IdleSource, now: u64, session: Option<(i64, String)>) -> Option<IdlePeriod> {
+        detector.update(now, source.idle_time()?, THRESHOLD, session)
+    }
+
+    fn running() -> Option<(i64, String)> {
+        Some((1, "Foo".to_string()))
+    }
+
+    #[test]
+    fn threshold_crossed() {
+        let mut detector = IdleDetector::default();
+
+        assert!(poll(&mut detector, &FakeIdle(Some(THRESHOLD - 1)), 1000, running()).is_none());
+        assert!(detector.away.is_none());
+
+        assert!(poll(&mut detector, &FakeIdle(Some(THRESHOLD)), 1001, running()).is_none());
+        assert_eq!(detector.away, Some((1, "Foo".to_string(), 1001 - THRESHOLD)));
+        assert!(detector.pending().is_none());
+
+        // Still away, the time the user left doesn't move
+        assert!(poll(&mut detector, &FakeIdle(Some(THRESHOLD + 60)), 1061, running()).is_none());
+        assert_eq!(detector.away, Some((1, "Foo".to_string(), 1001 - THRESHOLD)));
+    }
+
+    #[test]
+    fn activity_resumed() {
+        let mut detector = IdleDetector::default();
+
+        poll(&mut detector, &FakeIdle(Some(THRESHOLD)), 1000, running());
+        let period = poll(&mut detector, &FakeIdle(Some(10)), 2000, running()).expect("The user came back");
+
+        assert_eq!(period.session_id, 1);
+        assert_eq!(period.activity, "Foo");
+        assert_eq!(period.start, 1000 - THRESHOLD);
+        assert_eq!(period.end, 1990);
+        assert_eq!(detector.pending().map(|pending| (pending.start, pending.end)), Some((period.start, period.end)));
+        assert!(detector.away.is_none());
+
+        // The user is back, nothing more is reported
+        assert!(poll(&mut detector, &FakeIdle(Some(0)), 2005, running()).is_none());
+    }
+
+    #[test]
+    fn nothing_running() {
+        let mut detector = IdleDetector::default();
+
+        assert!(poll(&mut detector, &FakeIdle(Some(THRESHOLD * 2)), 1000, None).is_none());
+        assert!(detector.away.is_none());
+        assert!(poll(&mut detector, &FakeIdle(Some(0)), 2000, None).is_none());
+        assert!(detector.pending().is_none());
+    }
+
+    #[test]
+    fn unknown_idle_time() {
+        let mut detector = IdleDetector::default();
+
+        poll(&mut detector, &FakeIdle(Some(THRESHOLD)), 1000, running());
+        assert!(poll(&mut detector, &FakeIdle(None), 2000, running()).is_none());
+        assert!(detector.away.is_some());
+    }
+}
@@ -0,0 +1,272 @@
+use std::{fs::File, io::Read, path::Path};
+
+use chrono::{DateTime, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    activities::{split_tags, Activities, ImportedSession},
+    calendar,
+};
+
+/// The number of rows shown to the user to map the columns
+const SAMPLE_ROWS: usize = 5;
+
+/// The formats of the local dates and times accepted for the start and end columns
+const DATETIME_FORMATS: [&str; 6] = [
+    "%Y-%m-%d %H:%M",
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%dT%H:%M",
+    "%Y-%m-%dT%H:%M:%S",
+    "%d/%m/%Y %H:%M",
+    "%d/%m/%Y %H:%M:%S",
+];
+
+/**
+The index of the columns holding each field of the sessions, starting at 0.
+
+# Fields
+name - The name of the activity, it can contain inline `#tags`
+start - The start of the session
+end - The end of the session, the duration is used when it is not set or empty
+duration - The duration of the session
+tags - The tags of the session separated by spaces or commas, with or without the `#`
+notes - What was done during the session
+ */
+#[derive(Debug, Clone, Deserialize)]
+pub struct ColumnMapping {
+    pub name: usize,
+    pub start: usize,
+    pub end: Option<usize>,
+    pub duration: Option<usize>,
+    pub tags: Option<usize>,
+    pub notes: Option<usize>,
+}
+
+/// How to read the file to import
+#[derive(Debug, Clone, Deserialize)]
+pub struct ImportOptions {
+    /// Whether the first row holds the names of the columns
+    pub has_header: bool,
+    pub mapping: ColumnMapping,
+}
+
+/**
+A file picked to be imported, with what is needed to map its columns.
+
+# Fields
+path - The path of the file
+columns - The first row of the file, the names of the columns if it is a header
+sample - The rows after the first one, to show what the columns hold
+ */
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportFile {
+    pub path: String,
+    pub columns: Vec<String>,
+    pub sample: Vec<Vec<String>>,
+}
+
+/// A row of the file that can't be imported
+#[derive(Debug, Clone, Serialize)]
+pub struct RejectedRow {
+    /// The line of the row in the file, starting at 1
+    pub line: u64,
+    pub reason: String,
+}
+
+/// The sessions read from a file and the rows that can't be imported
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportPreview {
+    pub sessions: Vec<ImportedSession>,
+    pub rejected: Vec<RejectedRow>,
+}
+
+/// The result of an import
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportReport {
+    /// The number of sessions inserted
+    pub imported: usize,
+    pub rejected: Vec<RejectedRow>,
+}
+
+/// Guess the delimiter of a file from its first line, spreadsheets use `;` or tabs in some locales
+fn delimiter(content: &str) -> u8 {
+    let first_line = content.lines().next().unwrap_or_default();
+    [b',', b';', b'\t']
+        .into_iter()
+        .max_by_key(|delimiter| first_line.matches(*delimiter as char).count())
+        .unwrap_or(b',')
+}
+
+fn read_content(path: &Path) -> Result<String, String> {
+    let mut content = String::new();
+    File::open(path)
+        .and_then(|mut file| file.read_to_string(&mut content))
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    // Spreadsheets often start their CSV files with a byte order mark
+    Ok(content.trim_start_matches('\u{feff}').to_string())
+}
+
+fn reader(content: &str) -> csv::Reader<&[u8]> {
+    csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .delimiter(delimiter(content))
+        .from_reader(content.as_bytes())
+}
+
+/// Read the first rows of a file to map its columns
+pub fn read_file(path: &Path) -> Result<ImportFile, String> {
+    let content = read_content(path)?;
+    let mut rows = reader(&content).into_records();
+
+    let columns = match rows.next() {
+        Some(row) => row.map_err(|e| e.to_string())?.iter().map(str::to_string).collect(),
+        None => return Err("The file is empty".to_string()),
+    };
+    let mut sample = Vec::new();
+    for row in rows.take(SAMPLE_ROWS) {
+        sample.push(row.map_err(|e| e.to_string())?.iter().map(str::to_string).collect());
+    }
+
+    Ok(ImportFile {
+        path: path.to_string_lossy().to_string(),
+        columns,
+        sample,
+    })
+}
+
+/// Parse a date and time, either in one of `DATETIME_FORMATS` in local time, RFC 3339 or seconds since the epoch
+fn parse_datetime(value: &str) -> Option<u64> {
+    // The times are stored as signed integers
+    if let Ok(seconds) = value.parse::<i64>() {
+        return u64::try_from(seconds).ok();
+    }
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(value) {
+        return u64::try_from(datetime.timestamp()).ok();
+    }
+    DATETIME_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+        .map(calendar::local_timestamp)
+}
+
+/// Get a time in seconds from hours, minutes and seconds, None if it overflows
+fn to_seconds(hours: u64, minutes: u64, seconds: u64) -> Option<u64> {
+    hours.checked_mul(3600)?.checked_add(minutes.checked_mul(60)?)?.checked_add(seconds)
+}
+
+/// Parse a duration in seconds, as `h:mm`, `h:mm:ss`, `1h30m`, `1h30`, `45m` or decimal hours
+fn parse_duration(value: &str) -> Option<u64> {
+    if value.contains(':') {
+        let parts = value
+            .split(':')
+            .map(|part| part.trim().parse::<u64>().ok())
+            .collect::<Option<Vec<_>>>()?;
+        return match parts[..] {
+            [hours, minutes] if minutes < 60 => to_seconds(hours, minutes, 0),
+            [hours, minutes, seconds] if minutes < 60 && seconds < 60 => to_seconds(hours, minutes, seconds),
+            _ => None,
+        };
+    }
+
+    if value.contains(['h', 'm']) {
+        let (hours, minutes) = match value.split_once('h') {
+            Some((hours, minutes)) => (hours.trim().parse::<u64>().ok()?, minutes.trim().trim_end_matches('m').trim()),
+            None => (0, value.trim_end_matches('m').trim()),
+        };
+        let minutes = if minutes.is_empty() { 0 } else { minutes.parse::<u64>().ok()? };
+        return to_seconds(hours, minutes, 0);
+    }
+
+    let hours: f64 = value.replace(',', ".").parse().ok()?;
+    let seconds = (hours * 3600.0).round();
+    // The cast would silently clamp the infinite, NaN and too large values
+    (seconds.is_finite() && seconds >= 0.0 && seconds < u64::MAX as f64).then_some(seconds as u64)
+}
+
+/// Build a session from a row of the file
+fn parse_row(row: &csv::StringRecord, mapping: &ColumnMapping) -> Result<ImportedSession, String> {
+    let field = |index: Option<usize>| index.and_then(|index| row.get(index)).map(str::trim).filter(|value| !value.is_empty());
+
+    let (name, mut tags) = split_tags(field(Some(mapping.name)).ok_or("The activity name is missing")?);
+    if name.is_empty() {
+        return Err("The activity name is missing".to_string());
+    }
+    if let Some(value) = field(mapping.tags) {
+        tags.extend(value.split([' ', ',']).filter(|tag| !tag.is_empty()).map(|tag| tag.trim_start_matches('#').to_string()));
+    }
+
+    let start = field(Some(mapping.start)).ok_or("The start is missing")?;
+    let start_time = parse_datetime(start).ok_or_else(|| format!("The start \"{}\" is not a date and time", start))?;
+
+    let end_time = match (field(mapping.end), field(mapping.duration)) {
+        (Some(end), _) => parse_datetime(end).ok_or_else(|| format!("The end \"{}\" is not a date and time", end))?,
+        (None, Some(duration)) => {
+            let seconds = parse_duration(duration).ok_or_else(|| format!("The duration \"{}\" is not a duration", duration))?;
+            start_time
+                .checked_add(seconds)
+                .filter(|&end_time| i64::try_from(end_time).is_ok())
+                .ok_or_else(|| format!("The duration \"{}\" is too long", duration))?
+        }
+        (None, None) => return Err("The end and the duration are missing".to_string()),
+    };
+    if end_time <= start_time {
+        return Err("The session ends before it starts".to_string());
+    }
+
+    Ok(ImportedSession {
+        name,
+        start_time,
+        end_time,
+        tags,
+        notes: field(mapping.notes).map(str::to_string),
+    })
+}
+
+/**
+Read and validate the sessions of a file.
+
+The rows overlapping an existing session or a previous row of the file, or ending in the future, are rejected.
+
+# Arguments
+path - The path of the file
+options - Whether the file has a header and where the fields are
+activities - The activities the sessions will be imported in
+now - The current time in seconds since the epoch
+ */
+pub fn preview(path: &Path, options: &ImportOptions, activities: &Activities, now: u64) -> Result<ImportPreview, String> {
+    let content = read_content(path)?;
+
+    let mut sessions: Vec<ImportedSession> = Vec::new();
+    let mut rejected = Vec::new();
+    for (index, row) in reader(&content).into_records().enumerate() {
+        // The quoted fields can span several lines
+        let line = row.as_ref().ok().and_then(|row| row.position()).map(|position| position.line()).unwrap_or(index as u64 + 1);
+        if index == 0 && options.has_header {
+            continue;
+        }
+
+        let session = row
+            .map_err(|e| e.to_string())
+            .and_then(|row| parse_row(&row, &options.mapping))
+            .and_then(|session| {
+                if session.end_time > now {
+                    return Err("The session ends in the future".to_string());
+                }
+                if activities.overlaps_session(session.start_time, session.end_time).map_err(|e| e.to_string())? {
+                    return Err("The session overlaps an existing session".to_string());
+                }
+                if sessions.iter().any(|other| other.start_time < session.end_time && other.end_time > session.start_time) {
+                    return Err("The session overlaps a previous row".to_string());
+                }
+                Ok(session)
+            });
+
+        match session {
+            Ok(session) => sessions.push(session),
+            Err(reason) => rejected.push(RejectedRow { line, reason }),
+        }
+    }
+
+    Ok(ImportPreview { sessions, rejected })
+}
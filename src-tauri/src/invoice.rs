@@ -0,0 +1,110 @@
+use chrono::{Local, TimeZone};
+
+use crate::activities::Invoice;
+
+/// Escape the characters with a meaning in HTML
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+/// Format a time in seconds since the epoch as a local date
+fn date(time: u64) -> String {
+    Local
+        .timestamp_opt(time as i64, 0)
+        .earliest()
+        .expect("A timestamp always has a local time")
+        .format("%Y-%m-%d")
+        .to_string()
+}
+
+/// Format an amount in cents with its currency
+fn money(cents: i64, currency: &str) -> String {
+    let sign = if cents < 0 { "-" } else { "" };
+    format!("{}{}.{:02} {}", sign, cents.abs() / 100, cents.abs() % 100, escape(currency))
+}
+
+/// Format a time in seconds as decimal hours
+fn hours(seconds: u64) -> String {
+    format!("{:.2}", seconds as f64 / 3600.0)
+}
+
+/// The style of the invoice, the page is sized for A4 so printing it to PDF gives a ready to send invoice
+const STYLE: &str = "
+@page { size: A4; margin: 20mm; }
+body { font-family: sans-serif; font-size: 11pt; color: #222; }
+h1 { font-size: 20pt; margin-bottom: 4pt; }
+table { width: 100%; border-collapse: collapse; margin-top: 16pt; }
+th, td { padding: 4pt 6pt; border-bottom: 1px solid #ccc; text-align: left; }
+td.number, th.number { text-align: right; }
+tfoot td { font-weight: bold; border-bottom: none; }
+tr { page-break-inside: avoid; }
+";
+
+/**
+Render an invoice as a standalone HTML page.
+
+The page has no external resources and a print stylesheet so it can be printed to PDF as is.
+ */
+pub fn render_html(invoice: &Invoice) -> String {
+    let mut lines = String::new();
+    for line in &invoice.lines {
+        let project = match &line.client {
+            Some(client) => format!("{} › {}", client, line.project),
+            None => line.project.clone(),
+        };
+        let rate = match line.hourly_rate {
+            Some(rate) => money(rate, &invoice.currency),
+            None => "No rate".to_string(),
+        };
+        lines.push_str(&format!(
+            "<tr><td>{}</td><td>{}</td><td class=\"number\">{}</td><td class=\"number\">{}</td><td class=\"number\">{}</td></tr>\n",
+            escape(&project),
+            escape(&line.activity),
+            hours(line.seconds),
+            rate,
+            money(line.amount, &invoice.currency),
+        ));
+    }
+
+    let client = match &invoice.client {
+        Some(client) => format!("<p>Client : {}</p>", escape(client)),
+        None => String::new(),
+    };
+
+    // The end of the range is excluded so the last day is the one before it
+    format!(
+        "<!DOCTYPE html>
+<html>
+<head>
+<meta charset=\"utf-8\">
+<title>Invoice {from} - {to}</title>
+<style>{style}</style>
+</head>
+<body>
+<h1>Invoice</h1>
+{client}
+<p>Period : {from} - {to}</p>
+<table>
+<thead><tr><th>Project</th><th>Activity</th><th class=\"number\">Hours</th><th class=\"number\">Rate</th><th class=\"number\">Amount</th></tr></thead>
+<tbody>
+{lines}</tbody>
+<tfoot><tr><td colspan=\"2\">Total</td><td class=\"number\">{hours}</td><td></td><td class=\"number\">{amount}</td></tr></tfoot>
+</table>
+<p>{rounding}</p>
+</body>
+</html>
+",
+        from = date(invoice.from),
+        to = date(invoice.to.saturating_sub(1).max(invoice.from)),
+        style = STYLE,
+        client = client,
+        lines = lines,
+        hours = hours(invoice.seconds),
+        amount = money(invoice.amount, &invoice.currency),
+        rounding = escape(&invoice.rounding),
+    )
+}
@@ -1,11 +1,19 @@
 // Prevents additional console window on Windows in release, DO NOT REMOVE!!
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-use std::{io::{BufWriter, Write}, sync::Mutex};
+use std::sync::Mutex;
 
-use activities::Activities;
+use time_tracker_core::{activities, calendar};
+use activities::{split_tags, Activities, ApiSettings, Budget, BudgetUsage, Client, Dump, Grouping, Invoice, Issue, Project, Repair, RestoreMode, Rounding, Session, WorkingHours};
 use log::info;
-use tauri::{api::dialog::FileDialogBuilder, State};
+use tauri::{api::dialog::FileDialogBuilder, State, WindowEvent};
+use api::ApiServer;
+use budget_alerts::BudgetAlerts;
+use export::ExportOptions;
+use import::{ImportFile, ImportOptions, ImportPreview, ImportReport};
+use idle::{IdleDetector, IdlePeriod, IdleResolution, SystemIdle};
+use reminder::Reminder;
+use timer::{Timer, TimeboxStatus};
 
 /// Takes a Result. If it's an error, it sends it to the channel. If it's Ok, continues.
 macro_rules! channel_try {
@@ -20,20 +28,32 @@ macro_rules! channel_try {
     };
 }
 
-mod activities;
+mod api;
+mod budget_alerts;
+mod export;
+mod idle;
+mod import;
+mod invoice;
+mod reminder;
+#[cfg(unix)]
+mod socket;
+mod timer;
+mod tray;
 
 #[tauri::command]
 /// Start an activity with an offset. If an activity is already running, it will be stopped with the same offset.
 /// 
 /// # Arguments
-/// activity - The name of the activity
+/// activity - The name of the activity, the words starting with # are added as tags
+///     Ex : "code review #meeting"
 /// offset - The offset for the start of the activity in seconds from now 
 ///     Ex : 0 the activity starts now, 60 for 1 minute from now, -60 for 1 minute ago
 fn start_activity(db: State<Mutex<Activities>>, activity: &str, offset: i64) -> Result<(), String> {
     info!("Starting activity with name: {}", activity);
     let activities = db.lock().unwrap();
 
-    activities.start_activity(activity, offset).map_err(|e| e.to_string())?;
+    let (name, tags) = split_tags(activity);
+    activities.start_activity(&name, &tags, offset).map_err(|e| e.to_string())?;
 
     Ok(())
 }
@@ -44,14 +64,138 @@ fn start_activity(db: State<Mutex<Activities>>, activity: &str, offset: i64) ->
 /// # Arguments
 /// offset - The offset for the stop of the activity in seconds from now
 ///     Ex : 0 the activity stops now, 60 for 1 minute from now, -60 for 1 minute ago
-fn stop_activity(db: State<Mutex<Activities>>, offset: i64) -> Result<(), String> {
+/// notes - What was done during the activity, kept as the notes of the session if set
+fn stop_activity(db: State<Mutex<Activities>>, offset: i64, notes: Option<String>) -> Result<(), String> {
     let activities = db.lock().unwrap();
 
+    if notes.is_some() {
+        activities.set_current_notes(notes.as_deref()).map_err(|e| e.to_string())?;
+    }
     activities.stop_activity(offset).map_err(|e| e.to_string())?;
 
     Ok(())
 }
 
+#[tauri::command]
+/// Start an activity with a target length, the UI is alerted with a `timebox-finished` event when it runs out
+///
+/// # Arguments
+/// activity - The name of the activity, the words starting with # are added as tags
+/// offset - The offset for the start of the activity in seconds from now
+/// minutes - The length of the work blocks in minutes
+///     Ex : 25
+fn start_timebox(db: State<Mutex<Activities>>, timer: State<Mutex<Timer>>, activity: &str, offset: i64, minutes: u32) -> Result<(), String> {
+    if minutes == 0 {
+        return Err("The timebox must last at least a minute".to_string());
+    }
+
+    let activities = db.lock().unwrap();
+
+    let (name, tags) = split_tags(activity);
+    let id = activities.start_activity(&name, &tags, offset).map_err(|e| e.to_string())?;
+
+    let mut timer = timer.lock().unwrap();
+    timer.start(&activities, id, minutes as u64 * 60).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Stop the countdown of the current timebox, the activity keeps running
+fn cancel_timebox(timer: State<Mutex<Timer>>) -> Result<(), String> {
+    let mut timer = timer.lock().unwrap();
+
+    timer.cancel();
+    Ok(())
+}
+
+#[tauri::command]
+/// Get the state of the current timebox
+///
+/// # Returns
+/// The timebox if the running activity has one, the same value is sent every second with the `timebox` event
+///     Ex : {"activity": "Foo", "kind": "work", "length": 1500, "elapsed": 600, "paused": false}
+fn get_timebox(db: State<Mutex<Activities>>, timer: State<Mutex<Timer>>) -> Result<Option<TimeboxStatus>, String> {
+    let activities = db.lock().unwrap();
+    let timer = timer.lock().unwrap();
+
+    timer.status(&activities).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Get the idle time waiting for a decision, the same value is sent with the `idle` event when the user comes back
+///
+/// # Returns
+/// The time the user was away while a session was running if there is one
+///     Ex : {"session_id": 12, "activity": "Foo", "start": 1700000000, "end": 1700001800}
+fn get_idle(idle: State<Mutex<IdleDetector>>) -> Result<Option<IdlePeriod>, String> {
+    let idle = idle.lock().unwrap();
+
+    Ok(idle.pending())
+}
+
+#[tauri::command]
+/// Decide what to do with the idle time
+///
+/// # Arguments
+/// resolution - Keep the time, discard it from the session or move it to another activity
+///     Ex : {"kind": "keep"}, {"kind": "discard"}, {"kind": "reassign", "name": "Meeting #billable"}
+fn resolve_idle(db: State<Mutex<Activities>>, idle: State<Mutex<IdleDetector>>, resolution: IdleResolution) -> Result<(), String> {
+    let activities = db.lock().unwrap();
+    let mut idle = idle.lock().unwrap();
+
+    idle.resolve(&activities, resolution)
+}
+
+#[tauri::command]
+/// Get the minutes without input after which the user is considered away, 0 when the detection is off
+fn get_idle_minutes(db: State<Mutex<Activities>>) -> Result<u32, String> {
+    let activities = db.lock().unwrap();
+
+    activities.idle_minutes().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Set the minutes without input after which the user is considered away
+///
+/// # Arguments
+/// minutes - The idle time in minutes, 0 to turn the detection off
+fn set_idle_minutes(db: State<Mutex<Activities>>, minutes: u32) -> Result<(), String> {
+    let activities = db.lock().unwrap();
+
+    activities.set_idle_minutes(minutes).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Pause the current activity with an offset, the time until it is resumed doesn't count toward it
+///
+/// # Arguments
+/// offset - The offset for the start of the break in seconds from now
+///     Ex : 0 the break starts now, -300 for 5 minutes ago
+fn pause_activity(db: State<Mutex<Activities>>, offset: i64) -> Result<(), String> {
+    let activities = db.lock().unwrap();
+
+    activities.pause_activity(offset).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Resume the paused activity with an offset
+///
+/// # Arguments
+/// offset - The offset for the end of the break in seconds from now
+///     Ex : 0 the activity resumes now, -300 for 5 minutes ago
+fn resume_activity(db: State<Mutex<Activities>>, offset: i64) -> Result<(), String> {
+    let activities = db.lock().unwrap();
+
+    activities.resume_activity(offset).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Whether the current activity is paused
+fn is_paused(db: State<Mutex<Activities>>) -> Result<bool, String> {
+    let activities = db.lock().unwrap();
+
+    activities.is_paused().map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 /// Get the current activity
 /// 
@@ -68,14 +212,20 @@ fn get_current_activity(db: State<Mutex<Activities>>) -> Result<String, String>
 #[tauri::command]
 /// Get a list of all activities with their cumulative time
 /// 
+/// # Arguments
+/// grouping - Sum the time by "activity", "project", "client" or "tag", defaults to activity
+/// tag - Only count the sessions with this tag if set
+///
 /// # Returns
-/// A list of activities with their cumulative time
+/// A list of activities with their cumulative time, rounded if the rounding applies to the statistics
 ///     Ex : [("Foo", 3600), ("Bar", 1800), ("Baz", 720)]
 ///    The time is in seconds
-fn get_activities_times(db: State<Mutex<Activities>>) -> Result<Vec<(String, u64)>, String> {
+fn get_activities_times(db: State<Mutex<Activities>>, grouping: Option<Grouping>, tag: Option<String>) -> Result<Vec<(String, u64)>, String> {
     let activities = db.lock().unwrap();
 
-    activities.activities_times().map_err(|e| e.to_string())
+    let rounding = activities.rounding().map_err(|e| e.to_string())?;
+    let rounding = if rounding.statistics { rounding } else { Rounding::default() };
+    activities.activities_times(grouping.unwrap_or_default(), tag.as_deref(), rounding).map_err(|e| e.to_string())
         .map(|activities| activities.into_iter().collect())
 }
 
@@ -83,7 +233,7 @@ fn get_activities_times(db: State<Mutex<Activities>>) -> Result<Vec<(String, u64
 /// Get a list of all activities
 /// 
 /// # Returns
-/// A list of activities
+/// A list of activities, the most recently started first
 ///    Ex : ["Foo", "Bar", "Baz"]
 fn list_activities(db: State<Mutex<Activities>>) -> Result<Vec<String>, String> {
     let activities = db.lock().unwrap();
@@ -117,24 +267,588 @@ fn hard_clear_activities(db: State<Mutex<Activities>>) -> Result<(), String> {
 /// Get all the activities for today
 ///
 /// # Returns
-/// A list of activities with their start time and end time
-///     Ex : [("Foo", 3600, Some(7200)), ("Bar", 1800, None), ("Baz", 720, Some(3600))]
+/// A list of sessions with their id, name, start time and end time
+///     Ex : [{"id": 1, "name": "Foo", "start_time": 3600, "end_time": 7200}, {"id": 2, "name": "Bar", "start_time": 7200, "end_time": null}]
 ///     The time is in seconds
-fn todays_activities(db: State<Mutex<Activities>>) -> Result<Vec<(String, u64, Option<u64>)>, String> {
+fn todays_activities(db: State<Mutex<Activities>>) -> Result<Vec<Session>, String> {
     let activities = db.lock().unwrap();
 
     activities.todays_activities().map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+/// Get all the sessions overlapping a time range, clipped to the range
+///
+/// # Arguments
+/// from - The start of the range in seconds since the epoch
+/// to - The end of the range in seconds since the epoch
+///
+/// # Returns
+/// A list of sessions with their id, name, start time and end time
+///     Ex : [{"id": 1, "name": "Foo", "start_time": 3600, "end_time": 7200}, {"id": 2, "name": "Bar", "start_time": 7200, "end_time": null}]
+///     The time is in seconds
+fn sessions_between(db: State<Mutex<Activities>>, from: u64, to: u64) -> Result<Vec<Session>, String> {
+    let activities = db.lock().unwrap();
+
+    activities.sessions_between(from, to).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Add a closed session without stopping the current activity
+///
+/// # Arguments
+/// name - The name of the activity, the words starting with # are added as tags
+/// start_time - The start of the session in seconds since the epoch
+/// end_time - The end of the session in seconds since the epoch
+///
+/// # Returns
+/// The id of the new session
+fn add_session(db: State<Mutex<Activities>>, name: &str, start_time: u64, end_time: u64) -> Result<i64, String> {
+    let activities = db.lock().unwrap();
+
+    let (name, tags) = split_tags(name);
+    activities.add_session(&name, &tags, start_time, end_time).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Replace the tags of a session
+///
+/// # Arguments
+/// id - The id of the session
+/// tags - The tags with or without the #
+///     Ex : ["meeting", "#billable"]
+fn set_session_tags(db: State<Mutex<Activities>>, id: i64, tags: Vec<String>) -> Result<(), String> {
+    let activities = db.lock().unwrap();
+
+    activities.set_session_tags(id, &tags).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Replace the notes of a session
+///
+/// # Arguments
+/// id - The id of the session
+/// notes - What was done during the session, None or blank to remove the notes
+fn set_session_notes(db: State<Mutex<Activities>>, id: i64, notes: Option<String>) -> Result<(), String> {
+    let activities = db.lock().unwrap();
+
+    activities.set_session_notes(id, notes.as_deref()).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Mark a session as billable or not
+///
+/// # Arguments
+/// id - The id of the session
+/// billable - Whether the session is billed to the client
+fn set_session_billable(db: State<Mutex<Activities>>, id: i64, billable: bool) -> Result<(), String> {
+    let activities = db.lock().unwrap();
+
+    activities.set_session_billable(id, billable).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Get all the tags in use
+///
+/// # Returns
+/// A list of tags without the #
+///     Ex : ["billable", "meeting"]
+fn list_tags(db: State<Mutex<Activities>>) -> Result<Vec<String>, String> {
+    let activities = db.lock().unwrap();
+
+    activities.list_tags().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Replace the name, start time and end time of a session
+///
+/// # Arguments
+/// id - The id of the session
+/// name - The name of the activity
+/// start_time - The start of the session in seconds since the epoch
+/// end_time - The end of the session in seconds since the epoch, null to keep the running session running
+fn update_session(db: State<Mutex<Activities>>, id: i64, name: &str, start_time: u64, end_time: Option<u64>) -> Result<(), String> {
+    let activities = db.lock().unwrap();
+
+    activities.update_session(id, name, start_time, end_time).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Delete a session
+///
+/// # Arguments
+/// id - The id of the session
+fn delete_session(db: State<Mutex<Activities>>, id: i64) -> Result<(), String> {
+    let activities = db.lock().unwrap();
+
+    activities.delete_session(id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// List the sessions with a negative duration and the sessions overlapping each other
+///
+/// # Returns
+/// A list of issues
+///     Ex : [{"kind": "overlap", "earlier": {"id": 1, ...}, "later": {"id": 2, ...}}, {"kind": "negative_duration", "session": {"id": 3, ...}}]
+fn check_integrity(db: State<Mutex<Activities>>) -> Result<Vec<Issue>, String> {
+    let activities = db.lock().unwrap();
+
+    activities.check_integrity().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Fix the overlap between two sessions
+///
+/// # Arguments
+/// earlier - The id of the session starting first
+/// later - The id of the session starting during the earlier one
+/// repair - The strategy to use : "trim_earlier", "trim_later" or "split"
+fn repair_overlap(db: State<Mutex<Activities>>, earlier: i64, later: i64, repair: Repair) -> Result<(), String> {
+    let activities = db.lock().unwrap();
+
+    activities.repair_overlap(earlier, later, repair).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Get all the clients
+///
+/// # Returns
+/// A list of clients
+///     Ex : [{"id": 1, "name": "Acme"}]
+fn list_clients(db: State<Mutex<Activities>>) -> Result<Vec<Client>, String> {
+    let activities = db.lock().unwrap();
+
+    activities.list_clients().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Add a client
+///
+/// # Arguments
+/// name - The unique name of the client
+///
+/// # Returns
+/// The id of the new client
+fn add_client(db: State<Mutex<Activities>>, name: &str) -> Result<i64, String> {
+    let activities = db.lock().unwrap();
+
+    activities.add_client(name).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Set the hourly rate of a client, used by its projects without a rate
+///
+/// # Arguments
+/// id - The id of the client
+/// hourly_rate - The rate in cents per hour, null to remove it
+///     Ex : 6000 for 60.00 per hour
+fn set_client_rate(db: State<Mutex<Activities>>, id: i64, hourly_rate: Option<i64>) -> Result<(), String> {
+    let activities = db.lock().unwrap();
+
+    activities.set_client_rate(id, hourly_rate).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Get all the projects, the unassigned project first
+///
+/// # Returns
+/// A list of projects
+///     Ex : [{"id": 1, "name": "Unassigned", "client_id": null, "client": null, "hourly_rate": null}, {"id": 2, "name": "Website", "client_id": 1, "client": "Acme", "hourly_rate": 6000}]
+fn list_projects(db: State<Mutex<Activities>>) -> Result<Vec<Project>, String> {
+    let activities = db.lock().unwrap();
+
+    activities.list_projects().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Add a project
+///
+/// # Arguments
+/// name - The name of the project, unique for the client
+/// client_id - The id of the client of the project if it has one
+///
+/// # Returns
+/// The id of the new project
+fn add_project(db: State<Mutex<Activities>>, name: &str, client_id: Option<i64>) -> Result<i64, String> {
+    let activities = db.lock().unwrap();
+
+    activities.add_project(name, client_id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Set the hourly rate of a project, it overrides the rate of the client
+///
+/// # Arguments
+/// id - The id of the project
+/// hourly_rate - The rate in cents per hour, null to use the rate of the client
+///     Ex : 6000 for 60.00 per hour
+fn set_project_rate(db: State<Mutex<Activities>>, id: i64, hourly_rate: Option<i64>) -> Result<(), String> {
+    let activities = db.lock().unwrap();
+
+    activities.set_project_rate(id, hourly_rate).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Get every known activity with the id of its project
+///
+/// # Returns
+/// A list of activities and project ids
+///     Ex : [("Foo", 1), ("Bar", 2)]
+fn activity_projects(db: State<Mutex<Activities>>) -> Result<Vec<(String, i64)>, String> {
+    let activities = db.lock().unwrap();
+
+    activities.activity_projects().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Move an activity and all its sessions to a project
+///
+/// # Arguments
+/// name - The name of the activity
+/// project_id - The id of the project
+fn set_activity_project(db: State<Mutex<Activities>>, name: &str, project_id: i64) -> Result<(), String> {
+    let activities = db.lock().unwrap();
+
+    activities.set_activity_project(name, project_id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Get the rounding of the durations in the exports and the statistics
+///
+/// # Returns
+/// The rounding, the increment is in minutes
+///     Ex : {"mode": "up", "increment": 15, "scope": "session", "statistics": false}
+///    The mode is "none", "nearest", "up" or "down" and the scope "session" or "total"
+fn get_rounding(db: State<Mutex<Activities>>) -> Result<Rounding, String> {
+    let activities = db.lock().unwrap();
+
+    activities.rounding().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Set the rounding of the durations in the exports and the statistics
+///
+/// # Arguments
+/// rounding - The rounding, the increment must be between 1 and 1440 minutes
+///     Ex : {"mode": "nearest", "increment": 6, "scope": "total", "statistics": true}
+fn set_rounding(db: State<Mutex<Activities>>, rounding: Rounding) -> Result<(), String> {
+    let activities = db.lock().unwrap();
+
+    activities.set_rounding(rounding).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Get the budgets of the activities
+///
+/// # Returns
+/// A list of budgets in seconds ordered by activity name
+///     Ex : [{"name": "Foo", "daily": 7200, "weekly": null}]
+fn list_budgets(db: State<Mutex<Activities>>) -> Result<Vec<Budget>, String> {
+    let activities = db.lock().unwrap();
+
+    activities.list_budgets().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Set the budgets of an activity
+///
+/// # Arguments
+/// budget - The activity and its daily and weekly budgets in seconds, null for no budget
+///     Ex : {"name": "Foo", "daily": 7200, "weekly": 36000}
+fn set_budget(db: State<Mutex<Activities>>, budget: Budget) -> Result<(), String> {
+    let activities = db.lock().unwrap();
+
+    activities.set_budget(&budget).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Get the time spent on the activities with a budget during the current day and week
+///
+/// # Returns
+/// A list of budgets with the time spent in seconds
+///     Ex : [{"name": "Foo", "daily": 7200, "weekly": null, "today": 3600, "this_week": 18000}]
+fn get_budget_usage(db: State<Mutex<Activities>>) -> Result<Vec<BudgetUsage>, String> {
+    let activities = db.lock().unwrap();
+
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::SystemTime::UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    activities.budget_usage(now).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Get the logical day, week and month of the current time in the local timezone
+///
+/// # Returns
+/// The boundaries of the periods in seconds since the epoch and the start of each hour of the day
+///     Ex : {"day": {"start": 1700000000, "end": 1700086400}, "week": {...}, "month": {...}, "hours": [1700000000, 1700003600, ...]}
+fn get_calendar(db: State<Mutex<Activities>>) -> Result<calendar::Calendar, String> {
+    let activities = db.lock().unwrap();
+
+    activities.calendar().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Get the local hour at which the days start
+fn get_day_start_hour(db: State<Mutex<Activities>>) -> Result<u32, String> {
+    let activities = db.lock().unwrap();
+
+    activities.day_start_hour().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Set the local hour at which the days start
+///
+/// # Arguments
+/// hour - The hour between 0 and 23, the activities started before it count toward the previous day
+fn set_day_start_hour(db: State<Mutex<Activities>>, hour: u32) -> Result<(), String> {
+    let activities = db.lock().unwrap();
+
+    activities.set_day_start_hour(hour).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Whether the notes of the session are asked for when stopping an activity
+fn get_ask_notes_on_stop(db: State<Mutex<Activities>>) -> Result<bool, String> {
+    let activities = db.lock().unwrap();
+
+    activities.ask_notes_on_stop().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Set whether the notes of the session are asked for when stopping an activity
+fn set_ask_notes_on_stop(db: State<Mutex<Activities>>, ask: bool) -> Result<(), String> {
+    let activities = db.lock().unwrap();
+
+    activities.set_ask_notes_on_stop(ask).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Get the length of the pomodoro breaks in minutes
+fn get_pomodoro_break_minutes(db: State<Mutex<Activities>>) -> Result<u32, String> {
+    let activities = db.lock().unwrap();
+
+    activities.pomodoro_break_minutes().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Set the length of the pomodoro breaks
+///
+/// # Arguments
+/// minutes - The length of the breaks in minutes, at least 1
+fn set_pomodoro_break_minutes(db: State<Mutex<Activities>>, minutes: u32) -> Result<(), String> {
+    let activities = db.lock().unwrap();
+
+    activities.set_pomodoro_break_minutes(minutes).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Whether the activity is paused for a break at the end of each timebox
+fn get_pomodoro_auto_breaks(db: State<Mutex<Activities>>) -> Result<bool, String> {
+    let activities = db.lock().unwrap();
+
+    activities.pomodoro_auto_breaks().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Set whether the activity is paused for a break at the end of each timebox
+fn set_pomodoro_auto_breaks(db: State<Mutex<Activities>>, auto_breaks: bool) -> Result<(), String> {
+    let activities = db.lock().unwrap();
+
+    activities.set_pomodoro_auto_breaks(auto_breaks).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Get the working hours, shown on the timeline and during which the reminders are sent
+///
+/// # Returns
+/// The local start and end hours, the end is excluded
+///     Ex : {"start": 8, "end": 19}
+fn get_working_hours(db: State<Mutex<Activities>>) -> Result<WorkingHours, String> {
+    let activities = db.lock().unwrap();
+
+    activities.working_hours().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Set the working hours
+///
+/// # Arguments
+/// hours - The local start and end hours, the start must be before the end
+///     Ex : {"start": 9, "end": 18}
+fn set_working_hours(db: State<Mutex<Activities>>, hours: WorkingHours) -> Result<(), String> {
+    let activities = db.lock().unwrap();
+
+    activities.set_working_hours(hours).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Get the minutes without a running activity during the working hours before a reminder, 0 when the reminders are off
+fn get_reminder_minutes(db: State<Mutex<Activities>>) -> Result<u32, String> {
+    let activities = db.lock().unwrap();
+
+    activities.reminder_minutes().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Set the minutes without a running activity during the working hours before a reminder
+///
+/// # Arguments
+/// minutes - The time in minutes, 0 to turn the reminders off
+fn set_reminder_minutes(db: State<Mutex<Activities>>, minutes: u32) -> Result<(), String> {
+    let activities = db.lock().unwrap();
+
+    activities.set_reminder_minutes(minutes).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Get the currency symbol used on the invoices
+fn get_currency(db: State<Mutex<Activities>>) -> Result<String, String> {
+    let activities = db.lock().unwrap();
+
+    activities.currency().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Set the currency symbol used on the invoices
+///
+/// # Arguments
+/// currency - The symbol or code of the currency
+///     Ex : "€", "USD"
+fn set_currency(db: State<Mutex<Activities>>, currency: &str) -> Result<(), String> {
+    let activities = db.lock().unwrap();
+
+    activities.set_currency(currency).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Whether closing the window hides it in the system tray instead of quitting
+fn get_close_to_tray(db: State<Mutex<Activities>>) -> Result<bool, String> {
+    let activities = db.lock().unwrap();
+
+    activities.close_to_tray().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Set whether closing the window hides it in the system tray instead of quitting
+fn set_close_to_tray(db: State<Mutex<Activities>>, close_to_tray: bool) -> Result<(), String> {
+    let activities = db.lock().unwrap();
+
+    activities.set_close_to_tray(close_to_tray).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Get the settings of the local HTTP API, a token is generated the first time
+///
+/// # Returns
+/// Whether the API is served, its port and the token the requests must carry
+///     Ex : {"enabled": true, "port": 7463, "token": "3kZ..."}
+fn get_api_settings(db: State<Mutex<Activities>>) -> Result<ApiSettings, String> {
+    let activities = db.lock().unwrap();
+
+    api::settings(&activities).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Set the settings of the local HTTP API, the server is started, stopped or moved right away
+///
+/// # Arguments
+/// settings - Whether the API is served, its port and its token
+///     Ex : {"enabled": true, "port": 7463, "token": "3kZ..."}
+fn set_api_settings(app: tauri::AppHandle, db: State<Mutex<Activities>>, server: State<Mutex<ApiServer>>, settings: ApiSettings) -> Result<(), String> {
+    let activities = db.lock().unwrap();
+    activities.set_api_settings(&settings).map_err(|e| e.to_string())?;
+    drop(activities);
+
+    let mut server = server.lock().unwrap();
+    server.apply(&app, &settings).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Replace the token of the local HTTP API, the tools using the previous one are refused
+///
+/// # Returns
+/// The new token
+fn regenerate_api_token(db: State<Mutex<Activities>>) -> Result<String, String> {
+    let activities = db.lock().unwrap();
+
+    let mut settings = api::settings(&activities).map_err(|e| e.to_string())?;
+    settings.token = api::generate_token();
+    activities.set_api_settings(&settings).map_err(|e| e.to_string())?;
+    Ok(settings.token)
+}
+
+#[tauri::command]
+/// Build the invoice of the billable sessions between two times
+///
+/// # Arguments
+/// from - The start of the range in seconds since the epoch (included)
+/// to - The end of the range in seconds since the epoch (excluded)
+/// client_id - Only bill the projects of this client if set
+///
+/// # Returns
+/// The invoice lines grouped by client, project and activity with the amounts in cents
+///     Ex : {"from": 1700000000, "to": 1702592000, "client": "Acme", "currency": "€", "lines": [{"client": "Acme", "project": "Website", "activity": "Foo", "seconds": 5400, "hourly_rate": 6000, "amount": 9000}], "seconds": 5400, "amount": 9000}
+fn get_invoice(db: State<Mutex<Activities>>, from: u64, to: u64, client_id: Option<i64>) -> Result<Invoice, String> {
+    let activities = db.lock().unwrap();
+
+    activities.invoice(from, to, client_id).map_err(|e| e.to_string())
+}
+
 #[tauri::command(async)]
-/// Export activity time to a CSV file
-/// 
-/// Same as get_activities_times but exports to a CSV file
-fn export_activities(db: State<'_, Mutex<Activities>>) -> Result<(), String> {
+/// Export the invoice of the billable sessions between two times to an HTML file
+///
+/// Same as get_invoice but saves the invoice as a standalone HTML page ready to be printed to PDF
+fn export_invoice(db: State<'_, Mutex<Activities>>, from: u64, to: u64, client_id: Option<i64>) -> Result<(), String> {
     let activities = db.lock().unwrap();
 
-    let activities_times = activities.activities_times().map_err(|e| e.to_string())?;
+    let invoice = activities.invoice(from, to, client_id).map_err(|e| e.to_string())?;
+
+    // Unlock the mutex once we have the data to avoid blocking while the user pick a file
+    drop(activities);
+
+    let default_path = directories::UserDirs::new().unwrap().document_dir().unwrap().to_owned();
+
+    let (tx, rx) = std::sync::mpsc::channel::<Result<(), String>>();
+
+    FileDialogBuilder::new()
+        .set_directory(default_path)
+        .set_title("Save invoice to")
+        .add_filter("HTML", &["html"])
+        .save_file(move |path|{
+            let Some(path) = path else{
+                tx.send(Err("No file selected".to_string())).unwrap();
+                return;
+            };
+            let res = std::fs::write(path, invoice::render_html(&invoice)).map_err(|e| e.to_string());
+            channel_try!(tx, res);
+
+            tx.send(Ok(())).unwrap();
+        });
     
+    rx.recv().unwrap()
+}
+
+#[tauri::command(async)]
+/// Export the sessions or their totals to a CSV file
+///
+/// # Arguments
+/// options - What to export and how, every field is optional
+///     Ex : {"layout": "sessions", "tag": "meeting", "from": 1700000000, "to": 1700600000, "duration_format": "decimal", "columns": ["activity", "start", "end", "duration"]}
+///     Ex : {"layout": "totals", "grouping": "project", "duration_format": "hours_minutes"}
+///    The sessions since the last clear are exported when there is no range, the durations are rounded with the rounding setting
+fn export_activities(db: State<'_, Mutex<Activities>>, options: ExportOptions) -> Result<(), String> {
+    let activities = db.lock().unwrap();
+
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::SystemTime::UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    let sessions = activities.sessions_in(options.range(), options.tag.as_deref()).map_err(|e| e.to_string())?;
+    let rounding = activities.rounding().map_err(|e| e.to_string())?;
+    let csv = export::render_csv(sessions, &options, rounding, now).map_err(|e| e.to_string())?;
+
     // Unlock the mutex once we have the data to avoid blocking while the user pick a file
     drop(activities);
 
@@ -145,23 +859,115 @@ fn export_activities(db: State<'_, Mutex<Activities>>) -> Result<(), String> {
     FileDialogBuilder::new()
         .set_directory(default_path)
         .set_title("Save activities to")
+        .add_filter("CSV", &["csv"])
         .save_file(move |path|{
             let Some(path) = path else{
                 tx.send(Err("No file selected".to_string())).unwrap();
                 return;
             };
-            let file = std::fs::File::create(path).map_err(|e| e.to_string());
-            let file = channel_try!(tx, file);
-
-            let mut wtr = BufWriter::new(file);
-            channel_try!(tx, writeln!(wtr, "Activity,Time"));
-            for (activity, time) in activities_times {
-                let hours = time / 3600;
-                let minutes = (time % 3600) / 60;
-                channel_try!(tx, writeln!(wtr, "{},{}h{}m", activity, hours, minutes));
-            }
+            let res = std::fs::write(path, csv).map_err(|e| e.to_string());
+            channel_try!(tx, res);
+
+            tx.send(Ok(())).unwrap();
+        });
+    
+    rx.recv().unwrap()
+}
+
+#[tauri::command(async)]
+/// Pick a CSV file of sessions to import
+///
+/// # Returns
+/// The path of the file with its first row and a few rows after it, to map the columns
+///     Ex : {"path": "/home/foo/sessions.csv", "columns": ["Task", "From", "To"], "sample": [["Foo", "2024-01-08 09:00", "2024-01-08 10:30"]]}
+fn open_import() -> Result<ImportFile, String> {
+    let default_path = directories::UserDirs::new().unwrap().document_dir().unwrap().to_owned();
+
+    let (tx, rx) = std::sync::mpsc::channel::<Result<ImportFile, String>>();
+
+    FileDialogBuilder::new()
+        .set_directory(default_path)
+        .set_title("Import sessions from")
+        .add_filter("CSV", &["csv", "tsv", "txt"])
+        .pick_file(move |path|{
+            let Some(path) = path else{
+                tx.send(Err("No file selected".to_string())).unwrap();
+                return;
+            };
+            tx.send(import::read_file(&path)).unwrap();
+        });
+
+    rx.recv().unwrap()
+}
+
+#[tauri::command]
+/// Read and validate the sessions of a CSV file without importing them
+///
+/// # Arguments
+/// path - The path of the file given by open_import
+/// options - Whether the file has a header and the index of the columns of each field, starting at 0
+///     Ex : {"has_header": true, "mapping": {"name": 0, "start": 1, "end": 2, "duration": null, "tags": null, "notes": null}}
+///
+/// # Returns
+/// The sessions that can be imported and the rows rejected with the reason
+///     Ex : {"sessions": [{"name": "Foo", "start_time": 1704700800, "end_time": 1704706200, "tags": [], "notes": null}], "rejected": [{"line": 3, "reason": "The start is missing"}]}
+fn preview_import(db: State<Mutex<Activities>>, path: String, options: ImportOptions) -> Result<ImportPreview, String> {
+    let activities = db.lock().unwrap();
+
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::SystemTime::UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    import::preview(std::path::Path::new(&path), &options, &activities, now)
+}
+
+#[tauri::command]
+/// Import the valid sessions of a CSV file in one transaction
+///
+/// Same as preview_import but inserts the sessions, the rejected rows are skipped
+///
+/// # Returns
+/// The number of sessions imported and the rows rejected
+///     Ex : {"imported": 12, "rejected": [{"line": 3, "reason": "The start is missing"}]}
+fn import_sessions(db: State<Mutex<Activities>>, path: String, options: ImportOptions) -> Result<ImportReport, String> {
+    let activities = db.lock().unwrap();
+
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::SystemTime::UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    let preview = import::preview(std::path::Path::new(&path), &options, &activities, now)?;
+    let imported = activities.import_sessions(&preview.sessions).map_err(|e| e.to_string())?;
+
+    Ok(ImportReport { imported, rejected: preview.rejected })
+}
+
+#[tauri::command(async)]
+/// Save every table of the database to a JSON file, as a backup that can be diffed and moved between machines
+fn export_dump(db: State<'_, Mutex<Activities>>) -> Result<(), String> {
+    let activities = db.lock().unwrap();
+
+    let dump = activities.dump().map_err(|e| e.to_string())?;
+    let json = serde_json::to_string_pretty(&dump).map_err(|e| e.to_string())?;
+
+    // Unlock the mutex once we have the data to avoid blocking while the user pick a file
+    drop(activities);
+
+    let default_path = directories::UserDirs::new().unwrap().document_dir().unwrap().to_owned();
+
+    let (tx, rx) = std::sync::mpsc::channel::<Result<(), String>>();
 
-            channel_try!(tx, wtr.flush());
+    FileDialogBuilder::new()
+        .set_directory(default_path)
+        .set_title("Save backup to")
+        .add_filter("JSON", &["json"])
+        .save_file(move |path|{
+            let Some(path) = path else{
+                tx.send(Err("No file selected".to_string())).unwrap();
+                return;
+            };
+            let res = std::fs::write(path, json).map_err(|e| e.to_string());
+            channel_try!(tx, res);
 
             tx.send(Ok(())).unwrap();
         });
@@ -169,32 +975,143 @@ fn export_activities(db: State<'_, Mutex<Activities>>) -> Result<(), String> {
     rx.recv().unwrap()
 }
 
+#[tauri::command(async)]
+/// Restore a JSON backup made by export_dump
+///
+/// # Arguments
+/// mode - "merge" to add the backup to the current data, "replace" to delete the current data first
+///
+/// # Returns
+/// The number of sessions restored
+fn restore_dump(db: State<'_, Mutex<Activities>>, mode: RestoreMode) -> Result<usize, String> {
+    let default_path = directories::UserDirs::new().unwrap().document_dir().unwrap().to_owned();
+
+    let (tx, rx) = std::sync::mpsc::channel::<Result<Dump, String>>();
+
+    FileDialogBuilder::new()
+        .set_directory(default_path)
+        .set_title("Restore backup from")
+        .add_filter("JSON", &["json"])
+        .pick_file(move |path|{
+            let Some(path) = path else{
+                tx.send(Err("No file selected".to_string())).unwrap();
+                return;
+            };
+            let content = std::fs::read_to_string(path).map_err(|e| e.to_string());
+            let content = channel_try!(tx, content);
+            tx.send(serde_json::from_str(&content).map_err(|e| format!("Invalid backup: {}", e))).unwrap();
+        });
+
+    let dump = rx.recv().unwrap()?;
+
+    // The database is only locked once the file is read
+    let activities = db.lock().unwrap();
+    activities.restore(&dump, mode).map_err(|e| e.to_string())
+}
+
 
 fn main() {
     simple_logger::init().unwrap();
-    let mut data = directories::ProjectDirs::from("fr", "cideco", "activity-tracker").unwrap().data_local_dir().to_owned();
-    if !data.exists(){
-        std::fs::create_dir_all(&data).unwrap();
-    }
-    data.push("activity-tracker.db");
-
-    let conn = rusqlite::Connection::open(data).unwrap();
-    let activities = activities::Activities::new(conn);
-    activities.init_db().expect("Error initiating database");
+    // The command line client uses the same database
+    let data = time_tracker_core::database_path().unwrap();
+    let activities = Activities::open(&data).expect("Error initiating database");
     let activities = Mutex::from(activities);
 
     tauri::Builder::default()
         .manage(activities)
+        .manage(Mutex::new(Timer::default()))
+        .manage(Mutex::new(IdleDetector::default()))
+        .manage(Mutex::new(Reminder::default()))
+        .manage(Mutex::new(BudgetAlerts::default()))
+        .manage(Mutex::new(ApiServer::default()))
+        .setup(|app| {
+            timer::spawn(app.handle());
+            idle::spawn(app.handle(), SystemIdle);
+            reminder::spawn(app.handle());
+            budget_alerts::spawn(app.handle());
+            tray::spawn(app.handle());
+            api::spawn(app.handle());
+            #[cfg(unix)]
+            socket::spawn(app.handle());
+            Ok(())
+        })
+        .system_tray(tray::system_tray())
+        .on_system_tray_event(tray::handle)
+        .on_window_event(|event| {
+            if let WindowEvent::CloseRequested { api, .. } = event.event() {
+                tray::close_requested(event.window(), api);
+            }
+        })
         .invoke_handler(tauri::generate_handler![
             start_activity, 
             stop_activity, 
+            start_timebox,
+            cancel_timebox,
+            get_timebox,
+            get_idle,
+            resolve_idle,
+            get_idle_minutes,
+            set_idle_minutes,
+            pause_activity,
+            resume_activity,
+            is_paused,
             get_current_activity, 
             get_activities_times,
             list_activities,
             clear_activities,
             hard_clear_activities,
             todays_activities,
-            export_activities
+            sessions_between,
+            add_session,
+            update_session,
+            set_session_tags,
+            set_session_notes,
+            set_session_billable,
+            list_tags,
+            delete_session,
+            check_integrity,
+            repair_overlap,
+            list_clients,
+            add_client,
+            set_client_rate,
+            list_projects,
+            add_project,
+            set_project_rate,
+            activity_projects,
+            set_activity_project,
+            get_rounding,
+            set_rounding,
+            list_budgets,
+            set_budget,
+            get_budget_usage,
+            get_calendar,
+            get_day_start_hour,
+            set_day_start_hour,
+            get_ask_notes_on_stop,
+            set_ask_notes_on_stop,
+            get_pomodoro_break_minutes,
+            set_pomodoro_break_minutes,
+            get_pomodoro_auto_breaks,
+            set_pomodoro_auto_breaks,
+            get_working_hours,
+            set_working_hours,
+            get_reminder_minutes,
+            set_reminder_minutes,
+            get_currency,
+            set_currency,
+            get_close_to_tray,
+            set_close_to_tray,
+            get_api_settings,
+            set_api_settings,
+            regenerate_api_token,
+            get_invoice,
+            export_invoice,
+            export_activities,
+            open_import,
+            preview_import,
+            import_sessions,
+            export_dump,
+            restore_dump
             ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
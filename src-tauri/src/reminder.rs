@@ -0,0 +1,111 @@
+use std::{
+    sync::Mutex,
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use log::error;
+use tauri::{api::notification::Notification, AppHandle, Manager};
+
+use crate::{
+    activities::{Activities, ActivitiesError},
+    calendar,
+};
+
+/// How often the running activity is checked
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/**
+Reminds the user to start an activity when nothing runs during the working hours.
+
+# Fields
+last_reminder - The time of the last reminder in seconds since the epoch, the next one waits as long again
+ */
+#[derive(Debug, Default)]
+pub struct Reminder {
+    last_reminder: Option<u64>,
+}
+
+impl Reminder {
+    /**
+    Check if the user should be reminded to start an activity.
+
+    # Arguments
+    now - The current time in seconds since the epoch
+
+    # Returns
+    The minutes since nothing is running if a reminder is due
+     */
+    pub fn check(&mut self, activities: &Activities, now: u64) -> Result<Option<u64>, ActivitiesError> {
+        let minutes = activities.reminder_minutes()? as u64;
+        if minutes == 0 || activities.current_session()?.is_some() {
+            self.last_reminder = None;
+            return Ok(None);
+        }
+
+        // The working hours of the logical day, they can be shorter or longer on the days the clocks change
+        let hours = activities.working_hours()?;
+        let day = calendar::calendar_at(now, activities.day_start_hour()?);
+        let (Some(&work_start), Some(&work_end)) = (day.hours.get(hours.start as usize), day.hours.get(hours.end as usize)) else {
+            return Ok(None);
+        };
+        if now < work_start || now >= work_end {
+            return Ok(None);
+        }
+
+        let stopped = activities.last_end_time()?.unwrap_or(0).max(work_start);
+        let since = stopped.max(self.last_reminder.unwrap_or(0));
+        // A session ending in the future, or a clock set back, holds the reminder until then
+        if now.saturating_sub(since) < minutes.saturating_mul(60) {
+            return Ok(None);
+        }
+
+        self.last_reminder = Some(now);
+        Ok(Some(now.saturating_sub(stopped) / 60))
+    }
+}
+
+/**
+Start the thread sending the reminders.
+
+A reminder is a desktop notification along with a `reminder` event carrying the minutes since nothing is running, so
+the UI can show it too.
+ */
+pub fn spawn(app: AppHandle) {
+    thread::spawn(move || loop {
+        thread::sleep(POLL_INTERVAL);
+
+        let now = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+
+        // The activities are always locked before the reminder
+        let activities = app.state::<Mutex<Activities>>();
+        let activities = activities.lock().unwrap();
+        let reminder = app.state::<Mutex<Reminder>>();
+        let due = reminder.lock().unwrap().check(&activities, now);
+        drop(activities);
+
+        let minutes = match due {
+            Ok(Some(minutes)) => minutes,
+            Ok(None) => continue,
+            Err(e) => {
+                error!("Reminder error: {}", e);
+                continue;
+            }
+        };
+
+        let body = format!("Nothing has been running for {} minutes", minutes);
+        let notification = Notification::new(&app.config().tauri.bundle.identifier)
+            .title("No activity running")
+            .body(&body)
+            .show();
+        if let Err(e) = notification {
+            error!("Failed to show the reminder: {}", e);
+        }
+        if let Err(e) = app.emit_all("reminder", minutes) {
+            error!("Failed to send the reminder: {}", e);
+        }
+    });
+}
@@ -0,0 +1,223 @@
+use std::{
+    fs::{self, Permissions},
+    io::{self, BufRead, BufReader, Write},
+    os::unix::{
+        fs::PermissionsExt,
+        net::{UnixListener, UnixStream},
+    },
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use log::{error, info};
+use tauri::{AppHandle, Manager};
+use time_tracker_core::protocol::{Message, Request};
+
+use crate::activities::{split_tags, Activities, Status};
+
+/// How often the running activity is checked for changes, the command line client changes it behind the window
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// How long a subscriber can block the changes before it is dropped
+const WRITE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// The writing side of a client, locked while a message is written so the lines don't mix
+type Writer = Arc<Mutex<UnixStream>>;
+/// The clients sent the changes of the running activity
+type Subscribers = Arc<Mutex<Vec<Writer>>>;
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+/// Write a message as a line
+fn send(stream: &mut UnixStream, message: &Message) -> io::Result<()> {
+    let mut line = serde_json::to_string(message)?;
+    line.push('\n');
+    stream.write_all(line.as_bytes())
+}
+
+/// Run a request and get the status after it
+fn answer(app: &AppHandle, request: &Request) -> Result<Status, String> {
+    let activities = app.state::<Mutex<Activities>>();
+    let activities = activities.lock().unwrap();
+
+    match request {
+        Request::Start { activity, offset } => {
+            let (name, tags) = split_tags(activity);
+            activities.start_activity(&name, &tags, *offset).map_err(|e| e.to_string())?;
+        }
+        Request::Stop { offset, notes } => {
+            if notes.is_some() {
+                activities.set_current_notes(notes.as_deref()).map_err(|e| e.to_string())?;
+            }
+            activities.stop_activity(*offset).map_err(|e| e.to_string())?;
+        }
+        Request::Status | Request::Subscribe => {}
+    }
+
+    activities.status(now()).map_err(|e| e.to_string())
+}
+
+/// Answer the requests of a client until it disconnects
+fn serve(app: &AppHandle, stream: UnixStream, subscribers: &Subscribers) {
+    // The timeout is shared with the clones, a client not reading can't hold the others back for long
+    if let Err(e) = stream.set_write_timeout(Some(WRITE_TIMEOUT)) {
+        error!("Failed to set up a socket client: {}", e);
+        return;
+    }
+    let reader = match stream.try_clone() {
+        Ok(reader) => BufReader::new(reader),
+        Err(e) => {
+            error!("Failed to read from a socket client: {}", e);
+            return;
+        }
+    };
+    let writer = Writer::new(Mutex::new(stream));
+    let mut subscribed = false;
+
+    for line in reader.lines() {
+        let Ok(line) = line else { break; };
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let request = match serde_json::from_str::<Request>(&line) {
+            Ok(request) => request,
+            Err(e) => {
+                let message = Message::Error { message: format!("Invalid request: {}", e) };
+                if send(&mut writer.lock().unwrap(), &message).is_err() {
+                    break;
+                }
+                continue;
+            }
+        };
+        let message = match answer(app, &request) {
+            Ok(status) => Message::Ok { status },
+            Err(message) => Message::Error { message },
+        };
+
+        // The client is subscribed once the answer is written so no change is sent before it
+        let mut stream = writer.lock().unwrap();
+        if send(&mut stream, &message).is_err() {
+            break;
+        }
+        if matches!(request, Request::Subscribe) && !subscribed {
+            subscribers.lock().unwrap().push(writer.clone());
+            subscribed = true;
+        }
+    }
+}
+
+/// Send the changes of the running activity to the subscribers and the window
+fn watch(app: AppHandle, subscribers: Subscribers) {
+    let status = |app: &AppHandle| {
+        let activities = app.state::<Mutex<Activities>>();
+        let activities = activities.lock().unwrap();
+        activities.status(now())
+    };
+
+    let mut last = status(&app).unwrap_or_default();
+    loop {
+        thread::sleep(POLL_INTERVAL);
+
+        let status = match status(&app) {
+            Ok(status) => status,
+            Err(e) => {
+                error!("Socket status error: {}", e);
+                continue;
+            }
+        };
+        if status.same_session(&last) {
+            continue;
+        }
+        last = status.clone();
+
+        if let Err(e) = app.emit_all("activity", status.activity.clone().unwrap_or_default()) {
+            error!("Failed to send the activity change: {}", e);
+        }
+
+        // The subscribers are written to without the list locked so the clients coming in don't wait on them
+        let message = Message::Changed { status };
+        let current = subscribers.lock().unwrap().clone();
+        let gone: Vec<Writer> = current
+            .into_iter()
+            .filter(|subscriber| send(&mut subscriber.lock().unwrap(), &message).is_err())
+            .collect();
+        if !gone.is_empty() {
+            subscribers
+                .lock()
+                .unwrap()
+                .retain(|subscriber| !gone.iter().any(|gone| Arc::ptr_eq(gone, subscriber)));
+        }
+    }
+}
+
+/**
+Start listening on the socket of `time_tracker_core::socket_path()` for the local tools, see
+`time_tracker_core::protocol`.
+
+A socket left by a crash is replaced, the socket is left alone if another window still answers on it. The changes of
+the running activity, wherever they come from, are sent to the subscribers and to the window with an `activity` event.
+ */
+pub fn spawn(app: AppHandle) {
+    let path = match time_tracker_core::socket_path() {
+        Ok(path) => path,
+        Err(e) => {
+            error!("No path for the socket: {}", e);
+            return;
+        }
+    };
+    if path.exists() {
+        if UnixStream::connect(&path).is_ok() {
+            error!("Another window already listens on {}", path.display());
+            return;
+        }
+        if let Err(e) = fs::remove_file(&path) {
+            error!("Failed to remove the stale socket {}: {}", path.display(), e);
+            return;
+        }
+    }
+
+    // Only the user can start and stop the activities, the directory is private before the socket is bound in it
+    if let Some(dir) = path.parent() {
+        if let Err(e) = fs::set_permissions(dir, Permissions::from_mode(0o700)) {
+            error!("Failed to restrict the directory of the socket {}: {}", dir.display(), e);
+            return;
+        }
+    }
+
+    let listener = match UnixListener::bind(&path) {
+        Ok(listener) => listener,
+        Err(e) => {
+            error!("Failed to listen on {}: {}", path.display(), e);
+            return;
+        }
+    };
+    if let Err(e) = fs::set_permissions(&path, Permissions::from_mode(0o600)) {
+        error!("Failed to restrict the socket {}: {}", path.display(), e);
+    }
+    info!("Listening on {}", path.display());
+
+    let subscribers = Subscribers::default();
+    thread::spawn({
+        let app = app.clone();
+        let subscribers = subscribers.clone();
+        move || watch(app, subscribers)
+    });
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            match stream {
+                Ok(stream) => {
+                    let app = app.clone();
+                    let subscribers = subscribers.clone();
+                    thread::spawn(move || serve(&app, stream, &subscribers));
+                }
+                Err(e) => error!("Failed to accept a socket client: {}", e),
+            }
+        }
+    });
+}
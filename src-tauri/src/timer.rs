@@ -0,0 +1,213 @@
+use std::{
+    sync::Mutex,
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use log::error;
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+use crate::activities::{Activities, ActivitiesError};
+
+/// What a timebox is counting
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimeboxKind {
+    /// Time spent on the activity
+    Work,
+    /// A break between two work blocks, the activity is paused
+    Break,
+}
+
+/// A target length for the running session
+#[derive(Debug, Clone)]
+struct Timebox {
+    session_id: i64,
+    kind: TimeboxKind,
+    /// The length of the timebox in seconds
+    length: u64,
+    /// The worked time of the session when a work block started, the time a break started
+    start: u64,
+    /// The length of the work blocks in seconds, used to start the next block after a break
+    work_length: u64,
+}
+
+/// The state of the running timebox sent to the UI
+#[derive(Debug, Clone, Serialize)]
+pub struct TimeboxStatus {
+    pub activity: String,
+    pub kind: TimeboxKind,
+    /// The length of the timebox in seconds
+    pub length: u64,
+    /// The time elapsed in the timebox in seconds, the breaks don't count toward the work blocks
+    pub elapsed: u64,
+    /// Whether the activity is paused outside of a pomodoro break
+    pub paused: bool,
+}
+
+/// Sent to the UI when a timebox runs out
+#[derive(Debug, Clone, Serialize)]
+pub struct TimeboxFinished {
+    pub activity: String,
+    pub kind: TimeboxKind,
+}
+
+/**
+Tracks the timebox of the running activity.
+
+The timer lives in the backend so it keeps counting when the UI reloads. It is ticked every second by the thread
+started with `spawn`, which sends a `timebox` event with the `TimeboxStatus` (or null) and a `timebox-finished` event
+with a `TimeboxFinished` when a timebox runs out.
+ */
+#[derive(Debug, Default)]
+pub struct Timer(Option<Timebox>);
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+impl Timer {
+    /**
+    Start a timebox on a running session.
+
+    # Arguments
+    session_id - The id of the running session
+    length - The length of the work blocks in seconds
+     */
+    pub fn start(&mut self, activities: &Activities, session_id: i64, length: u64) -> Result<(), ActivitiesError> {
+        let session = activities.session(session_id)?.ok_or(ActivitiesError::SessionNotFound(session_id))?;
+
+        self.0 = Some(Timebox {
+            session_id,
+            kind: TimeboxKind::Work,
+            length,
+            start: session.duration(now()),
+            work_length: length,
+        });
+        Ok(())
+    }
+
+    /// Stop counting, the activity keeps running
+    pub fn cancel(&mut self) {
+        self.0 = None;
+    }
+
+    /// Get the state of the timebox, None if there is no timebox or if its session isn't running anymore
+    pub fn status(&self, activities: &Activities) -> Result<Option<TimeboxStatus>, ActivitiesError> {
+        let Some(timebox) = &self.0 else {
+            return Ok(None);
+        };
+        let Some(session) = activities.session(timebox.session_id)? else {
+            return Ok(None);
+        };
+        if session.end_time.is_some() {
+            return Ok(None);
+        }
+
+        let now = now();
+        let paused = activities.is_paused()?;
+        let elapsed = match timebox.kind {
+            TimeboxKind::Work => session.duration(now).saturating_sub(timebox.start),
+            TimeboxKind::Break => now.saturating_sub(timebox.start),
+        };
+
+        Ok(Some(TimeboxStatus {
+            activity: session.name,
+            kind: timebox.kind,
+            length: timebox.length,
+            elapsed,
+            paused: paused && timebox.kind == TimeboxKind::Work,
+        }))
+    }
+
+    /**
+    Move the timebox forward.
+
+    When a work block runs out the activity is paused for a break if the automatic breaks are on, otherwise the timebox
+    ends and the activity keeps running. When a break runs out, or when the activity is resumed during a break, the
+    activity resumes for a new work block.
+
+    # Returns
+    The state of the timebox and the timebox that ran out if there is one
+     */
+    pub fn tick(&mut self, activities: &Activities) -> Result<(Option<TimeboxStatus>, Option<TimeboxFinished>), ActivitiesError> {
+        let Some(status) = self.status(activities)? else {
+            // The activity was stopped or replaced
+            self.0 = None;
+            return Ok((None, None));
+        };
+        let Some(timebox) = self.0.clone() else {
+            return Ok((None, None));
+        };
+
+        let resumed_early = timebox.kind == TimeboxKind::Break && !activities.is_paused()?;
+        if status.elapsed < status.length && !resumed_early {
+            return Ok((Some(status), None));
+        }
+
+        let finished = TimeboxFinished {
+            activity: status.activity,
+            kind: timebox.kind,
+        };
+        match timebox.kind {
+            TimeboxKind::Work if activities.pomodoro_auto_breaks()? => {
+                activities.pause_activity(0)?;
+                self.0 = Some(Timebox {
+                    kind: TimeboxKind::Break,
+                    length: activities.pomodoro_break_minutes()? as u64 * 60,
+                    start: now(),
+                    ..timebox
+                });
+            }
+            TimeboxKind::Work => {
+                self.0 = None;
+            }
+            TimeboxKind::Break => {
+                if activities.is_paused()? {
+                    activities.resume_activity(0)?;
+                }
+                self.start(activities, timebox.session_id, timebox.work_length)?;
+            }
+        }
+
+        // Don't tell that a break ran out when the user ended it
+        let finished = if resumed_early { None } else { Some(finished) };
+        Ok((self.status(activities)?, finished))
+    }
+}
+
+/// Start the thread ticking the timer every second and sending its state to the UI
+pub fn spawn(app: AppHandle) {
+    thread::spawn(move || loop {
+        thread::sleep(Duration::from_secs(1));
+
+        // The activities are always locked before the timer
+        let activities = app.state::<Mutex<Activities>>();
+        let activities = activities.lock().unwrap();
+        let timer = app.state::<Mutex<Timer>>();
+        let mut timer = timer.lock().unwrap();
+
+        let (status, finished) = match timer.tick(&activities) {
+            Ok(res) => res,
+            Err(e) => {
+                error!("Timer error: {}", e);
+                continue;
+            }
+        };
+        drop(timer);
+        drop(activities);
+
+        if let Err(e) = app.emit_all("timebox", status) {
+            error!("Failed to send the timebox: {}", e);
+        }
+        if let Some(finished) = finished {
+            if let Err(e) = app.emit_all("timebox-finished", finished) {
+                error!("Failed to send the end of the timebox: {}", e);
+            }
+        }
+    });
+}
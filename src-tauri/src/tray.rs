@@ -0,0 +1,206 @@
+use std::{
+    sync::Mutex,
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use log::error;
+use tauri::{
+    AppHandle, CloseRequestApi, CustomMenuItem, Manager, SystemTray, SystemTrayEvent, SystemTrayMenu,
+    SystemTrayMenuItem, Window,
+};
+
+use crate::activities::{Activities, ActivitiesError, Status};
+
+/// How many of the last activities can be started from the tray
+const RECENT_ACTIVITIES: usize = 5;
+/// How often the running activity is checked, for the elapsed time and the changes made outside of the tray
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+/// The prefix of the ids of the items starting an activity, followed by the name of the activity
+const START_PREFIX: &str = "start:";
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+/// Describe the running activity with its elapsed time
+fn describe(status: &Status) -> String {
+    match &status.activity {
+        None => "Nothing is running".to_string(),
+        Some(activity) => format!(
+            "{}{} - {}h{:02}",
+            activity,
+            if status.paused { " (paused)" } else { "" },
+            status.elapsed / 3600,
+            status.elapsed % 3600 / 60
+        ),
+    }
+}
+
+/// Get the running activity and the activities that can be started from the tray
+fn content(activities: &Activities) -> Result<(Status, Vec<String>), ActivitiesError> {
+    let status = activities.status(now())?;
+    let mut recent = activities.list_activities()?;
+    recent.truncate(RECENT_ACTIVITIES);
+    Ok((status, recent))
+}
+
+/**
+Build the menu of the tray.
+
+# Arguments
+status - The running activity, shown at the top
+recent - The activities that can be started in one click, the running one aside
+ */
+fn menu(status: &Status, recent: &[String]) -> SystemTrayMenu {
+    let mut menu = SystemTrayMenu::new()
+        .add_item(CustomMenuItem::new("current", describe(status)).disabled());
+
+    let stop = CustomMenuItem::new("stop", "Stop");
+    menu = menu.add_item(if status.activity.is_some() { stop } else { stop.disabled() });
+
+    let recent: Vec<_> = recent.iter().filter(|name| status.activity.as_ref() != Some(*name)).collect();
+    if !recent.is_empty() {
+        menu = menu.add_native_item(SystemTrayMenuItem::Separator);
+        for name in recent {
+            menu = menu.add_item(CustomMenuItem::new(format!("{}{}", START_PREFIX, name), format!("Start {}", name)));
+        }
+    }
+
+    menu.add_native_item(SystemTrayMenuItem::Separator)
+        .add_item(CustomMenuItem::new("toggle", "Show / hide the window"))
+        .add_item(CustomMenuItem::new("quit", "Quit"))
+}
+
+/// The tray, its menu is filled by `spawn`
+pub fn system_tray() -> SystemTray {
+    SystemTray::new().with_menu(menu(&Status::default(), &[]))
+}
+
+/// Show the window if it is hidden, hide it otherwise
+fn toggle_window(app: &AppHandle) {
+    let Some(window) = app.get_window("main") else {
+        return;
+    };
+
+    let res = if window.is_visible().unwrap_or(false) {
+        window.hide()
+    } else {
+        window.show().and_then(|_| window.set_focus())
+    };
+    if let Err(e) = res {
+        error!("Failed to toggle the window: {}", e);
+    }
+}
+
+/**
+Hide the window in the tray instead of closing it, unless it is turned off in the settings.
+
+# Arguments
+window - The window being closed
+api - The request to close the window
+ */
+pub fn close_requested(window: &Window, api: &CloseRequestApi) {
+    let activities = window.state::<Mutex<Activities>>();
+    let close_to_tray = activities.lock().unwrap().close_to_tray().unwrap_or(true);
+    if !close_to_tray {
+        return;
+    }
+
+    api.prevent_close();
+    if let Err(e) = window.hide() {
+        error!("Failed to hide the window: {}", e);
+    }
+}
+
+/// Show the running activity and the recent activities in the tray
+fn refresh(app: &AppHandle, status: &Status, recent: &[String]) {
+    let tray = app.tray_handle();
+    if let Err(e) = tray.set_menu(menu(status, recent)) {
+        error!("Failed to update the tray menu: {}", e);
+    }
+    if let Err(e) = tray.set_tooltip(&describe(status)) {
+        error!("Failed to update the tray tooltip: {}", e);
+    }
+}
+
+/// Start or stop the activities from the menu of the tray, a click on the icon shows or hides the window
+pub fn handle(app: &AppHandle, event: SystemTrayEvent) {
+    let id = match event {
+        SystemTrayEvent::LeftClick { .. } => return toggle_window(app),
+        SystemTrayEvent::MenuItemClick { id, .. } => id,
+        _ => return,
+    };
+
+    match id.as_str() {
+        "toggle" => return toggle_window(app),
+        "quit" => return app.exit(0),
+        _ => {}
+    }
+
+    let activities = app.state::<Mutex<Activities>>();
+    let activities = activities.lock().unwrap();
+    let res = match id.strip_prefix(START_PREFIX) {
+        Some(name) => activities.start_activity(name, &[], 0).map(|_| ()),
+        None if id == "stop" => activities.stop_activity(0).map_err(ActivitiesError::from),
+        None => return,
+    };
+    if let Err(e) = res {
+        error!("Failed to {} from the tray: {}", id, e);
+    }
+    let content = content(&activities);
+    drop(activities);
+
+    match content {
+        Ok((status, recent)) => {
+            refresh(app, &status, &recent);
+            if let Err(e) = app.emit_all("activity", status.activity.unwrap_or_default()) {
+                error!("Failed to send the activity change: {}", e);
+            }
+        }
+        Err(e) => error!("Tray error: {}", e),
+    }
+}
+
+/**
+Start the thread keeping the tray up to date.
+
+The elapsed time is updated in place, the menu is rebuilt when the running activity or the recent activities change.
+ */
+pub fn spawn(app: AppHandle) {
+    thread::spawn(move || {
+        let mut last: Option<(Status, Vec<String>)> = None;
+        loop {
+            let activities = app.state::<Mutex<Activities>>();
+            let activities = activities.lock().unwrap();
+            let content = content(&activities);
+            drop(activities);
+
+            match content {
+                Ok((status, recent)) => {
+                    let changed = last.as_ref().is_none_or(|(last_status, last_recent)| {
+                        !last_status.same_session(&status) || *last_recent != recent
+                    });
+                    if changed {
+                        refresh(&app, &status, &recent);
+                    } else {
+                        let tray = app.tray_handle();
+                        let description = describe(&status);
+                        let res = tray.set_tooltip(&description);
+                        let res = res.and_then(|_| tray.get_item("current").set_title(description));
+                        if let Err(e) = res {
+                            error!("Failed to update the tray: {}", e);
+                        }
+                    }
+                    last = Some((status, recent));
+                }
+                Err(e) => error!("Tray error: {}", e),
+            }
+
+            thread::sleep(POLL_INTERVAL);
+        }
+    });
+}
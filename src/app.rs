@@ -1,7 +1,7 @@
 use leptos::*;
 use wasm_bindgen::prelude::*;
 
-use crate::{components::{actions::Actions, reporting::Reporting, statistics::Statistics, timeline::Timeline}, notifications::Notifications};
+use crate::{components::{actions::Actions, idle_prompt::IdlePrompt, integrity::Integrity, reporting::Reporting, statistics::{Statistics, StatisticsFilter}, timeline::Timeline}, notifications::Notifications};
 
 #[wasm_bindgen]
 extern "C" {
@@ -12,6 +12,8 @@ extern "C" {
 
 #[component]
 pub fn App() -> impl IntoView {
+    provide_context(StatisticsFilter::default());
+
     view! {
         <main class="container mx-auto p-4 h-screen flex flex-col gap-4 ">
             <Notifications/>
@@ -19,6 +21,8 @@ pub fn App() -> impl IntoView {
                 <Timeline/>
             </div>
             <Reporting/>
+            <IdlePrompt/>
+            <Integrity/>
             <Statistics/>
             <Actions/>
         </main>
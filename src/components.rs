@@ -2,4 +2,14 @@ pub mod reporting;
 pub mod statistics;
 pub mod pure_html;
 pub mod actions;
-pub mod timeline;
\ No newline at end of file
+pub mod timeline;
+pub mod settings;
+pub mod session_editor;
+pub mod integrity;
+pub mod projects;pub mod invoices;
+pub mod countdown;
+pub mod idle_prompt;
+pub mod budgets;
+pub mod export;
+pub mod import;
+pub mod backup;
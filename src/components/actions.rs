@@ -1,30 +1,13 @@
 use closure::closure;
 use leptos::*;
 
-use crate::{invoke::{clear_activities, hard_clear_activities}, notifications::Messages};
+use crate::{components::{backup::Backup, budgets::Budgets, export::Export, import::Import, invoices::Invoices, projects::Projects, settings::Settings}, invoke::{clear_activities, hard_clear_activities}, notifications::Messages};
 
 #[component]
 pub fn Actions() -> impl IntoView{
     let message = expect_context::<Messages>();
     let (clear_dialog, set_clear_dialog) = create_signal(false);
 
-    let export = move |_| {
-        log::info!("Exporting data");
-        
-        spawn_local(async move {
-            let res = crate::invoke::export_activities().await;
-
-            match res {
-                Ok(_) => {
-                    message.success("Data exported".to_string());
-                },
-                Err(err) => {
-                    message.error(format!("Failed to export data: {}", err));
-                },
-            }
-        });
-    };
-
     let open_clear_dialog = closure!(clone set_clear_dialog, |_| {
         set_clear_dialog.set(true);
     });
@@ -81,7 +64,13 @@ pub fn Actions() -> impl IntoView{
 
     view! {
         <div class="bg-base-200 p-6 flex items-center rounded-lg justify-end gap-4" id="actions">
-            <button class="btn btn-accent" on:click=export>{"Export"}</button>
+            <Projects/>
+            <Settings/>
+            <Invoices/>
+            <Budgets/>
+            <Import/>
+            <Export/>
+            <Backup/>
             <button class="btn btn-error" on:click=open_clear_dialog>{"Clear"}</button>
             <dialog open=clear_dialog  class="modal">
                 <div  class="modal-box">
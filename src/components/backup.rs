@@ -0,0 +1,58 @@
+use leptos::*;
+
+use crate::{invoke::{export_dump, restore_dump, RestoreMode}, notifications::Messages};
+
+#[component]
+/// A button opening a dialog to save the whole database to a JSON file and to restore it
+pub fn Backup() -> impl IntoView {
+    let messages = expect_context::<Messages>();
+    let (dialog, set_dialog) = create_signal(false);
+
+    let save = move |_| {
+        spawn_local(async move {
+            match export_dump().await {
+                Ok(_) => {
+                    set_dialog.set(false);
+                    messages.success("Backup saved".to_string());
+                },
+                Err(err) => {
+                    messages.error(format!("Failed to save backup: {}", err));
+                },
+            }
+        });
+    };
+
+    let restore = move |mode: RestoreMode| {
+        spawn_local(async move {
+            match restore_dump(mode).await {
+                Ok(sessions) => {
+                    set_dialog.set(false);
+                    messages.success(format!("Backup restored, {} sessions added", sessions));
+                },
+                Err(err) => {
+                    messages.error(format!("Failed to restore backup: {}", err));
+                },
+            }
+        });
+    };
+
+    view! {
+        <button class="btn" on:click=move |_| set_dialog.set(true)>{"Backup"}</button>
+        <dialog open=dialog class="modal">
+            <div class="modal-box">
+                <h3 class="font-bold text-lg">Backup</h3>
+                <ul>
+                    <li>Save every session, project and setting -> Save</li>
+                    <li>Add a backup to the current data -> Merge</li>
+                    <li>Irreparably replace all data with a backup -> Replace (Double click)</li>
+                </ul>
+                <div class="modal-action">
+                    <button class="btn" on:click=move |_| set_dialog.set(false)>Cancel</button>
+                    <button class="btn btn-primary" on:click=save>Save</button>
+                    <button class="btn btn-warning" on:click=move |_| restore(RestoreMode::Merge)>Merge</button>
+                    <button class="btn btn-error" on:dblclick=move |_| restore(RestoreMode::Replace)>Replace</button>
+                </div>
+            </div>
+        </dialog>
+    }
+}
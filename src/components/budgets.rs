@@ -0,0 +1,87 @@
+use leptos::*;
+
+use crate::{invoke::{list_activities, list_budgets, set_budget, Budget}, notifications::Messages};
+
+/// Parse a budget input in hours into seconds, an empty or invalid input is no budget
+fn parse_hours(value: &str) -> Option<u64> {
+    let hours: f64 = value.trim().replace(',', ".").parse().ok()?;
+    let seconds = (hours * 3600.0).round();
+    (seconds > 0.0).then_some(seconds as u64)
+}
+
+/// Format a budget in seconds as the value of an input in hours
+fn hours_value(budget: Option<u64>) -> String {
+    budget.map(|seconds| format!("{}", seconds as f64 / 3600.0)).unwrap_or_default()
+}
+
+/// Format a duration in seconds as hours and minutes
+pub fn format_duration(seconds: u64) -> String {
+    format!("{}h{:02}", seconds / 3600, seconds % 3600 / 60)
+}
+
+#[component]
+/// A button opening a dialog to set the daily and weekly budget of each activity
+pub fn Budgets() -> impl IntoView {
+    let messages = expect_context::<Messages>();
+    let (dialog, set_dialog) = create_signal(false);
+    let (budgets, set_budgets) = create_signal(Vec::<Budget>::new());
+
+    // Every known activity is listed, with its budgets if it has some
+    let refresh = move || async move {
+        let Ok(mut budgets) = list_budgets().await else {
+            return;
+        };
+        if let Ok(activities) = list_activities().await {
+            for name in activities {
+                if !budgets.iter().any(|budget| budget.name == name) {
+                    budgets.push(Budget { name, daily: None, weekly: None });
+                }
+            }
+        }
+        budgets.sort_by(|a, b| a.name.cmp(&b.name));
+        set_budgets.set(budgets);
+    };
+
+    create_effect(move |_| {
+        if dialog.get() {
+            spawn_local(refresh());
+        }
+    });
+
+    let update = move |budget: Budget| {
+        spawn_local(async move {
+            if let Err(err) = set_budget(budget).await {
+                messages.error(format!("Failed to set budget: {}", err));
+            }
+            refresh().await;
+        });
+    };
+
+    let render_budget = move |budget: Budget| {
+        let daily = budget.clone();
+        let weekly = budget.clone();
+        view! {
+            <li class="flex items-center gap-4">
+                <span class="w-full truncate">{budget.name}</span>
+                <input class="input input-sm input-bordered w-24" type="number" min="0" step="0.25" placeholder="Daily" prop:value=hours_value(budget.daily) on:change=move |ev| update(Budget { daily: parse_hours(&event_target_value(&ev)), ..daily.clone() })/>
+                <input class="input input-sm input-bordered w-24" type="number" min="0" step="0.25" placeholder="Weekly" prop:value=hours_value(budget.weekly) on:change=move |ev| update(Budget { weekly: parse_hours(&event_target_value(&ev)), ..weekly.clone() })/>
+            </li>
+        }
+    };
+
+    view! {
+        <button class="btn" on:click=move |_| set_dialog.set(true)>{"Budgets"}</button>
+        <dialog open=dialog class="modal">
+            <div class="modal-box flex flex-col gap-4">
+                <h3 class="font-bold text-lg">Budgets</h3>
+                <p class="text-sm">Hours per day and per week, leave empty for no budget</p>
+                <ul class="flex flex-col gap-2 max-h-96 overflow-y-scroll">
+                    {move || budgets.get().into_iter().map(render_budget).collect_view()}
+                </ul>
+                <div class="modal-action">
+                    <button class="btn" on:click=move |_| set_dialog.set(false)>Close</button>
+                </div>
+            </div>
+        </dialog>
+    }
+}
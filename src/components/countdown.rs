@@ -0,0 +1,65 @@
+use leptos::*;
+
+use crate::{invoke::{cancel_timebox, get_timebox, listen, TimeboxFinished, TimeboxKind, TimeboxStatus}, notifications::Messages};
+
+/// Format a number of seconds as minutes and seconds
+fn countdown_string(seconds: u64) -> String {
+    format!("{:02}:{:02}", seconds / 60, seconds % 60)
+}
+
+#[component]
+/// The time left in the timebox of the running activity, updated by the backend every second
+pub fn Countdown() -> impl IntoView {
+    let messages = expect_context::<Messages>();
+    let (timebox, set_timebox) = create_signal(None::<TimeboxStatus>);
+
+    // The backend sends the state every second, fetch it once to not wait for the first event
+    spawn_local(async move {
+        if let Ok(status) = get_timebox().await {
+            set_timebox.set(status);
+        }
+    });
+
+    listen("timebox", move |status: Option<TimeboxStatus>| {
+        set_timebox.set(status);
+    });
+
+    listen("timebox-finished", move |finished: TimeboxFinished| {
+        match finished.kind {
+            TimeboxKind::Work => messages.success(format!("Time is up for {}", finished.activity)),
+            TimeboxKind::Break => messages.success(format!("The break is over, back to {}", finished.activity)),
+        }
+    });
+
+    let cancel = move |_| {
+        spawn_local(async move {
+            if cancel_timebox().await.is_ok() {
+                set_timebox.set(None);
+            }
+        });
+    };
+
+    view! {
+        {move || timebox.get().map(|timebox| {
+            let left = timebox.length.saturating_sub(timebox.elapsed);
+            let progress = (timebox.elapsed.min(timebox.length) * 100 / timebox.length.max(1)).to_string();
+            let (label, class) = match (timebox.kind, timebox.paused) {
+                (TimeboxKind::Break, _) => ("Break", "radial-progress text-success"),
+                (TimeboxKind::Work, true) => ("Paused", "radial-progress text-warning"),
+                (TimeboxKind::Work, false) => ("Focus", "radial-progress text-primary"),
+            };
+            view! {
+                <div class="bg-base-200 p-6 flex items-center rounded-lg gap-4" id="countdown">
+                    <div class=class style=format!("--value:{}; --size:4rem;", progress) role="progressbar">
+                        {countdown_string(left)}
+                    </div>
+                    <div class="w-full">
+                        <p class="font-bold">{label}</p>
+                        <p class="text-sm truncate">{timebox.activity}</p>
+                    </div>
+                    <button class="btn btn-sm" on:click=cancel>Cancel timer</button>
+                </div>
+            }
+        })}
+    }
+}
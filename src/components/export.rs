@@ -0,0 +1,121 @@
+use leptos::*;
+
+use crate::{components::{invoices::date_range, statistics::StatisticsFilter}, invoke::{export_activities, DurationFormat, ExportLayout, ExportOptions, SessionColumn}, notifications::Messages};
+
+/// The columns of the sessions layout with their labels, in the order they are exported
+const COLUMNS: [(SessionColumn, &str); 9] = [
+    (SessionColumn::Activity, "Activity"),
+    (SessionColumn::Project, "Project"),
+    (SessionColumn::Client, "Client"),
+    (SessionColumn::Tags, "Tags"),
+    (SessionColumn::Start, "Start"),
+    (SessionColumn::End, "End"),
+    (SessionColumn::Duration, "Duration"),
+    (SessionColumn::Notes, "Notes"),
+    (SessionColumn::Billable, "Billable"),
+];
+
+#[component]
+/// A button opening a dialog to export the sessions or their totals to a CSV file
+pub fn Export() -> impl IntoView {
+    let messages = expect_context::<Messages>();
+    let filter = expect_context::<StatisticsFilter>();
+    let (dialog, set_dialog) = create_signal(false);
+    let (layout, set_layout) = create_signal(ExportLayout::Totals);
+    let (duration_format, set_duration_format) = create_signal(DurationFormat::HoursMinutes);
+    let (from, set_from) = create_signal(String::new());
+    let (to, set_to) = create_signal(String::new());
+    let (columns, set_columns) = create_signal(vec![SessionColumn::Activity, SessionColumn::Start, SessionColumn::End, SessionColumn::Duration, SessionColumn::Notes]);
+
+    let toggle_column = move |column: SessionColumn, checked: bool| {
+        set_columns.update(|columns| {
+            columns.retain(|c| *c != column);
+            if checked {
+                columns.push(column);
+                // Keep the columns in the order they are listed
+                columns.sort_by_key(|c| COLUMNS.iter().position(|(column, _)| column == c));
+            }
+        });
+    };
+
+    let export = move |_| {
+        log::info!("Exporting data");
+
+        // Without dates the sessions since the last clear are exported
+        let (from, to) = (from.get_untracked(), to.get_untracked());
+        let range = match (from.is_empty(), to.is_empty()) {
+            (true, true) => None,
+            _ => match date_range(&from, &to) {
+                Some(range) => Some(range),
+                None => {
+                    messages.error("Both dates are required to export a range".to_string());
+                    return;
+                },
+            },
+        };
+
+        // Export what is shown in the statistics
+        let options = ExportOptions {
+            layout: layout.get_untracked(),
+            grouping: filter.grouping.get_untracked(),
+            tag: filter.tag.get_untracked(),
+            from: range.map(|(from, _)| from),
+            to: range.map(|(_, to)| to),
+            duration_format: duration_format.get_untracked(),
+            columns: columns.get_untracked(),
+        };
+        spawn_local(async move {
+            match export_activities(options).await {
+                Ok(_) => {
+                    set_dialog.set(false);
+                    messages.success("Data exported".to_string());
+                },
+                Err(err) => {
+                    messages.error(format!("Failed to export data: {}", err));
+                },
+            }
+        });
+    };
+
+    view! {
+        <button class="btn btn-accent" on:click=move |_| set_dialog.set(true)>{"Export"}</button>
+        <dialog open=dialog class="modal">
+            <div class="modal-box flex flex-col gap-4">
+                <h3 class="font-bold text-lg">Export</h3>
+                <div class="flex gap-2">
+                    <select class="select select-bordered w-full" on:change=move |ev| set_layout.set(if event_target_value(&ev) == "sessions" { ExportLayout::Sessions } else { ExportLayout::Totals })>
+                        <option value="totals" selected=move || layout.get() == ExportLayout::Totals>Totals</option>
+                        <option value="sessions" selected=move || layout.get() == ExportLayout::Sessions>Sessions</option>
+                    </select>
+                    <select class="select select-bordered w-full" on:change=move |ev| set_duration_format.set(if event_target_value(&ev) == "decimal" { DurationFormat::Decimal } else { DurationFormat::HoursMinutes })>
+                        <option value="hours_minutes" selected=move || duration_format.get() == DurationFormat::HoursMinutes>h:mm</option>
+                        <option value="decimal" selected=move || duration_format.get() == DurationFormat::Decimal>Decimal hours</option>
+                    </select>
+                </div>
+                <div class="flex items-center gap-2">
+                    <input type="date" class="input input-bordered w-full" prop:value=from on:change=move |ev| set_from.set(event_target_value(&ev))/>
+                    <span>to</span>
+                    <input type="date" class="input input-bordered w-full" prop:value=to on:change=move |ev| set_to.set(event_target_value(&ev))/>
+                </div>
+                <p class="text-sm">Leave the dates empty to export since the last clear. The totals are grouped and filtered like the statistics.</p>
+                <Show when=move || layout.get() == ExportLayout::Sessions>
+                    <div class="flex flex-wrap gap-4">
+                        {COLUMNS.iter().map(|(column, label)| {
+                            let column = *column;
+                            view! {
+                                <label class="label cursor-pointer gap-2">
+                                    <input type="checkbox" class="checkbox checkbox-sm" prop:checked=move || columns.with(|columns| columns.contains(&column)) on:change=move |ev| toggle_column(column, event_target_checked(&ev))/>
+                                    <span class="label-text">{*label}</span>
+                                </label>
+                            }
+                        }).collect_view()}
+                    </div>
+                </Show>
+                <div class="modal-action">
+                    <button class="btn" on:click=move |_| set_dialog.set(false)>Cancel</button>
+                    <button class="btn btn-accent" on:click=export>Export</button>
+                </div>
+            </div>
+        </dialog>
+    }
+}
@@ -0,0 +1,64 @@
+use leptos::*;
+
+use crate::{components::session_editor::datetime_local_value, invoke::{get_idle, listen, resolve_idle, IdlePeriod, IdleResolution}, notifications::Messages};
+
+#[component]
+/// Asks what to do with the time the user was away while an activity was running
+pub fn IdlePrompt() -> impl IntoView {
+    let messages = expect_context::<Messages>();
+    let (period, set_period) = create_signal(None::<IdlePeriod>);
+    let (name, set_name) = create_signal(String::new());
+
+    // The idle time may have been detected before the UI loaded
+    spawn_local(async move {
+        if let Ok(idle) = get_idle().await {
+            set_period.set(idle);
+        }
+    });
+
+    listen("idle", move |idle: IdlePeriod| {
+        set_period.set(Some(idle));
+    });
+
+    let resolve = move |resolution: IdleResolution| {
+        spawn_local(async move {
+            match resolve_idle(resolution).await {
+                Ok(_) => {
+                    set_period.set(None);
+                    set_name.set(String::new());
+                },
+                Err(err) => {
+                    messages.error(format!("Failed to update the session: {}", err));
+                },
+            }
+        });
+    };
+
+    let description = move || period.get().map(|period| {
+        let minutes = period.end.saturating_sub(period.start) / 60;
+        format!(
+            "You were away for {} minutes, from {} to {}, while {} was running.",
+            minutes,
+            &datetime_local_value(period.start)[11..],
+            &datetime_local_value(period.end)[11..],
+            period.activity
+        )
+    });
+
+    view! {
+        <dialog open=move || period.with(Option::is_some) class="modal">
+            <div class="modal-box flex flex-col gap-4">
+                <h3 class="font-bold text-lg">Welcome back</h3>
+                <p>{description}</p>
+                <div class="flex gap-2">
+                    <input list="known-activity" class="input input-bordered w-full" type="text" placeholder="Activity #tag" prop:value=name on:change=move |ev| set_name.set(event_target_value(&ev))/>
+                    <button class="btn btn-secondary" on:click=move |_| resolve(IdleResolution::Reassign { name: name.get_untracked() })>Move to it</button>
+                </div>
+                <div class="modal-action">
+                    <button class="btn btn-error" on:click=move |_| resolve(IdleResolution::Discard)>Discard</button>
+                    <button class="btn btn-primary" on:click=move |_| resolve(IdleResolution::Keep)>Keep</button>
+                </div>
+            </div>
+        </dialog>
+    }
+}
@@ -0,0 +1,187 @@
+use leptos::*;
+
+use crate::{components::{budgets::format_duration, session_editor::datetime_local_value}, invoke::{import_sessions, open_import, preview_import, ColumnMapping, ImportFile, ImportOptions, ImportPreview}, notifications::Messages};
+
+/// The number of sessions shown in the preview
+const PREVIEW_ROWS: usize = 10;
+
+/// Find the column whose name contains one of the names, ignoring the case
+fn find_column(columns: &[String], names: &[&str]) -> Option<usize> {
+    columns.iter().position(|column| {
+        let column = column.to_lowercase();
+        names.iter().any(|name| column.contains(name))
+    })
+}
+
+/// Guess the mapping of the columns from their names, the first columns are used when nothing matches
+fn guess_mapping(columns: &[String]) -> ColumnMapping {
+    ColumnMapping {
+        name: find_column(columns, &["activity", "name", "task"]).unwrap_or(0),
+        start: find_column(columns, &["start", "from", "begin"]).unwrap_or(1),
+        end: find_column(columns, &["end", "stop", "to"]),
+        duration: find_column(columns, &["duration", "time", "hours"]),
+        tags: find_column(columns, &["tag"]),
+        notes: find_column(columns, &["note", "description", "comment"]),
+    }
+}
+
+#[component]
+/// A select of the column holding a field, optional fields can be left unmapped
+fn ColumnSelect(
+    label: &'static str,
+    file: ReadSignal<Option<ImportFile>>,
+    has_header: ReadSignal<bool>,
+    value: Signal<Option<usize>>,
+    on_change: Callback<Option<usize>>,
+    #[prop(optional)]
+    optional: bool,
+) -> impl IntoView {
+    view! {
+        <label class="form-control w-full">
+            <div class="label">
+                <span class="label-text">{label}</span>
+            </div>
+            <select class="select select-bordered select-sm" on:change=move |ev| on_change.call(event_target_value(&ev).parse().ok())>
+                {optional.then(|| view! {
+                    <option value="" selected=move || value.get().is_none()>Not in the file</option>
+                })}
+                {move || file.get().map(|file| file.columns.iter().enumerate().map(|(index, column)| {
+                    // Without a header the first row is data, show it to tell the columns apart
+                    let text = if has_header.get() { column.clone() } else { format!("Column {} ({})", index + 1, column) };
+                    view! {
+                        <option value=index.to_string() selected=move || value.get() == Some(index)>{text}</option>
+                    }
+                }).collect_view())}
+            </select>
+        </label>
+    }
+}
+
+#[component]
+/// A button opening a CSV file of sessions from another tracker, with a dialog to map its columns and preview the import
+pub fn Import() -> impl IntoView {
+    let messages = expect_context::<Messages>();
+    let (file, set_file) = create_signal(None::<ImportFile>);
+    let (has_header, set_has_header) = create_signal(true);
+    let (mapping, set_mapping) = create_signal(ColumnMapping::default());
+    let (preview, set_preview) = create_signal(None::<ImportPreview>);
+
+    let open = move |_| {
+        spawn_local(async move {
+            match open_import().await {
+                Ok(opened) => {
+                    set_mapping.set(guess_mapping(&opened.columns));
+                    set_has_header.set(true);
+                    set_file.set(Some(opened));
+                },
+                Err(err) => {
+                    messages.error(format!("Failed to open file: {}", err));
+                },
+            }
+        });
+    };
+
+    // Validate the rows again when the mapping changes
+    create_effect(move |_| {
+        let Some(path) = file.with(|file| file.as_ref().map(|file| file.path.clone())) else {
+            set_preview.set(None);
+            return;
+        };
+        let options = ImportOptions { has_header: has_header.get(), mapping: mapping.get() };
+        spawn_local(async move {
+            match preview_import(&path, options).await {
+                Ok(preview) => set_preview.set(Some(preview)),
+                Err(err) => {
+                    set_preview.set(None);
+                    messages.error(format!("Failed to read file: {}", err));
+                },
+            }
+        });
+    });
+
+    let import = move |_| {
+        let Some(path) = file.with_untracked(|file| file.as_ref().map(|file| file.path.clone())) else {
+            return;
+        };
+        let options = ImportOptions { has_header: has_header.get_untracked(), mapping: mapping.get_untracked() };
+        spawn_local(async move {
+            match import_sessions(&path, options).await {
+                Ok(report) if report.rejected.is_empty() => {
+                    set_file.set(None);
+                    messages.success(format!("Imported {} sessions", report.imported));
+                },
+                Ok(report) => {
+                    set_file.set(None);
+                    messages.warning(format!("Imported {} sessions, {} rows rejected", report.imported, report.rejected.len()));
+                },
+                Err(err) => {
+                    messages.error(format!("Failed to import sessions: {}", err));
+                },
+            }
+        });
+    };
+
+    let render_preview = move |preview: ImportPreview| {
+        let count = preview.sessions.len();
+        let sessions = preview.sessions.into_iter().take(PREVIEW_ROWS).map(|session| {
+            let tags = session.tags.iter().map(|tag| format!("#{}", tag)).collect::<Vec<_>>().join(" ");
+            view! {
+                <tr>
+                    <td>{session.name}{" "}<span class="opacity-60">{tags}</span></td>
+                    <td>{datetime_local_value(session.start_time).replace('T', " ")}</td>
+                    <td class="text-right">{format_duration(session.end_time - session.start_time)}</td>
+                </tr>
+            }
+        }).collect_view();
+        let rejected = preview.rejected.into_iter().map(|row| view! {
+            <li>{format!("Line {} : {}", row.line, row.reason)}</li>
+        }).collect_view();
+        view! {
+            <p class="text-sm">{format!("{} sessions to import", count)}</p>
+            <table class="table table-sm">
+                <thead><tr><th>Activity</th><th>Start</th><th class="text-right">Duration</th></tr></thead>
+                <tbody>{sessions}</tbody>
+            </table>
+            <ul class="text-sm text-error max-h-32 overflow-y-scroll">{rejected}</ul>
+        }
+    };
+
+    view! {
+        <button class="btn" on:click=open>{"Import"}</button>
+        <dialog open=move || file.with(Option::is_some) class="modal">
+            <div class="modal-box flex flex-col gap-4 max-w-3xl">
+                <h3 class="font-bold text-lg">Import sessions</h3>
+                <label class="label cursor-pointer">
+                    <span class="label-text">The first row holds the names of the columns</span>
+                    <input type="checkbox" class="toggle" prop:checked=has_header on:change=move |ev| set_has_header.set(event_target_checked(&ev))/>
+                </label>
+                <div class="grid grid-cols-3 gap-2">
+                    <ColumnSelect label="Activity" file=file has_header=has_header
+                        value=Signal::derive(move || Some(mapping.get().name))
+                        on_change=Callback::new(move |index: Option<usize>| set_mapping.update(|mapping| mapping.name = index.unwrap_or_default()))/>
+                    <ColumnSelect label="Start" file=file has_header=has_header
+                        value=Signal::derive(move || Some(mapping.get().start))
+                        on_change=Callback::new(move |index: Option<usize>| set_mapping.update(|mapping| mapping.start = index.unwrap_or_default()))/>
+                    <ColumnSelect label="End" file=file has_header=has_header optional=true
+                        value=Signal::derive(move || mapping.get().end)
+                        on_change=Callback::new(move |index| set_mapping.update(|mapping| mapping.end = index))/>
+                    <ColumnSelect label="Duration" file=file has_header=has_header optional=true
+                        value=Signal::derive(move || mapping.get().duration)
+                        on_change=Callback::new(move |index| set_mapping.update(|mapping| mapping.duration = index))/>
+                    <ColumnSelect label="Tags" file=file has_header=has_header optional=true
+                        value=Signal::derive(move || mapping.get().tags)
+                        on_change=Callback::new(move |index| set_mapping.update(|mapping| mapping.tags = index))/>
+                    <ColumnSelect label="Notes" file=file has_header=has_header optional=true
+                        value=Signal::derive(move || mapping.get().notes)
+                        on_change=Callback::new(move |index| set_mapping.update(|mapping| mapping.notes = index))/>
+                </div>
+                <p class="text-sm">The end is used when it is set, the duration otherwise. Rows overlapping existing sessions are rejected.</p>
+                {move || preview.get().map(render_preview)}
+                <div class="modal-action">
+                    <button class="btn" on:click=move |_| set_file.set(None)>Cancel</button>
+                    <button class="btn btn-primary" on:click=import disabled=move || preview.with(|preview| preview.as_ref().is_none_or(|preview| preview.sessions.is_empty()))>Import</button>
+                </div>
+            </div>
+        </dialog>
+    }
+}
@@ -0,0 +1,87 @@
+use leptos::*;
+use web_time::Duration;
+
+use crate::{components::session_editor::{datetime_local_value, SessionEditor}, invoke::{check_integrity, repair_overlap, Issue, Repair, Session}, notifications::Messages};
+
+/// Describe a session in a single line
+fn describe(session: &Session) -> String {
+    let end = match session.end_time {
+        Some(end) => datetime_local_value(end).replace('T', " "),
+        None => "now".to_string(),
+    };
+    format!("{} ({} → {})", session.name, datetime_local_value(session.start_time).replace('T', " "), end)
+}
+
+#[component]
+/// A panel listing the overlapping and negative sessions with the actions to fix them
+/// Hidden when there is nothing to fix
+pub fn Integrity() -> impl IntoView {
+    let messages = expect_context::<Messages>();
+    let (issues, set_issues) = create_signal(Vec::new());
+    // The session being edited in the session editor
+    let (editing, set_editing) = create_signal(None::<Session>);
+
+    let update_issues = move || async move {
+        if let Ok(issues) = check_integrity().await {
+            set_issues.set(issues);
+        }
+    };
+
+    spawn_local(update_issues());
+
+    set_interval(move || {
+        spawn_local(update_issues());
+    }, Duration::from_secs(10));
+
+    let repair = move |earlier: i64, later: i64, repair: Repair| {
+        spawn_local(async move {
+            match repair_overlap(earlier, later, repair).await {
+                Ok(_) => {
+                    messages.success("Overlap repaired".to_string());
+                },
+                Err(err) => {
+                    messages.error(format!("Failed to repair overlap: {}", err));
+                },
+            }
+            update_issues().await;
+        });
+    };
+
+    let render_issue = move |issue: Issue| {
+        match issue {
+            Issue::NegativeDuration { session } => {
+                let description = describe(&session);
+                view! {
+                    <li class="flex items-center gap-4">
+                        <span class="w-full">{"Ends before it starts: "}{description}</span>
+                        <button class="btn btn-sm" on:click=move |_| set_editing.set(Some(session.clone()))>Edit</button>
+                    </li>
+                }
+            },
+            Issue::Overlap { earlier, later } => {
+                let description = format!("{} overlaps {}", describe(&later), describe(&earlier));
+                let (earlier, later) = (earlier.id, later.id);
+                view! {
+                    <li class="flex items-center gap-4">
+                        <span class="w-full">{description}</span>
+                        <button class="btn btn-sm" on:click=move |_| repair(earlier, later, Repair::TrimEarlier)>Trim earlier</button>
+                        <button class="btn btn-sm" on:click=move |_| repair(earlier, later, Repair::TrimLater)>Trim later</button>
+                        <button class="btn btn-sm" on:click=move |_| repair(earlier, later, Repair::Split)>Split</button>
+                    </li>
+                }
+            },
+        }
+    };
+
+    view! {
+        <Show when=move || issues.with(|issues| !issues.is_empty())>
+            <div class="bg-base-200 p-6 rounded-lg flex flex-col gap-2" id="integrity">
+                <h3 class="font-bold text-lg">Sessions to fix</h3>
+                <ul class="flex flex-col gap-2">
+                    {move || issues.get().into_iter().map(render_issue).collect_view()}
+                </ul>
+            </div>
+        </Show>
+        <SessionEditor session=editing set_session=set_editing on_change=move || spawn_local(update_issues())/>
+    }
+}
@@ -0,0 +1,150 @@
+use leptos::*;
+
+use crate::{components::session_editor::{datetime_local_value, parse_datetime_local}, invoke::{export_invoice, get_calendar, get_invoice, list_clients, Invoice}, notifications::Messages};
+
+/// Format an amount in cents with its currency
+pub fn format_money(cents: i64, currency: &str) -> String {
+    let sign = if cents < 0 { "-" } else { "" };
+    format!("{}{}.{:02} {}", sign, cents.abs() / 100, cents.abs() % 100, currency)
+}
+
+/// Parse an hourly rate typed in currency units to cents, None if it is empty or invalid
+pub fn parse_rate(value: &str) -> Option<i64> {
+    let rate: f64 = value.trim().replace(',', ".").parse().ok()?;
+    Some((rate * 100.0).round() as i64)
+}
+
+/// Format an hourly rate in cents as the value of an input
+pub fn rate_value(hourly_rate: Option<i64>) -> String {
+    hourly_rate.map(|rate| format!("{}.{:02}", rate / 100, rate % 100)).unwrap_or_default()
+}
+
+/// Get the range of the local dates of two date inputs, the end date included
+pub fn date_range(from: &str, to: &str) -> Option<(u64, u64)> {
+    let from = parse_datetime_local(&format!("{}T00:00", from))?;
+    // The end of the last day is used instead of the next midnight to avoid computing the next date
+    let to = parse_datetime_local(&format!("{}T23:59", to))? + 60;
+    Some((from, to))
+}
+
+#[component]
+/// A button opening a dialog to preview and export the invoice of a client over a date range
+pub fn Invoices() -> impl IntoView {
+    let messages = expect_context::<Messages>();
+    let (dialog, set_dialog) = create_signal(false);
+    let (clients, set_clients) = create_signal(Vec::new());
+    let (client, set_client) = create_signal(None::<i64>);
+    let (from, set_from) = create_signal(String::new());
+    let (to, set_to) = create_signal(String::new());
+    let (invoice, set_invoice) = create_signal(None::<Invoice>);
+
+    // Default to the current month every time the dialog opens
+    create_effect(move |_| {
+        if !dialog.get() {
+            return;
+        }
+        spawn_local(async move {
+            if let Ok(clients) = list_clients().await {
+                set_clients.set(clients);
+            }
+            if let Ok(calendar) = get_calendar().await {
+                set_from.set(datetime_local_value(calendar.month.start)[..10].to_string());
+                set_to.set(datetime_local_value(calendar.month.end - 1)[..10].to_string());
+            }
+        });
+    });
+
+    // Refresh the preview when the options change
+    create_effect(move |_| {
+        let client_id = client.get();
+        let Some((from, to)) = date_range(&from.get(), &to.get()) else {
+            set_invoice.set(None);
+            return;
+        };
+        spawn_local(async move {
+            match get_invoice(from, to, client_id).await {
+                Ok(invoice) => set_invoice.set(Some(invoice)),
+                Err(err) => {
+                    set_invoice.set(None);
+                    messages.error(format!("Failed to build invoice: {}", err));
+                },
+            }
+        });
+    });
+
+    let export = move |_| {
+        let client_id = client.get_untracked();
+        let Some((from, to)) = date_range(&from.get_untracked(), &to.get_untracked()) else {
+            messages.error("The invoice dates are required".to_string());
+            return;
+        };
+        spawn_local(async move {
+            match export_invoice(from, to, client_id).await {
+                Ok(_) => {
+                    messages.success("Invoice exported".to_string());
+                },
+                Err(err) => {
+                    messages.error(format!("Failed to export invoice: {}", err));
+                },
+            }
+        });
+    };
+
+    let render_invoice = move |invoice: Invoice| {
+        let currency = invoice.currency.clone();
+        let lines = invoice.lines.into_iter().map(|line| {
+            let project = match &line.client {
+                Some(client) => format!("{} › {}", client, line.project),
+                None => line.project.clone(),
+            };
+            view! {
+                <tr>
+                    <td>{project}</td>
+                    <td>{line.activity}</td>
+                    <td class="text-right">{format!("{:.2}", line.seconds as f64 / 3600.0)}</td>
+                    <td class="text-right">{line.hourly_rate.map(|rate| format_money(rate, &currency)).unwrap_or("No rate".to_string())}</td>
+                    <td class="text-right">{format_money(line.amount, &currency)}</td>
+                </tr>
+            }
+        }).collect_view();
+        view! {
+            <table class="table table-sm">
+                <thead><tr><th>Project</th><th>Activity</th><th class="text-right">Hours</th><th class="text-right">Rate</th><th class="text-right">Amount</th></tr></thead>
+                <tbody>{lines}</tbody>
+                <tfoot><tr>
+                    <td colspan="2">Total</td>
+                    <td class="text-right">{format!("{:.2}", invoice.seconds as f64 / 3600.0)}</td>
+                    <td></td>
+                    <td class="text-right">{format_money(invoice.amount, &invoice.currency)}</td>
+                </tr></tfoot>
+            </table>
+            <p class="text-sm">{invoice.rounding}</p>
+        }
+    };
+
+    view! {
+        <button class="btn" on:click=move |_| set_dialog.set(true)>{"Invoice"}</button>
+        <dialog open=dialog class="modal">
+            <div class="modal-box max-w-3xl flex flex-col gap-4">
+                <h3 class="font-bold text-lg">Invoice</h3>
+                <div class="flex gap-2">
+                    <select class="select select-bordered" on:change=move |ev| set_client.set(event_target_value(&ev).parse().ok())>
+                        <option value="">All clients</option>
+                        {move || clients.get().into_iter().map(|client| view!{
+                            <option value=client.id.to_string()>{client.name}</option>
+                        }).collect_view()}
+                    </select>
+                    <input class="input input-bordered" type="date" title="From" prop:value=from on:change=move |ev| set_from.set(event_target_value(&ev))/>
+                    <input class="input input-bordered" type="date" title="To" prop:value=to on:change=move |ev| set_to.set(event_target_value(&ev))/>
+                </div>
+                <div class="max-h-64 overflow-y-scroll">
+                    {move || invoice.get().map(render_invoice)}
+                </div>
+                <div class="modal-action">
+                    <button class="btn" on:click=move |_| set_dialog.set(false)>Close</button>
+                    <button class="btn btn-accent" on:click=export>Export</button>
+                </div>
+            </div>
+        </dialog>
+    }
+}
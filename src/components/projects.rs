@@ -0,0 +1,163 @@
+use leptos::*;
+
+use crate::{components::invoices::{parse_rate, rate_value}, invoke::{activity_projects, add_client, add_project, list_clients, list_projects, set_activity_project, set_client_rate, set_project_rate}, notifications::Messages};
+
+#[component]
+/// A button opening a dialog to manage the clients, the projects and the project of each activity
+pub fn Projects() -> impl IntoView {
+    let messages = expect_context::<Messages>();
+    let (dialog, set_dialog) = create_signal(false);
+    let (clients, set_clients) = create_signal(Vec::new());
+    let (projects, set_projects) = create_signal(Vec::new());
+    let (activities, set_activities) = create_signal(Vec::new());
+    let (client_name, set_client_name) = create_signal(String::new());
+    let (project_name, set_project_name) = create_signal(String::new());
+    let (project_client, set_project_client) = create_signal(None::<i64>);
+
+    let refresh = move || async move {
+        if let Ok(clients) = list_clients().await {
+            set_clients.set(clients);
+        }
+        if let Ok(projects) = list_projects().await {
+            set_projects.set(projects);
+        }
+        if let Ok(activities) = activity_projects().await {
+            set_activities.set(activities);
+        }
+    };
+
+    // Load the hierarchy every time the dialog opens
+    create_effect(move |_| {
+        if dialog.get() {
+            spawn_local(refresh());
+        }
+    });
+
+    let submit_client = move |_| {
+        let name = client_name.get_untracked();
+        spawn_local(async move {
+            match add_client(&name).await {
+                Ok(_) => {
+                    set_client_name.set(String::new());
+                    messages.success(format!("Added client: {}", name));
+                },
+                Err(err) => {
+                    messages.error(format!("Failed to add client: {}", err));
+                },
+            }
+            refresh().await;
+        });
+    };
+
+    let submit_project = move |_| {
+        let name = project_name.get_untracked();
+        let client_id = project_client.get_untracked();
+        spawn_local(async move {
+            match add_project(&name, client_id).await {
+                Ok(_) => {
+                    set_project_name.set(String::new());
+                    messages.success(format!("Added project: {}", name));
+                },
+                Err(err) => {
+                    messages.error(format!("Failed to add project: {}", err));
+                },
+            }
+            refresh().await;
+        });
+    };
+
+    let assign = move |name: String, project_id: i64| {
+        spawn_local(async move {
+            if let Err(err) = set_activity_project(&name, project_id).await {
+                messages.error(format!("Failed to move activity: {}", err));
+            }
+            refresh().await;
+        });
+    };
+
+    // An empty rate removes it, the projects without a rate use the one of their client
+    let update_client_rate = move |id: i64, value: String| {
+        spawn_local(async move {
+            if let Err(err) = set_client_rate(id, parse_rate(&value)).await {
+                messages.error(format!("Failed to set rate: {}", err));
+            }
+            refresh().await;
+        });
+    };
+
+    let update_project_rate = move |id: i64, value: String| {
+        spawn_local(async move {
+            if let Err(err) = set_project_rate(id, parse_rate(&value)).await {
+                messages.error(format!("Failed to set rate: {}", err));
+            }
+            refresh().await;
+        });
+    };
+
+    let render_activity = move |(name, project_id): (String, i64)| {
+        let on_change = {
+            let name = name.clone();
+            move |ev| {
+                if let Ok(project_id) = event_target_value(&ev).parse() {
+                    assign(name.clone(), project_id);
+                }
+            }
+        };
+        view! {
+            <li class="flex items-center gap-4">
+                <span class="w-full truncate">{name}</span>
+                <select class="select select-sm" on:change=on_change>
+                    {move || projects.get().into_iter().map(|project| view!{
+                        <option value=project.id.to_string() selected=project.id == project_id>{project.label()}</option>
+                    }).collect_view()}
+                </select>
+            </li>
+        }
+    };
+
+    view! {
+        <button class="btn" on:click=move |_| set_dialog.set(true)>{"Projects"}</button>
+        <dialog open=dialog class="modal">
+            <div class="modal-box flex flex-col gap-4">
+                <h3 class="font-bold text-lg">Projects</h3>
+                <div class="flex gap-2">
+                    <input class="input input-bordered w-full" type="text" placeholder="New client" prop:value=client_name on:change=move |ev| set_client_name.set(event_target_value(&ev))/>
+                    <button class="btn btn-secondary" on:click=submit_client>Add</button>
+                </div>
+                <div class="flex gap-2">
+                    <input class="input input-bordered w-full" type="text" placeholder="New project" prop:value=project_name on:change=move |ev| set_project_name.set(event_target_value(&ev))/>
+                    <select class="select select-bordered" on:change=move |ev| set_project_client.set(event_target_value(&ev).parse().ok())>
+                        <option value="">No client</option>
+                        {move || clients.get().into_iter().map(|client| view!{
+                            <option value=client.id.to_string()>{client.name}</option>
+                        }).collect_view()}
+                    </select>
+                    <button class="btn btn-secondary" on:click=submit_project>Add</button>
+                </div>
+                <details class="collapse collapse-arrow bg-base-200">
+                    <summary class="collapse-title">Hourly rates</summary>
+                    <ul class="collapse-content flex flex-col gap-2 max-h-64 overflow-y-scroll">
+                        {move || clients.get().into_iter().map(|client| view!{
+                            <li class="flex items-center gap-4">
+                                <span class="w-full truncate font-bold">{client.name}</span>
+                                <input class="input input-sm input-bordered w-32" type="number" min="0" step="0.01" placeholder="No rate" prop:value=rate_value(client.hourly_rate) on:change=move |ev| update_client_rate(client.id, event_target_value(&ev))/>
+                            </li>
+                        }).collect_view()}
+                        {move || projects.get().into_iter().map(|project| view!{
+                            <li class="flex items-center gap-4">
+                                <span class="w-full truncate">{project.label()}</span>
+                                <input class="input input-sm input-bordered w-32" type="number" min="0" step="0.01" placeholder="Client rate" prop:value=rate_value(project.hourly_rate) on:change=move |ev| update_project_rate(project.id, event_target_value(&ev))/>
+                            </li>
+                        }).collect_view()}
+                    </ul>
+                </details>
+                <ul class="flex flex-col gap-2 max-h-64 overflow-y-scroll">
+                    {move || activities.get().into_iter().map(render_activity).collect_view()}
+                </ul>
+                <div class="modal-action">
+                    <button class="btn btn-primary" on:click=move |_| set_dialog.set(false)>Close</button>
+                </div>
+            </div>
+        </dialog>
+    }
+}
@@ -1,9 +1,10 @@
 use closure::closure;
-use leptos::{html::Input, *};
+use leptos::{html::{Input, Textarea}, *};
 use log::info;
 use web_sys::{KeyboardEvent, MouseEvent, SubmitEvent};
+use web_time::Duration;
 
-use crate::{invoke::{get_current_activity, list_activities, start_activity, stop_activity}, notifications::Messages};
+use crate::{components::{countdown::Countdown, session_editor::parse_datetime_local}, invoke::{add_session, get_ask_notes_on_stop, get_current_activity, is_paused, list_activities, listen, pause_activity, resume_activity, start_activity, start_timebox, stop_activity}, notifications::Messages};
 
 pub fn offset_string(offset: i64) -> String {
     if offset == 0 {
@@ -95,16 +96,125 @@ pub fn OffsetModal<ModalCb: Fn(bool) + Clone + 'static, OffsetCb: Fn(i64) + 'sta
     }
 }
 
+#[component]
+/// Asks what was done during the activity being stopped
+/// 
+/// notes_cb is called with the notes when they are saved and with None when they are skipped
+pub fn NotesModal<ModalCb: Fn(bool) + Clone + 'static, NotesCb: Fn(Option<String>) + Clone + 'static>(open: ReadSignal<bool>, modal_cb: ModalCb, notes_cb: NotesCb) -> impl IntoView{
+    let element_ref = create_node_ref::<Textarea>();
+
+    create_effect(move |_| {
+        if !open.get() {return;}
+        let Some(element) = element_ref.get() else {return;};
+
+        element.set_value("");
+        if let Err(e) = element.focus() {
+            log::error!("Failed to focus notes: {:?}", e);
+        }
+    });
+
+    let submit = closure!(clone modal_cb, clone notes_cb, |notes: Option<String>| {
+        notes_cb(notes);
+        modal_cb(false);
+    });
+
+    let handle_key = closure!(clone modal_cb, clone submit, |ev: KeyboardEvent| {
+        match ev.key().as_str() {
+            "Escape" => {
+                modal_cb(false);
+            },
+            // Shift+Enter adds a new line
+            "Enter" if !ev.shift_key() => {
+                ev.prevent_default();
+                submit(element_ref.get_untracked().map(|element| element.value()));
+            },
+            _ => {},
+        }
+    });
+
+    view! {
+        <dialog open=open class="modal" on:keydown=handle_key>
+            <div class="modal-box flex flex-col gap-4">
+                <h3 class="text-lg w-full">What did you do?</h3>
+                <textarea _ref=element_ref class="textarea textarea-bordered w-full" rows="3" placeholder="Notes"></textarea>
+                <div class="modal-action w-full flex justify-end">
+                    <button on:click=closure!(clone modal_cb, |_| modal_cb(false))>Cancel</button>
+                    <button class="btn" on:click=closure!(clone submit, |_| submit(None))>Skip</button>
+                    <button class="btn btn-primary" on:click=move |_| submit(element_ref.get_untracked().map(|element| element.value()))>Save</button>
+                </div>
+            </div>
+        </dialog>
+    }
+}
+
+#[component]
+/// A form to log a past session with an explicit start and end, without touching the running activity
+pub fn ManualEntry() -> impl IntoView {
+    let messages = expect_context::<Messages>();
+    let (name, set_name) = create_signal(String::new());
+    let (start_time, set_start_time) = create_signal(None::<u64>);
+    let (end_time, set_end_time) = create_signal(None::<u64>);
+
+    let submit = move |ev: SubmitEvent| {
+        ev.prevent_default();
+
+        let name = name.get_untracked();
+        let (Some(start_time), Some(end_time)) = (start_time.get_untracked(), end_time.get_untracked()) else {
+            messages.error("The start and end of the session are required".to_string());
+            return;
+        };
+
+        spawn_local(async move {
+            match add_session(&name, start_time, end_time).await {
+                Ok(_) => {
+                    messages.success(format!("Added session: {}", name));
+                },
+                Err(err) => {
+                    messages.error(format!("Failed to add session: {}", err));
+                },
+            }
+        });
+    };
+
+    view! {
+        <form class="flex items-center gap-4" on:submit=submit>
+            <input list="known-activity" class="input w-full" type="text" placeholder="Activity #tag" on:change=move |ev| set_name.set(event_target_value(&ev))/>
+            <input class="input" type="datetime-local" title="Start" on:change=move |ev| set_start_time.set(parse_datetime_local(&event_target_value(&ev)))/>
+            <input class="input" type="datetime-local" title="End" on:change=move |ev| set_end_time.set(parse_datetime_local(&event_target_value(&ev)))/>
+            <input type="submit" class="btn btn-secondary" value="Add" />
+        </form>
+    }
+}
+
 #[component]
 pub fn Reporting()-> impl IntoView{
     let (activity_name, set_activity_name) = create_signal(String::new());
     let (activities, set_activities) = create_signal(Vec::new());
     let (offset_modal_open, set_offset_modal_open) = create_signal(false);
+    let (notes_modal_open, set_notes_modal_open) = create_signal(false);
+    // The offset of the stop waiting for the notes
+    let (stop_offset, set_stop_offset) = create_signal(0i64);
     // The action to perform when the form is submitted true for start, false for stop
     let (action, set_action) = create_signal(true);
+    let (paused, set_paused) = create_signal(false);
+    // The length of the timebox in minutes, no timebox if not set
+    let (timebox, set_timebox) = create_signal(None::<u32>);
 
     let messages = expect_context::<Messages>();
 
+    let stop = move |offset: i64, notes: Option<String>| async move {
+        let activity = activity_name.get_untracked();
+        let res = stop_activity(offset, notes).await;
+        match res {
+            Ok(_) => {
+                set_paused.set(false);
+                messages.success(format!("Stopped activity: {}", activity));
+            },
+            Err(_) => {
+                messages.error(format!("Failed to stop activity"));
+            }
+        }
+    };
 
     let offset_submit = closure!(
         clone action,
@@ -115,34 +225,42 @@ pub fn Reporting()-> impl IntoView{
 
             if action {
                 let activity = activity_name.get_untracked();
+                let timebox = timebox.get_untracked();
                 spawn_local(async move {
-                    let res = start_activity(&activity, offset).await;
+                    let res = match timebox {
+                        Some(minutes) => start_timebox(&activity, offset, minutes).await,
+                        None => start_activity(&activity, offset).await.map_err(|_| "Failed to start activity".to_string()),
+                    };
                     match res {
                         Ok(_) => {
+                            set_paused.set(false);
                             messages.success(format!("Started activity: {}", activity));
                         },
-                        Err(_) => {
-                            messages.error(format!("Failed to start activity"));
+                        Err(err) => {
+                            messages.error(format!("Failed to start activity: {}", err));
                         }
                     }
                 });
             } else {
-                let activity = activity_name.get_untracked();
                 spawn_local(async move {
-                    let res = stop_activity(offset).await;
-                    match res {
-                        Ok(_) => {
-                            messages.success(format!("Stopped activity: {}", activity));
-                        },
-                        Err(_) => {
-                            messages.error(format!("Failed to stop activity"));
-                        }
+                    if get_ask_notes_on_stop().await.unwrap_or(true) {
+                        set_stop_offset.set(offset);
+                        set_notes_modal_open.set(true);
+                    } else {
+                        stop(offset, None).await;
                     }
                 });
             }
         }
     );
 
+    let notes_submit = move |notes: Option<String>| {
+        let offset = stop_offset.get_untracked();
+        spawn_local(async move {
+            stop(offset, notes).await;
+        });
+    };
+
     // Update the activity name when the input value changes
     let update_value = move |event| {
         set_activity_name.set(event_target_value(&event));
@@ -165,13 +283,49 @@ pub fn Reporting()-> impl IntoView{
     });
 
 
-    spawn_local({
-        let set_activity_name = set_activity_name.clone();
-        async move {
-            let activity = get_current_activity().await;
-            set_activity_name.set(activity);
+    // The break ends when the activity resumes, the paused time doesn't count toward the activity
+    let toggle_pause = move |ev: MouseEvent| {
+        ev.prevent_default();
+        let resume = paused.get_untracked();
+        spawn_local(async move {
+            let res = if resume {
+                resume_activity(0).await
+            } else {
+                pause_activity(0).await
+            };
+            match res {
+                Ok(_) => {
+                    set_paused.set(!resume);
+                    messages.success(if resume { "Activity resumed" } else { "Activity paused" }.to_string());
+                },
+                Err(err) => {
+                    messages.error(format!("Failed to {} activity: {}", if resume { "resume" } else { "pause" }, err));
+                }
+            }
+        });
+    };
+
+    // Sent by the backend when nothing runs during the working hours
+    listen("reminder", move |minutes: u64| {
+        messages.warning(format!("Nothing has been running for {} minutes", minutes));
+    });
+
+    // The running activity last seen in the backend, it can also be changed by the command line client
+    let (running, set_running) = create_signal(None::<String>);
+    let refresh_running = move || spawn_local(async move {
+        let activity = get_current_activity().await;
+        if running.get_untracked().as_ref() != Some(&activity) {
+            set_activity_name.set(activity.clone());
+            set_running.set(Some(activity));
+        }
+        if let Ok(paused) = is_paused().await {
+            set_paused.set(paused);
         }
     });
+    refresh_running();
+    set_interval(refresh_running, Duration::from_secs(5));
+    // Sent by the backend when the running activity changes outside of the window
+    listen("activity", move |_: String| refresh_running());
 
     spawn_local(
         async move {
@@ -188,13 +342,25 @@ pub fn Reporting()-> impl IntoView{
     view! {
         <>
         <form class="bg-base-200 p-6 flex items-center rounded-lg gap-4" id="reporting" on:submit=start_activity>
-            <input id="activity-input" list="known-activity" class="input w-full" type="text" placeholder="Activity" on:change=update_value value=activity_name/>
+            <input id="activity-input" list="known-activity" class="input w-full" type="text" placeholder="Activity #tag" on:change=update_value value=activity_name/>
             <datalist id="known-activity">
                 {move ||activities.get().into_iter().map(|activity| view!{<option value=activity/>}).collect_view()}
             </datalist>
+            <input class="input w-24" type="number" min="1" placeholder="min" title="Timebox length in minutes" on:change=move |ev| set_timebox.set(event_target_value(&ev).parse().ok().filter(|minutes| *minutes > 0))/>
+            <Show when=move || paused.get()>
+                <span class="badge badge-warning">Paused</span>
+            </Show>
             <input type="submit" class="btn btn-primary" value="Start!" />
+            <button class="btn btn-warning" on:click=toggle_pause>{move || if paused.get() { "Resume" } else { "Pause" }}</button>
             <button class="btn btn-error" on:click=stop_activity>{"Stop!"}</button>
         </form>
+        <Countdown/>
+        <details class="collapse collapse-arrow bg-base-200 rounded-lg">
+            <summary class="collapse-title">Add a past session</summary>
+            <div class="collapse-content">
+                <ManualEntry/>
+            </div>
+        </details>
         <OffsetModal 
             open=offset_modal_open
             modal_cb={move|v|{
@@ -203,6 +369,13 @@ pub fn Reporting()-> impl IntoView{
             }}
             offset_cb=offset_submit
             />
+        <NotesModal
+            open=notes_modal_open
+            modal_cb={move|v|{
+                set_notes_modal_open.set(v);
+            }}
+            notes_cb=notes_submit
+            />
         </>
     }
 
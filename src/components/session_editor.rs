@@ -0,0 +1,151 @@
+use leptos::*;
+use wasm_bindgen::JsValue;
+
+use crate::{invoke::{delete_session, set_session_billable, set_session_notes, set_session_tags, update_session, Session}, notifications::Messages};
+
+/// Format a time in seconds since the epoch as the value of a datetime-local input, in the local timezone
+pub fn datetime_local_value(time: u64) -> String {
+    let date = js_sys::Date::new(&JsValue::from_f64(time as f64 * 1000.0));
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}",
+        date.get_full_year(),
+        date.get_month() + 1,
+        date.get_date(),
+        date.get_hours(),
+        date.get_minutes()
+    )
+}
+
+/// Parse the value of a datetime-local input, in the local timezone, to a time in seconds since the epoch
+pub fn parse_datetime_local(value: &str) -> Option<u64> {
+    if value.is_empty() {
+        return None;
+    }
+
+    // Date-time strings without an offset are parsed as local time
+    let time = js_sys::Date::new(&JsValue::from_str(value)).get_time();
+    if time.is_nan() {
+        return None;
+    }
+
+    Some((time / 1000.0) as u64)
+}
+
+#[component]
+/// A dialog to edit or delete a session, open while `session` is set
+///
+/// # Arguments
+/// session - The session to edit, None to close the dialog
+/// set_session - Used to close the dialog
+/// on_change - Called after the session was updated or deleted
+pub fn SessionEditor<OnChange: Fn() + Clone + 'static>(session: ReadSignal<Option<Session>>, set_session: WriteSignal<Option<Session>>, on_change: OnChange) -> impl IntoView {
+    let messages = expect_context::<Messages>();
+    let (name, set_name) = create_signal(String::new());
+    let (start_time, set_start_time) = create_signal(0u64);
+    let (end_time, set_end_time) = create_signal(None::<u64>);
+    let (tags, set_tags) = create_signal(String::new());
+    let (notes, set_notes) = create_signal(String::new());
+    let (billable, set_billable) = create_signal(true);
+
+    // Reset the fields every time a new session is edited
+    create_effect(move |_| {
+        if let Some(session) = session.get() {
+            set_name.set(session.name);
+            set_start_time.set(session.start_time);
+            set_end_time.set(session.end_time);
+            set_tags.set(session.tags.iter().map(|tag| format!("#{}", tag)).collect::<Vec<_>>().join(" "));
+            set_notes.set(session.notes.unwrap_or_default());
+            set_billable.set(session.billable);
+        }
+    });
+
+    let update_start = move |ev| {
+        match parse_datetime_local(&event_target_value(&ev)) {
+            Some(time) => set_start_time.set(time),
+            None => messages.error("Invalid start time".to_string()),
+        }
+    };
+
+    let update_end = move |ev| {
+        set_end_time.set(parse_datetime_local(&event_target_value(&ev)));
+    };
+
+    let save = {
+        let on_change = on_change.clone();
+        move |_| {
+            let Some(session) = session.get_untracked() else { return; };
+            let on_change = on_change.clone();
+            spawn_local(async move {
+                let res = update_session(session.id, &name.get_untracked(), start_time.get_untracked(), end_time.get_untracked()).await;
+                let tags = tags.get_untracked().split_whitespace().map(str::to_string).collect();
+                let res = match res {
+                    Ok(_) => set_session_tags(session.id, tags).await,
+                    Err(err) => Err(err),
+                };
+                let res = match res {
+                    Ok(_) => set_session_notes(session.id, Some(notes.get_untracked())).await,
+                    Err(err) => Err(err),
+                };
+                let res = match res {
+                    Ok(_) => set_session_billable(session.id, billable.get_untracked()).await,
+                    Err(err) => Err(err),
+                };
+                match res {
+                    Ok(_) => {
+                        messages.success("Session updated".to_string());
+                        set_session.set(None);
+                        on_change();
+                    },
+                    Err(err) => {
+                        messages.error(format!("Failed to update session: {}", err));
+                    },
+                }
+            });
+        }
+    };
+
+    let delete = move |_| {
+        let Some(session) = session.get_untracked() else { return; };
+        let on_change = on_change.clone();
+        spawn_local(async move {
+            match delete_session(session.id).await {
+                Ok(_) => {
+                    messages.success("Session deleted".to_string());
+                    set_session.set(None);
+                    on_change();
+                },
+                Err(err) => {
+                    messages.error(format!("Failed to delete session: {}", err));
+                },
+            }
+        });
+    };
+
+    view! {
+        <dialog open=move || session.with(Option::is_some) class="modal">
+            <div class="modal-box flex flex-col gap-2">
+                <h3 class="font-bold text-lg">Edit session</h3>
+                <input class="input input-bordered w-full" type="text" placeholder="Activity" prop:value=name on:change=move |ev| set_name.set(event_target_value(&ev))/>
+                <input class="input input-bordered w-full" type="text" placeholder="#tags" prop:value=tags on:change=move |ev| set_tags.set(event_target_value(&ev))/>
+                <label class="flex items-center gap-2">
+                    <span class="w-12">Start</span>
+                    <input class="input input-bordered w-full" type="datetime-local" prop:value=move || datetime_local_value(start_time.get()) on:change=update_start/>
+                </label>
+                <label class="flex items-center gap-2">
+                    <span class="w-12">End</span>
+                    <input class="input input-bordered w-full" type="datetime-local" prop:value=move || end_time.get().map(datetime_local_value).unwrap_or_default() on:change=update_end/>
+                </label>
+                <textarea class="textarea textarea-bordered w-full" rows="3" placeholder="Notes" prop:value=notes on:change=move |ev| set_notes.set(event_target_value(&ev))></textarea>
+                <label class="label cursor-pointer">
+                    <span class="label-text">Billable</span>
+                    <input type="checkbox" class="checkbox" prop:checked=billable on:change=move |ev| set_billable.set(event_target_checked(&ev))/>
+                </label>
+                <div class="modal-action">
+                    <button class="btn btn-error" on:dblclick=delete>Delete (Double click)</button>
+                    <button class="btn" on:click=move |_| set_session.set(None)>Cancel</button>
+                    <button class="btn btn-primary" on:click=save>Save</button>
+                </div>
+            </div>
+        </dialog>
+    }
+}
@@ -0,0 +1,320 @@
+use leptos::*;
+
+use crate::{invoke::{get_api_settings, get_ask_notes_on_stop, get_close_to_tray, get_currency, get_day_start_hour, get_idle_minutes, get_pomodoro_auto_breaks, get_pomodoro_break_minutes, get_reminder_minutes, get_rounding, get_working_hours, regenerate_api_token, set_api_settings, set_ask_notes_on_stop, set_close_to_tray, set_currency, set_day_start_hour, set_idle_minutes, set_pomodoro_auto_breaks, set_pomodoro_break_minutes, set_reminder_minutes, set_rounding, set_working_hours, ApiSettings, Rounding, RoundingMode, RoundingScope, WorkingHours}, notifications::Messages};
+
+#[component]
+/// A button opening a dialog to edit the settings of the application
+pub fn Settings() -> impl IntoView {
+    let messages = expect_context::<Messages>();
+    let (dialog, set_dialog) = create_signal(false);
+    let (day_start_hour, set_day_start_hour_value) = create_signal(0u32);
+    let (ask_notes_on_stop, set_ask_notes_on_stop_value) = create_signal(true);
+    let (close_to_tray, set_close_to_tray_value) = create_signal(true);
+    let (currency, set_currency_value) = create_signal(String::new());
+    let (break_minutes, set_break_minutes_value) = create_signal(5u32);
+    let (auto_breaks, set_auto_breaks_value) = create_signal(false);
+    let (idle_minutes, set_idle_minutes_value) = create_signal(5u32);
+    let (working_hours, set_working_hours_value) = create_signal(WorkingHours::default());
+    let (reminder_minutes, set_reminder_minutes_value) = create_signal(15u32);
+    let (rounding, set_rounding_value) = create_signal(Rounding::default());
+    let (api, set_api_value) = create_signal(ApiSettings::default());
+
+    // Load the current settings every time the dialog opens
+    create_effect(move |_| {
+        if !dialog.get() {
+            return;
+        }
+        spawn_local(async move {
+            if let Ok(hour) = get_day_start_hour().await {
+                set_day_start_hour_value.set(hour);
+            }
+            if let Ok(ask) = get_ask_notes_on_stop().await {
+                set_ask_notes_on_stop_value.set(ask);
+            }
+            if let Ok(close_to_tray) = get_close_to_tray().await {
+                set_close_to_tray_value.set(close_to_tray);
+            }
+            if let Ok(currency) = get_currency().await {
+                set_currency_value.set(currency);
+            }
+            if let Ok(minutes) = get_pomodoro_break_minutes().await {
+                set_break_minutes_value.set(minutes);
+            }
+            if let Ok(auto_breaks) = get_pomodoro_auto_breaks().await {
+                set_auto_breaks_value.set(auto_breaks);
+            }
+            if let Ok(minutes) = get_idle_minutes().await {
+                set_idle_minutes_value.set(minutes);
+            }
+            if let Ok(hours) = get_working_hours().await {
+                set_working_hours_value.set(hours);
+            }
+            if let Ok(minutes) = get_reminder_minutes().await {
+                set_reminder_minutes_value.set(minutes);
+            }
+            if let Ok(rounding) = get_rounding().await {
+                set_rounding_value.set(rounding);
+            }
+            if let Ok(api) = get_api_settings().await {
+                set_api_value.set(api);
+            }
+        });
+    });
+
+    let update_day_start_hour = move |ev| {
+        match event_target_value(&ev).parse() {
+            Ok(hour) => set_day_start_hour_value.set(hour),
+            Err(_) => messages.error("The day start hour must be a number".to_string()),
+        }
+    };
+
+    let save = move |_| {
+        set_dialog.set(false);
+
+        let hour = day_start_hour.get_untracked();
+        let ask = ask_notes_on_stop.get_untracked();
+        let close_to_tray = close_to_tray.get_untracked();
+        let currency = currency.get_untracked();
+        let minutes = break_minutes.get_untracked();
+        let auto = auto_breaks.get_untracked();
+        let idle = idle_minutes.get_untracked();
+        let hours = working_hours.get_untracked();
+        let reminder = reminder_minutes.get_untracked();
+        let rounding = rounding.get_untracked();
+        let api = api.get_untracked();
+        spawn_local(async move {
+            let res = match set_day_start_hour(hour).await {
+                Ok(_) => set_ask_notes_on_stop(ask).await,
+                Err(err) => Err(err),
+            };
+            let res = match res {
+                Ok(_) => set_close_to_tray(close_to_tray).await,
+                Err(err) => Err(err),
+            };
+            let res = match res {
+                Ok(_) => set_currency(&currency).await,
+                Err(err) => Err(err),
+            };
+            let res = match res {
+                Ok(_) => set_pomodoro_break_minutes(minutes).await,
+                Err(err) => Err(err),
+            };
+            let res = match res {
+                Ok(_) => set_pomodoro_auto_breaks(auto).await,
+                Err(err) => Err(err),
+            };
+            let res = match res {
+                Ok(_) => set_idle_minutes(idle).await,
+                Err(err) => Err(err),
+            };
+            let res = match res {
+                Ok(_) => set_working_hours(hours).await,
+                Err(err) => Err(err),
+            };
+            let res = match res {
+                Ok(_) => set_reminder_minutes(reminder).await,
+                Err(err) => Err(err),
+            };
+            let res = match res {
+                Ok(_) => set_rounding(rounding).await,
+                Err(err) => Err(err),
+            };
+            let res = match res {
+                Ok(_) => set_api_settings(api).await,
+                Err(err) => Err(err),
+            };
+            match res {
+                Ok(_) => {
+                    messages.success("Settings saved".to_string());
+                },
+                Err(err) => {
+                    messages.error(format!("Failed to save settings: {}", err));
+                },
+            }
+        });
+    };
+
+    // Takes effect right away, the tools using the previous token are refused
+    let regenerate_token = move |_| {
+        spawn_local(async move {
+            match regenerate_api_token().await {
+                Ok(token) => {
+                    set_api_value.update(|api| api.token = token);
+                    messages.success("New API token generated".to_string());
+                },
+                Err(err) => {
+                    messages.error(format!("Failed to generate an API token: {}", err));
+                },
+            }
+        });
+    };
+
+    view! {
+        <button class="btn" on:click=move |_| set_dialog.set(true)>{"Settings"}</button>
+        <dialog open=dialog class="modal">
+            <div class="modal-box">
+                <h3 class="font-bold text-lg">Settings</h3>
+                <label class="form-control w-full">
+                    <div class="label">
+                        <span class="label-text">Day starts at (hour)</span>
+                    </div>
+                    <input type="number" min="0" max="23" class="input input-bordered w-full" prop:value=move || day_start_hour.get().to_string() on:change=update_day_start_hour/>
+                    <div class="label">
+                        <span class="label-text-alt">Activities started before this hour count toward the previous day</span>
+                    </div>
+                </label>
+                <label class="form-control w-full">
+                    <div class="label">
+                        <span class="label-text">Currency of the invoices</span>
+                    </div>
+                    <input type="text" class="input input-bordered w-full" prop:value=currency on:change=move |ev| set_currency_value.set(event_target_value(&ev))/>
+                </label>
+                <label class="label cursor-pointer">
+                    <span class="label-text">Ask what was done when stopping an activity</span>
+                    <input type="checkbox" class="toggle" prop:checked=ask_notes_on_stop on:change=move |ev| set_ask_notes_on_stop_value.set(event_target_checked(&ev))/>
+                </label>
+                <label class="label cursor-pointer">
+                    <span class="label-text">Keep running in the system tray when the window is closed</span>
+                    <input type="checkbox" class="toggle" prop:checked=close_to_tray on:change=move |ev| set_close_to_tray_value.set(event_target_checked(&ev))/>
+                </label>
+                <label class="form-control w-full">
+                    <div class="label">
+                        <span class="label-text">Pomodoro break length (minutes)</span>
+                    </div>
+                    <input type="number" min="1" class="input input-bordered w-full" prop:value=move || break_minutes.get().to_string() on:change=move |ev| {
+                        match event_target_value(&ev).parse() {
+                            Ok(minutes) => set_break_minutes_value.set(minutes),
+                            Err(_) => messages.error("The break length must be a number".to_string()),
+                        }
+                    }/>
+                </label>
+                <label class="label cursor-pointer">
+                    <span class="label-text">Take a break at the end of each timebox</span>
+                    <input type="checkbox" class="toggle" prop:checked=auto_breaks on:change=move |ev| set_auto_breaks_value.set(event_target_checked(&ev))/>
+                </label>
+                <label class="form-control w-full">
+                    <div class="label">
+                        <span class="label-text">Away after (minutes without input)</span>
+                    </div>
+                    <input type="number" min="0" class="input input-bordered w-full" prop:value=move || idle_minutes.get().to_string() on:change=move |ev| {
+                        match event_target_value(&ev).parse() {
+                            Ok(minutes) => set_idle_minutes_value.set(minutes),
+                            Err(_) => messages.error("The idle time must be a number".to_string()),
+                        }
+                    }/>
+                    <div class="label">
+                        <span class="label-text-alt">0 turns the idle detection off</span>
+                    </div>
+                </label>
+                <label class="form-control w-full">
+                    <div class="label">
+                        <span class="label-text">Working hours</span>
+                    </div>
+                    <div class="flex items-center gap-2">
+                        <input type="number" min="0" max="23" class="input input-bordered w-full" prop:value=move || working_hours.get().start.to_string() on:change=move |ev| {
+                            match event_target_value(&ev).parse() {
+                                Ok(start) => set_working_hours_value.update(|hours| hours.start = start),
+                                Err(_) => messages.error("The working hours must be numbers".to_string()),
+                            }
+                        }/>
+                        <span>to</span>
+                        <input type="number" min="1" max="24" class="input input-bordered w-full" prop:value=move || working_hours.get().end.to_string() on:change=move |ev| {
+                            match event_target_value(&ev).parse() {
+                                Ok(end) => set_working_hours_value.update(|hours| hours.end = end),
+                                Err(_) => messages.error("The working hours must be numbers".to_string()),
+                            }
+                        }/>
+                    </div>
+                    <div class="label">
+                        <span class="label-text-alt">Shown on the timeline</span>
+                    </div>
+                </label>
+                <label class="form-control w-full">
+                    <div class="label">
+                        <span class="label-text">Remind after (minutes with nothing running)</span>
+                    </div>
+                    <input type="number" min="0" class="input input-bordered w-full" prop:value=move || reminder_minutes.get().to_string() on:change=move |ev| {
+                        match event_target_value(&ev).parse() {
+                            Ok(minutes) => set_reminder_minutes_value.set(minutes),
+                            Err(_) => messages.error("The reminder delay must be a number".to_string()),
+                        }
+                    }/>
+                    <div class="label">
+                        <span class="label-text-alt">Only during the working hours, 0 turns the reminders off</span>
+                    </div>
+                </label>
+                <label class="form-control w-full">
+                    <div class="label">
+                        <span class="label-text">Rounding of the exported times</span>
+                    </div>
+                    <div class="flex items-center gap-2">
+                        <select class="select select-bordered" on:change=move |ev| {
+                            let mode = match event_target_value(&ev).as_str() {
+                                "nearest" => RoundingMode::Nearest,
+                                "up" => RoundingMode::Up,
+                                "down" => RoundingMode::Down,
+                                _ => RoundingMode::None,
+                            };
+                            set_rounding_value.update(|rounding| rounding.mode = mode);
+                        }>
+                            <option value="none" selected=move || rounding.get().mode == RoundingMode::None>No rounding</option>
+                            <option value="nearest" selected=move || rounding.get().mode == RoundingMode::Nearest>Nearest</option>
+                            <option value="up" selected=move || rounding.get().mode == RoundingMode::Up>Up</option>
+                            <option value="down" selected=move || rounding.get().mode == RoundingMode::Down>Down</option>
+                        </select>
+                        <input type="number" min="1" class="input input-bordered w-full" prop:value=move || rounding.get().increment.to_string() on:change=move |ev| {
+                            match event_target_value(&ev).parse() {
+                                Ok(increment) => set_rounding_value.update(|rounding| rounding.increment = increment),
+                                Err(_) => messages.error("The rounding increment must be a number".to_string()),
+                            }
+                        }/>
+                        <span>minutes</span>
+                        <select class="select select-bordered" on:change=move |ev| {
+                            let scope = if event_target_value(&ev) == "session" { RoundingScope::Session } else { RoundingScope::Total };
+                            set_rounding_value.update(|rounding| rounding.scope = scope);
+                        }>
+                            <option value="total" selected=move || rounding.get().scope == RoundingScope::Total>On the totals</option>
+                            <option value="session" selected=move || rounding.get().scope == RoundingScope::Session>Per session</option>
+                        </select>
+                    </div>
+                </label>
+                <label class="label cursor-pointer">
+                    <span class="label-text">Round the statistics too</span>
+                    <input type="checkbox" class="toggle" prop:checked=move || rounding.get().statistics on:change=move |ev| {
+                        let statistics = event_target_checked(&ev);
+                        set_rounding_value.update(|rounding| rounding.statistics = statistics);
+                    }/>
+                </label>
+                <label class="label cursor-pointer">
+                    <span class="label-text">Serve the HTTP API on this machine</span>
+                    <input type="checkbox" class="toggle" prop:checked=move || api.get().enabled on:change=move |ev| {
+                        let enabled = event_target_checked(&ev);
+                        set_api_value.update(|api| api.enabled = enabled);
+                    }/>
+                </label>
+                <label class="form-control w-full">
+                    <div class="label">
+                        <span class="label-text">API port and token</span>
+                    </div>
+                    <div class="flex items-center gap-2">
+                        <input type="number" min="1" max="65535" class="input input-bordered w-28" prop:value=move || api.get().port.to_string() on:change=move |ev| {
+                            match event_target_value(&ev).parse() {
+                                Ok(port) => set_api_value.update(|api| api.port = port),
+                                Err(_) => messages.error("The port must be a number between 1 and 65535".to_string()),
+                            }
+                        }/>
+                        <input type="text" readonly=true class="input input-bordered w-full font-mono" prop:value=move || api.get().token/>
+                        <button class="btn" on:click=regenerate_token>New token</button>
+                    </div>
+                    <div class="label">
+                        <span class="label-text-alt">{"Sent as \"Authorization: Bearer <token>\" to http://127.0.0.1:<port>"}</span>
+                    </div>
+                </label>
+                <div class="modal-action">
+                    <button class="btn" on:click=move |_| set_dialog.set(false)>Cancel</button>
+                    <button class="btn btn-primary" on:click=save>Save</button>
+                </div>
+            </div>
+        </dialog>
+    }
+}
@@ -1,15 +1,43 @@
 use leptos::*;
-use web_time::Duration;
+use web_time::{Duration, SystemTime};
 
-use crate::invoke::get_activities_time;
+use crate::{components::budgets::format_duration, invoke::{get_activities_time, get_budget_usage, get_calendar, get_rounding, list_tags, listen, sessions_between, BudgetAlert, BudgetUsage, Calendar, Grouping, Period, Rounding, RoundingMode, Session}, notifications::Messages};
 
 
+/// The time spent on an activity and its budget for the period in seconds
+#[derive(Clone, Copy, PartialEq, Debug)]
+struct BudgetProgress {
+    used: u64,
+    budget: u64,
+}
+
+impl BudgetProgress {
+    fn label(&self) -> String {
+        if self.used > self.budget {
+            format!("{} / {} budget, {} over", format_duration(self.used), format_duration(self.budget), format_duration(self.used - self.budget))
+        } else {
+            format!("{} / {} budget, {} left", format_duration(self.used), format_duration(self.budget), format_duration(self.budget - self.used))
+        }
+    }
+}
+
 #[component]
 fn ProportionBar(
     activity: String,
     time: String,
     proportion: f64,
+    #[prop(default = None)]
+    budget: Option<BudgetProgress>,
 ) -> impl IntoView {
+    let budget = budget.map(|budget| {
+        let class = if budget.used >= budget.budget { "progress progress-error w-full" } else { "progress progress-success w-full" };
+        view! {
+            <div class="flex items-center gap-2 text-xs px-2">
+                <progress class=class value=budget.used.min(budget.budget).to_string() max=budget.budget.to_string()></progress>
+                <span class="text-nowrap">{budget.label()}</span>
+            </div>
+        }
+    });
     let style = format!("width: {percent}%; background-color:color-mix(in srgb, #CC8899 {percent}%, #00AAFF); color:#111111;", percent = proportion * 100.0);
     view! {
         <li class="w-full p-2">
@@ -18,18 +46,118 @@ fn ProportionBar(
                 <p class="absolute w-full top-0 left-0 p-2">{time.clone()}{" ● "}{activity.clone()}</p>
                 <p class="absolute top-0 left-0 overflow-hidden rounded text-nowrap p-2" style=style>{time}{" ● "}{activity}</p>
             </div>
+            {budget}
         </li> 
     }
 }
 
+/// The period the statistics are computed on
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum StatisticsPeriod {
+    SinceClear,
+    Today,
+    Week,
+    Month,
+}
+
+impl StatisticsPeriod {
+    const ALL: [StatisticsPeriod; 4] = [StatisticsPeriod::SinceClear, StatisticsPeriod::Today, StatisticsPeriod::Week, StatisticsPeriod::Month];
+
+    fn label(&self) -> &'static str {
+        match self {
+            StatisticsPeriod::SinceClear => "Since last clear",
+            StatisticsPeriod::Today => "Today",
+            StatisticsPeriod::Week => "This week",
+            StatisticsPeriod::Month => "This month",
+        }
+    }
+
+    /// Get the time range of a calendar period
+    ///
+    /// The time since the last clear is not a calendar period and is queried separately
+    fn range(&self, calendar: &Calendar) -> Period {
+        match self {
+            StatisticsPeriod::SinceClear => unreachable!("The time since the last clear has no calendar range"),
+            StatisticsPeriod::Today => calendar.day,
+            StatisticsPeriod::Week => calendar.week,
+            StatisticsPeriod::Month => calendar.month,
+        }
+    }
+
+    /// Get the budget of an activity for the period with the time spent on it, only days and weeks have budgets
+    fn budget(&self, usage: &BudgetUsage) -> Option<BudgetProgress> {
+        match self {
+            StatisticsPeriod::Today => usage.daily.map(|budget| BudgetProgress { used: usage.today, budget }),
+            StatisticsPeriod::Week => usage.weekly.map(|budget| BudgetProgress { used: usage.this_week, budget }),
+            _ => None,
+        }
+    }
+}
+
+/// The groupings the statistics can be shown by
+const GROUPINGS: [(Grouping, &str); 4] = [(Grouping::Activity, "By activity"), (Grouping::Project, "By project"), (Grouping::Client, "By client"), (Grouping::Tag, "By tag")];
+
+/// The grouping and the tag filter selected in the statistics, the export uses the same ones
+#[derive(Copy, Clone, Debug, Default)]
+pub struct StatisticsFilter {
+    pub grouping: RwSignal<Grouping>,
+    /// Only count the sessions with this tag if set
+    pub tag: RwSignal<Option<String>>,
+}
+
+/// Sum the duration of the sessions for each activity, project, client or tag, rounded like the backend does
+fn sum_sessions(sessions: Vec<Session>, grouping: Grouping, tag: Option<&str>, rounding: Rounding) -> Vec<(String, u64)> {
+    let now = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap().as_secs();
+    let mut times: Vec<(String, u64)> = Vec::new();
+
+    for session in sessions {
+        if tag.is_some_and(|tag| !session.tags.iter().any(|t| t == tag)) {
+            continue;
+        }
+        let duration = rounding.round_session(session.duration(now));
+        for key in grouping.keys(&session) {
+            match times.iter_mut().find(|(name, _)| *name == key) {
+                Some((_, time)) => *time += duration,
+                None => times.push((key, duration)),
+            }
+        }
+    }
+
+    for (_, time) in times.iter_mut() {
+        *time = rounding.round_total(*time);
+    }
+
+    times
+}
+
 #[component]
 pub fn Statistics() -> impl IntoView {
     let (activities, set_activities) = create_signal(Vec::new());
+    let (period, set_period) = create_signal(StatisticsPeriod::SinceClear);
+    let filter = expect_context::<StatisticsFilter>();
+    let (tags, set_tags) = create_signal(Vec::new());
+    let (budgets, set_budgets) = create_signal(Vec::new());
+    // The rounding shown under the statistics, None when they are not rounded
+    let (rounding, set_rounding) = create_signal(None::<Rounding>);
+    let messages = expect_context::<Messages>();
 
     let update_statistics = move || {
         let set_activities = set_activities.clone();
         async move {
-            let activities = get_activities_time().await;
+            let grouping = filter.grouping.get_untracked();
+            let tag = filter.tag.get_untracked();
+            // The backend already rounds the time since the last clear
+            let statistics_rounding = get_rounding().await.ok().filter(|rounding| rounding.statistics && rounding.mode != RoundingMode::None);
+            let activities = match period.get_untracked() {
+                StatisticsPeriod::SinceClear => get_activities_time(grouping, tag).await,
+                period => match get_calendar().await {
+                    Ok(calendar) => {
+                        let range = period.range(&calendar);
+                        sessions_between(range.start, range.end).await.map(|sessions| sum_sessions(sessions, grouping, tag.as_deref(), statistics_rounding.unwrap_or_default()))
+                    },
+                    Err(_) => return,
+                },
+            };
             let mut activities = match activities {
                 Ok(activities) => activities,
                 Err(_) => return,
@@ -37,6 +165,14 @@ pub fn Statistics() -> impl IntoView {
 
             activities.sort_by(|a, b| b.1.cmp(&a.1));
 
+            // The budgets are per activity so they are only shown when grouping by activity
+            let budgets = match grouping {
+                Grouping::Activity => get_budget_usage().await.unwrap_or_default(),
+                _ => Vec::new(),
+            };
+
+            set_budgets.set(budgets);
+            set_rounding.set(statistics_rounding);
             set_activities.set(activities);
         }
     };
@@ -47,9 +183,48 @@ pub fn Statistics() -> impl IntoView {
     set_interval(move ||{
         spawn_local(update_statistics());
     }, Duration::from_secs(1));
+
+    // Sent by the backend when a budget is about to be exceeded or is exceeded
+    listen("budget", move |alert: BudgetAlert| {
+        let message = format!("{} budget of {}: {} / {}", alert.period, alert.name, format_duration(alert.used), format_duration(alert.budget));
+        if alert.exceeded {
+            messages.error(format!("Exceeded the {}", message));
+        } else {
+            messages.warning(format!("Almost exceeded the {}", message));
+        }
+    });
+
+    let select_period = move |ev| {
+        let index: usize = event_target_value(&ev).parse().unwrap_or_default();
+        set_period.set(StatisticsPeriod::ALL[index]);
+        spawn_local(update_statistics());
+    };
+
+    let select_grouping = move |ev| {
+        let index: usize = event_target_value(&ev).parse().unwrap_or_default();
+        filter.grouping.set(GROUPINGS[index].0);
+        spawn_local(update_statistics());
+    };
+
+    let select_tag = move |ev| {
+        let tag = event_target_value(&ev);
+        filter.tag.set(if tag.is_empty() { None } else { Some(tag) });
+        spawn_local(update_statistics());
+    };
+
+    // Refresh the known tags when the tag list is opened
+    let update_tags = move |_| {
+        spawn_local(async move {
+            if let Ok(tags) = list_tags().await {
+                set_tags.set(tags);
+            }
+        });
+    };
     
     let render_activities = move || {
         let activities = activities.get();
+        let period = period.get();
+        let budgets = budgets.get();
 
         let max_time: u64 = *activities.iter().map(|(_, time)| time).max().unwrap_or(&1);
         
@@ -61,15 +236,41 @@ pub fn Statistics() -> impl IntoView {
             let hours = time / 3600;
             let minutes = (time % 3600) / 60;
 
+            let budget = budgets.iter().find(|usage| usage.name == activity).and_then(|usage| period.budget(usage));
+
             view! {
-                <ProportionBar activity=activity time=format!("{hours:2}h{minutes:2}") proportion=proportion/>
+                <ProportionBar activity=activity time=format!("{hours:2}h{minutes:2}") proportion=proportion budget=budget/>
             }
         }).collect_view()
     };
     
     view! {
         <ul class="w-full h-full bg-base-200 rounded-lg flex flex-col p-4 gap-2 overflow-y-scroll">
+            <li class="w-full flex justify-end gap-2">
+                <select class="select select-sm" on:change=select_tag on:focus=update_tags>
+                    <option value="">All tags</option>
+                    {move || tags.get().into_iter().map(|tag| {
+                        let selected = filter.tag.with(|selected| selected.as_ref() == Some(&tag));
+                        view!{
+                            <option value=tag.clone() selected=selected>{format!("#{}", tag)}</option>
+                        }
+                    }).collect_view()}
+                </select>
+                <select class="select select-sm" on:change=select_grouping>
+                    {GROUPINGS.iter().enumerate().map(|(index, (_, label))| view!{
+                        <option value=index.to_string()>{*label}</option>
+                    }).collect_view()}
+                </select>
+                <select class="select select-sm" on:change=select_period>
+                    {StatisticsPeriod::ALL.iter().enumerate().map(|(index, period)| view!{
+                        <option value=index.to_string()>{period.label()}</option>
+                    }).collect_view()}
+                </select>
+            </li>
             {render_activities}
+            {move || rounding.get().map(|rounding| view! {
+                <li class="w-full text-sm text-right">{rounding.describe()}</li>
+            })}
         </ul>
     }
 }
\ No newline at end of file
@@ -1,24 +1,21 @@
+use std::ops::RangeInclusive;
+
 use leptos::*;
 use web_time::{Duration, SystemTime};
 
-use crate::{invoke::todays_activities, notifications::Messages};
-
-/// The start hour of the timeline
-const START_HOUR: u32 = 8;
-/// The end hour of the timeline (the timeline finishes at END_HOUR exaclty, it does not include it)
-const END_HOUR: u32 = 19;
+use crate::{components::session_editor::SessionEditor, invoke::{get_calendar, get_working_hours, todays_activities, Calendar, Period, Session, WorkingHours}, notifications::Messages};
 
-
-pub fn hour_mark(hour: u32) -> impl IntoView{
-    let left = ((hour - START_HOUR) as f64 / (END_HOUR - START_HOUR) as f64 * 100.0) as u32;
+pub fn hour_mark(hour: u32, bounds: TimelineBounds) -> impl IntoView{
+    let left = bounds.hour_position(hour) as u32;
     view! {
         <div class="absolute top-0 bg-base-content h-12" style=format!("left: {left}%; width: 1px;", left=left)></div>
     }
 }
 
-pub fn hour_label(hour: u32) -> impl IntoView{
-    let left = ((hour - START_HOUR) as f64 / (END_HOUR - START_HOUR) as f64 * 100.0) as u32;
-    let text = format!("{:02}:00", hour);
+pub fn hour_label(hour: u32, bounds: TimelineBounds) -> impl IntoView{
+    let left = bounds.hour_position(hour) as u32;
+    // The hours past midnight belong to the day when it starts later
+    let text = format!("{:02}:00", hour % 24);
     view! {
         <p class="absolute top-0 text-sm text-center" style=format!("left: calc({left}% - 1.25rem); width: 2.5rem;", left=left)>
             {text}
@@ -26,8 +23,69 @@ pub fn hour_label(hour: u32) -> impl IntoView{
     }
 }
 
-pub fn render_activity((activity, start, end):(String, u64, Option<u64>)) -> impl IntoView{
-    let end = match end {
+/// The start and end of the timeline in seconds since the epoch, along with the hours they are at
+#[derive(Clone, Copy, PartialEq)]
+pub struct TimelineBounds {
+    start: u64,
+    end: u64,
+    first_hour: u32,
+    last_hour: u32,
+}
+
+impl TimelineBounds {
+    /**
+    Get the bounds of the timeline from the hours of the logical day.
+
+    The timeline shows the working hours and grows to the hours of the sessions outside of them, up to the end of the
+    logical day which can be past midnight.
+
+    # Arguments
+    calendar - The logical day and its hours
+    hours - The working hours
+    sessions - The sessions of the day
+    now - The current time in seconds since the epoch, the end of the running session
+     */
+    fn new(calendar: &Calendar, hours: WorkingHours, sessions: &[Session], now: u64) -> Option<Self> {
+        let mut first_hour = hours.start as usize;
+        let mut last_hour = hours.end as usize;
+        for session in sessions {
+            let end = session.end_time.unwrap_or(now);
+            if let Some(hour) = calendar.hours.iter().rposition(|&hour| hour <= session.start_time) {
+                first_hour = first_hour.min(hour);
+            }
+            let hour = calendar.hours.iter().position(|&hour| hour >= end).unwrap_or(calendar.hours.len().saturating_sub(1));
+            last_hour = last_hour.max(hour);
+        }
+
+        let start = *calendar.hours.get(first_hour)?;
+        let end = *calendar.hours.get(last_hour)?;
+        Some(Self { start, end, first_hour: first_hour as u32, last_hour: last_hour as u32 })
+    }
+
+    /// The hours shown on the timeline, from the midnight of the date of the day
+    fn hours(&self) -> RangeInclusive<u32> {
+        self.first_hour..=self.last_hour
+    }
+
+    /// Get the position of an hour mark on the timeline in percent
+    fn hour_position(&self, hour: u32) -> f64 {
+        (hour - self.first_hour) as f64 / (self.last_hour - self.first_hour) as f64 * 100.0
+    }
+
+    /// Get the position of a time on the timeline in percent
+    fn position(&self, time: u64) -> f32 {
+        (time as f32 - self.start as f32) / (self.end - self.start) as f32 * 100.0
+    }
+}
+
+pub fn render_activity(bounds: TimelineBounds, session: Session, on_edit: WriteSignal<Option<Session>>) -> impl IntoView{
+    let activity = session.name.clone();
+    let mut title = session.tags.iter().fold(activity.clone(), |title, tag| format!("{} #{}", title, tag));
+    if let Some(notes) = &session.notes {
+        title = format!("{}\n{}", title, notes);
+    }
+    let start = session.start_time;
+    let end = match session.end_time {
         Some(end) => end,
         None => {
             let now = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap().as_secs();
@@ -35,13 +93,8 @@ pub fn render_activity((activity, start, end):(String, u64, Option<u64>)) -> imp
         }
     };
 
-    let day_start = start - start % 86400;
-    let timeline_start = day_start + START_HOUR as u64 * 3600;
-    let timeline_end = day_start + END_HOUR as u64 * 3600;
-    let timeline_duration = timeline_end - timeline_start;
-
-    let left = (start - timeline_start as u64) as f32 / timeline_duration as f32 * 100.0;
-    let width = (end - start) as f32 / timeline_duration as f32 * 100.0;
+    let left = bounds.position(start);
+    let width = bounds.position(end) - left;
 
     let mut hue: u32 = 0;
     for byte in activity.as_bytes() {
@@ -52,35 +105,67 @@ pub fn render_activity((activity, start, end):(String, u64, Option<u64>)) -> imp
 
     let style = format!("left: {left:.2}%; width: {width:.2}%; background-color: hsl({hue}, 70%, 70%);", left=left, width=width, hue=hue);
 
+    // The breaks are drawn over the session, positioned relative to it
+    let breaks = session.breaks.iter().filter(|_| width > 0.0).map(|pause| {
+        let pause_start = pause.start_time.clamp(start, end);
+        let pause_end = pause.end_time.unwrap_or(end).clamp(pause_start, end);
+        let pause_left = (bounds.position(pause_start) - left) / width * 100.0;
+        let pause_width = (bounds.position(pause_end) - bounds.position(pause_start)) / width * 100.0;
+        let style = format!("left: {:.2}%; width: {:.2}%;", pause_left, pause_width);
+        view! {
+            <div class="absolute top-0 h-12 bg-base-100 opacity-60" style=style></div>
+        }
+    }).collect_view();
+
     view! {
-        <div title={activity.clone()} class="absolute top-0 h-12 p-2 radius rounded-md text-primary-content" style=style>
-            <p class="text-xs truncate">{activity}</p>
+        <div title=title class="absolute top-0 h-12 p-2 radius rounded-md text-primary-content cursor-pointer" style=style on:click=move |_| on_edit.set(Some(session.clone()))>
+            {breaks}
+            <p class="relative text-xs truncate">{activity}</p>
         </div>
     }
 }
 
-fn now_line() -> impl IntoView{
+fn now_line(day: Period, bounds: TimelineBounds) -> impl IntoView{
     let now = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap().as_secs();
-    let day_start = now - now % 86400;
-    let timeline_start = day_start + START_HOUR as u64 * 3600;
-    let timeline_end = day_start + END_HOUR as u64 * 3600;
-    let timeline_duration = timeline_end - timeline_start;
 
-    let left = (now - timeline_start as u64) as f32 / timeline_duration as f32 * 100.0;
+    // The day changed since the calendar was fetched
+    if now < day.start || now >= day.end {
+        return ().into_view();
+    }
+
+    let left = bounds.position(now);
 
     view! {
         <div class="absolute top-0 bg-primary h-12" style=format!("left: {left:2}%; width: 3px;", left=left)></div>
-    }
+    }.into_view()
 }
 
 #[component]
 pub fn Timeline() -> impl IntoView{
     let (activities, set_activities) = create_signal(Vec::new());
+    let (calendar, set_calendar) = create_signal(Calendar::default());
+    let (hours, set_hours) = create_signal(WorkingHours::default());
+    // The session being edited in the session editor
+    let (editing, set_editing) = create_signal(None::<Session>);
     let messages = expect_context::<Messages>();
 
     let update_activities = move || {
         let set_activities = set_activities.clone();
         async move {
+            // The day boundaries are computed by the backend in the local timezone
+            match get_calendar().await {
+                Ok(calendar) => {
+                    set_calendar.set(calendar);
+                },
+                Err(_) => {
+                    messages.error("Failed to fetch the calendar".to_string());
+                }
+            }
+
+            if let Ok(working_hours) = get_working_hours().await {
+                set_hours.set(working_hours);
+            }
+
             let activities = todays_activities().await;
             match activities {
                 Ok(activities) => {
@@ -100,21 +185,37 @@ pub fn Timeline() -> impl IntoView{
     }, Duration::from_secs(10));
     
 
+    // The bounds follow the sessions, the running one grows with the refreshes of the activities
+    let bounds = create_memo(move |_| {
+        let now = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap().as_secs();
+        calendar.with(|calendar| activities.with(|activities| TimelineBounds::new(calendar, hours.get(), activities, now)))
+    });
+
     view! {
         <div class="w-full flex flex-col px-5">
             <div class="w-full h-12 relative overflow-hidden">
                 // Timeline lines
-                {(START_HOUR..=END_HOUR).into_iter().map(hour_mark).collect_view()}
+                {move || {
+                    let Some(bounds) = bounds.get() else {
+                        return ().into_view();
+                    };
+                    bounds.hours().map(|hour| hour_mark(hour, bounds)).collect_view()
+                }}
                 // Activities & now line
                 {
                     move || {
                         let activities = activities.get();
+                        let day = calendar.with(|calendar| calendar.day);
+                        // Nothing to place on the timeline until the calendar is fetched
+                        let Some(bounds) = bounds.get() else {
+                            return ().into_view();
+                        };
                         view! {
                             <>
-                                {activities.into_iter().map(render_activity).collect_view()}
-                                {now_line()} // Now line just tags along for the update
+                                {activities.into_iter().map(|session| render_activity(bounds, session, set_editing)).collect_view()}
+                                {now_line(day, bounds)} // Now line just tags along for the update
                             </>
-                        }
+                        }.into_view()
                     }
                 }
             </div>
@@ -123,8 +224,14 @@ pub fn Timeline() -> impl IntoView{
                 // Non breaking space for the hours labels to align with the lines
                 {"\u{00A0}"}
                 // Hours labels
-                {(START_HOUR..=END_HOUR).into_iter().map(hour_label).collect_view()}
+                {move || {
+                    let Some(bounds) = bounds.get() else {
+                        return ().into_view();
+                    };
+                    bounds.hours().map(|hour| hour_label(hour, bounds)).collect_view()
+                }}
             </p>
+            <SessionEditor session=editing set_session=set_editing on_change=move || spawn_local(update_activities())/>
         </div>
     }
 }
\ No newline at end of file
@@ -7,6 +7,25 @@ use wasm_bindgen::prelude::*;
 extern "C" {
     #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "tauri"], catch)]
     async fn invoke(cmd: &str, args: JsValue) -> Result<JsValue, JsValue>;
+
+    #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "event"], js_name = listen)]
+    fn tauri_listen(event: &str, handler: &Closure<dyn FnMut(JsValue)>) -> js_sys::Promise;
+}
+
+/// Call the handler with the payload of every event with this name sent by the backend.
+/// The handler is kept for the lifetime of the application.
+pub fn listen<T: serde::de::DeserializeOwned + 'static>(event: &str, handler: impl Fn(T) + 'static) {
+    let name = event.to_string();
+    let closure = Closure::<dyn FnMut(JsValue)>::new(move |event: JsValue| {
+        let payload = js_sys::Reflect::get(&event, &JsValue::from_str("payload")).unwrap_or(JsValue::NULL);
+        match serde_wasm_bindgen::from_value(payload) {
+            Ok(payload) => handler(payload),
+            Err(e) => error!("{} event error: {:?}", name, e),
+        }
+    });
+
+    let _ = tauri_listen(event, &closure);
+    closure.forget();
 }
 
 pub async fn get_current_activity() -> String {
@@ -45,13 +64,15 @@ pub async fn start_activity(activity: &str, offset: i64) -> Result<(), ()> {
 #[derive(serde::Serialize)]
 struct StopActivityArgs {
     offset: i64,
+    notes: Option<String>,
 }
 
-pub async fn stop_activity(offset: i64) -> Result<(), ()>  {
+pub async fn stop_activity(offset: i64, notes: Option<String>) -> Result<(), ()>  {
     let args = StopActivityArgs {
         offset,
+        notes,
     };
-    let res = invoke("stop_activity", to_value(&args).expect("Serde should deserialize i64")).await;
+    let res = invoke("stop_activity", to_value(&args).expect("Serde should deserialize the stop arguments")).await;
 
     if let Err(err) = res {
         error!("stop_activity error: {:?}", err);
@@ -61,8 +82,336 @@ pub async fn stop_activity(offset: i64) -> Result<(), ()>  {
     Ok(())
 }
 
-pub async fn get_activities_time() -> Result<Vec<(String, u64)>, ()> {
-    let res = invoke("get_activities_times", to_value(&()).expect("Serde should deserialize ()")).await;
+/// What a timebox is counting
+#[derive(serde::Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TimeboxKind {
+    Work,
+    Break,
+}
+
+/// The state of the running timebox
+#[derive(serde::Deserialize, Clone, Debug, PartialEq)]
+pub struct TimeboxStatus {
+    pub activity: String,
+    pub kind: TimeboxKind,
+    /// The length of the timebox in seconds
+    pub length: u64,
+    /// The time elapsed in the timebox in seconds
+    pub elapsed: u64,
+    /// Whether the activity is paused outside of a pomodoro break
+    pub paused: bool,
+}
+
+/// Sent when a timebox runs out
+#[derive(serde::Deserialize, Clone, Debug, PartialEq)]
+pub struct TimeboxFinished {
+    pub activity: String,
+    pub kind: TimeboxKind,
+}
+
+#[derive(serde::Serialize)]
+struct StartTimeboxArgs {
+    activity: String,
+    offset: i64,
+    minutes: u32,
+}
+
+pub async fn start_timebox(activity: &str, offset: i64, minutes: u32) -> Result<(), String> {
+    let args = StartTimeboxArgs { activity: activity.to_string(), offset, minutes };
+    let res = invoke("start_timebox", to_value(&args).expect("Serde should serialize the timebox")).await;
+
+    if let Err(err) = res {
+        error!("start_timebox error: {:?}", err);
+
+        if err.is_string() {
+            return Err(err.as_string().unwrap());
+        } else {
+            return Err(format!("{:?}", err));
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn cancel_timebox() -> Result<(), ()> {
+    let res = invoke("cancel_timebox", to_value(&()).expect("Serde should serialize ()")).await;
+
+    if let Err(err) = res {
+        error!("cancel_timebox error: {:?}", err);
+        return Err(());
+    }
+
+    Ok(())
+}
+
+pub async fn get_timebox() -> Result<Option<TimeboxStatus>, ()> {
+    let res = invoke("get_timebox", to_value(&()).expect("Serde should serialize ()")).await;
+
+    match res {
+        Ok(val) => serde_wasm_bindgen::from_value(val).map_err(|e| {
+            error!("get_timebox error: {:?}", e);
+        }),
+        Err(e) => {
+            error!("get_timebox error: {:?}", e);
+            Err(())
+        }
+    }
+}
+
+pub async fn get_pomodoro_break_minutes() -> Result<u32, ()> {
+    let res = invoke("get_pomodoro_break_minutes", to_value(&()).expect("Serde should serialize ()")).await;
+
+    match res {
+        Ok(val) => serde_wasm_bindgen::from_value(val).map_err(|e| {
+            error!("get_pomodoro_break_minutes error: {:?}", e);
+        }),
+        Err(e) => {
+            error!("get_pomodoro_break_minutes error: {:?}", e);
+            Err(())
+        }
+    }
+}
+
+#[derive(serde::Serialize)]
+struct SetPomodoroBreakMinutesArgs {
+    minutes: u32,
+}
+
+pub async fn set_pomodoro_break_minutes(minutes: u32) -> Result<(), String> {
+    let args = SetPomodoroBreakMinutesArgs { minutes };
+    let res = invoke("set_pomodoro_break_minutes", to_value(&args).expect("Serde should serialize u32")).await;
+
+    if let Err(err) = res {
+        error!("set_pomodoro_break_minutes error: {:?}", err);
+
+        if err.is_string() {
+            return Err(err.as_string().unwrap());
+        } else {
+            return Err(format!("{:?}", err));
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn get_pomodoro_auto_breaks() -> Result<bool, ()> {
+    let res = invoke("get_pomodoro_auto_breaks", to_value(&()).expect("Serde should serialize ()")).await;
+
+    match res {
+        Ok(val) => serde_wasm_bindgen::from_value(val).map_err(|e| {
+            error!("get_pomodoro_auto_breaks error: {:?}", e);
+        }),
+        Err(e) => {
+            error!("get_pomodoro_auto_breaks error: {:?}", e);
+            Err(())
+        }
+    }
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SetPomodoroAutoBreaksArgs {
+    auto_breaks: bool,
+}
+
+pub async fn set_pomodoro_auto_breaks(auto_breaks: bool) -> Result<(), String> {
+    let args = SetPomodoroAutoBreaksArgs { auto_breaks };
+    let res = invoke("set_pomodoro_auto_breaks", to_value(&args).expect("Serde should serialize bool")).await;
+
+    if let Err(err) = res {
+        error!("set_pomodoro_auto_breaks error: {:?}", err);
+
+        if err.is_string() {
+            return Err(err.as_string().unwrap());
+        } else {
+            return Err(format!("{:?}", err));
+        }
+    }
+
+    Ok(())
+}
+
+/// A time the user was away while a session was running
+#[derive(serde::Deserialize, Clone, Debug, PartialEq)]
+pub struct IdlePeriod {
+    pub session_id: i64,
+    pub activity: String,
+    /// The time of the last input before leaving in seconds since the epoch
+    pub start: u64,
+    /// The time of the first input after coming back in seconds since the epoch
+    pub end: u64,
+}
+
+/// What to do with the idle time
+#[derive(serde::Serialize, Clone, Debug, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum IdleResolution {
+    Keep,
+    Discard,
+    Reassign { name: String },
+}
+
+pub async fn get_idle() -> Result<Option<IdlePeriod>, ()> {
+    let res = invoke("get_idle", to_value(&()).expect("Serde should serialize ()")).await;
+
+    match res {
+        Ok(val) => serde_wasm_bindgen::from_value(val).map_err(|e| {
+            error!("get_idle error: {:?}", e);
+        }),
+        Err(e) => {
+            error!("get_idle error: {:?}", e);
+            Err(())
+        }
+    }
+}
+
+#[derive(serde::Serialize)]
+struct ResolveIdleArgs {
+    resolution: IdleResolution,
+}
+
+pub async fn resolve_idle(resolution: IdleResolution) -> Result<(), String> {
+    let args = ResolveIdleArgs { resolution };
+    let res = invoke("resolve_idle", to_value(&args).expect("Serde should serialize the resolution")).await;
+
+    if let Err(err) = res {
+        error!("resolve_idle error: {:?}", err);
+
+        if err.is_string() {
+            return Err(err.as_string().unwrap());
+        } else {
+            return Err(format!("{:?}", err));
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn get_idle_minutes() -> Result<u32, ()> {
+    let res = invoke("get_idle_minutes", to_value(&()).expect("Serde should serialize ()")).await;
+
+    match res {
+        Ok(val) => serde_wasm_bindgen::from_value(val).map_err(|e| {
+            error!("get_idle_minutes error: {:?}", e);
+        }),
+        Err(e) => {
+            error!("get_idle_minutes error: {:?}", e);
+            Err(())
+        }
+    }
+}
+
+#[derive(serde::Serialize)]
+struct SetIdleMinutesArgs {
+    minutes: u32,
+}
+
+pub async fn set_idle_minutes(minutes: u32) -> Result<(), String> {
+    let args = SetIdleMinutesArgs { minutes };
+    let res = invoke("set_idle_minutes", to_value(&args).expect("Serde should serialize u32")).await;
+
+    if let Err(err) = res {
+        error!("set_idle_minutes error: {:?}", err);
+
+        if err.is_string() {
+            return Err(err.as_string().unwrap());
+        } else {
+            return Err(format!("{:?}", err));
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(serde::Serialize)]
+struct OffsetArgs {
+    offset: i64,
+}
+
+pub async fn pause_activity(offset: i64) -> Result<(), String> {
+    let args = OffsetArgs { offset };
+    let res = invoke("pause_activity", to_value(&args).expect("Serde should serialize i64")).await;
+
+    if let Err(err) = res {
+        error!("pause_activity error: {:?}", err);
+
+        if err.is_string() {
+            return Err(err.as_string().unwrap());
+        } else {
+            return Err(format!("{:?}", err));
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn resume_activity(offset: i64) -> Result<(), String> {
+    let args = OffsetArgs { offset };
+    let res = invoke("resume_activity", to_value(&args).expect("Serde should serialize i64")).await;
+
+    if let Err(err) = res {
+        error!("resume_activity error: {:?}", err);
+
+        if err.is_string() {
+            return Err(err.as_string().unwrap());
+        } else {
+            return Err(format!("{:?}", err));
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn is_paused() -> Result<bool, ()> {
+    let res = invoke("is_paused", to_value(&()).expect("Serde should serialize ()")).await;
+
+    match res {
+        Ok(val) => serde_wasm_bindgen::from_value(val).map_err(|e| {
+            error!("is_paused error: {:?}", e);
+        }),
+        Err(e) => {
+            error!("is_paused error: {:?}", e);
+            Err(())
+        }
+    }
+}
+
+/// What the time of the sessions is summed by
+#[derive(serde::Serialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Grouping {
+    #[default]
+    Activity,
+    Project,
+    Client,
+    Tag,
+}
+
+impl Grouping {
+    /// Get the names of the groups a session belongs to, same as the backend
+    pub fn keys(&self, session: &Session) -> Vec<String> {
+        match (self, &session.client) {
+            (Grouping::Activity, _) => vec![session.name.clone()],
+            (Grouping::Project, Some(client)) => vec![format!("{} › {}", client, session.project)],
+            (Grouping::Project, None) => vec![session.project.clone()],
+            (Grouping::Client, Some(client)) => vec![client.clone()],
+            (Grouping::Client, None) => vec!["No client".to_string()],
+            (Grouping::Tag, _) if session.tags.is_empty() => vec!["No tag".to_string()],
+            (Grouping::Tag, _) => session.tags.iter().map(|tag| format!("#{}", tag)).collect(),
+        }
+    }
+}
+
+#[derive(serde::Serialize)]
+struct GetActivitiesTimeArgs {
+    grouping: Grouping,
+    tag: Option<String>,
+}
+
+pub async fn get_activities_time(grouping: Grouping, tag: Option<String>) -> Result<Vec<(String, u64)>, ()> {
+    let args = GetActivitiesTimeArgs { grouping, tag };
+    let res = invoke("get_activities_times", to_value(&args).expect("Serde should serialize the grouping")).await;
 
     match res {
         Ok(val) => serde_wasm_bindgen::from_value(val).map_err(|e| {
@@ -123,7 +472,67 @@ pub async fn hard_clear_activities() -> Result<(), String> {
     Ok(())
 }
 
-pub async fn todays_activities() -> Result<Vec<(String, u64, Option<u64>)>, ()> {
+/// A session of an activity
+#[derive(serde::Deserialize, Clone, Debug, PartialEq)]
+pub struct Session {
+    pub id: i64,
+    pub name: String,
+    /// The start of the session in seconds since the epoch
+    pub start_time: u64,
+    /// The end of the session in seconds since the epoch, None if it is still running
+    pub end_time: Option<u64>,
+    /// The name of the project of the activity
+    pub project: String,
+    /// The name of the client of the project if it has one
+    pub client: Option<String>,
+    /// The tags of the session without the #
+    pub tags: Vec<String>,
+    /// What was done during the session
+    pub notes: Option<String>,
+    /// Whether the session is billed to the client
+    pub billable: bool,
+    /// The hourly rate in cents of the project or of its client
+    pub hourly_rate: Option<i64>,
+    /// The breaks taken during the session ordered by start time
+    pub breaks: Vec<Break>,
+}
+
+impl Session {
+    /// The time spent on the session without the breaks, now is used as the end of the running session and break
+    /// The time of the breaks overlapping each other is only removed once
+    pub fn duration(&self, now: u64) -> u64 {
+        let end_time = self.end_time.unwrap_or(now).max(self.start_time);
+        let mut breaks: Vec<(u64, u64)> = self.breaks.iter().map(|pause| {
+            let start = pause.start_time.clamp(self.start_time, end_time);
+            let end = pause.end_time.unwrap_or(now).clamp(start, end_time);
+            (start, end)
+        }).collect();
+        breaks.sort_unstable();
+
+        // The end of the breaks counted so far, a break is only counted from there
+        let mut counted = self.start_time;
+        let mut paused = 0;
+        for (start, end) in breaks {
+            let start = start.max(counted);
+            if end > start {
+                paused += end - start;
+                counted = end;
+            }
+        }
+
+        (end_time - self.start_time).saturating_sub(paused)
+    }
+}
+
+/// A break taken during a session
+#[derive(serde::Deserialize, Clone, Debug, PartialEq)]
+pub struct Break {
+    pub start_time: u64,
+    /// None while the session is paused
+    pub end_time: Option<u64>,
+}
+
+pub async fn todays_activities() -> Result<Vec<Session>, ()> {
     let res = invoke("todays_activities", to_value(&()).expect("Serde should serialize ()")).await;
 
     match res {
@@ -138,13 +547,1287 @@ pub async fn todays_activities() -> Result<Vec<(String, u64, Option<u64>)>, ()>
     }
 }
 
-pub async fn export_activities()-> Result<(), String>{
-    let res = invoke("export_activities", to_value(&()).expect("Serde should serialize ()")).await;
+#[derive(serde::Serialize)]
+struct SessionsBetweenArgs {
+    from: u64,
+    to: u64,
+}
+
+pub async fn sessions_between(from: u64, to: u64) -> Result<Vec<Session>, ()> {
+    let args = SessionsBetweenArgs { from, to };
+    let res = invoke("sessions_between", to_value(&args).expect("Serde should serialize (u64, u64)")).await;
+
+    match res {
+        Ok(val) => serde_wasm_bindgen::from_value(val).map_err(|e| {
+            error!("sessions_between error: {:?}", e);
+        }),
+        Err(e) => {
+            error!("sessions_between error: {:?}", e);
+            Err(())
+        }
+    }
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AddSessionArgs {
+    name: String,
+    start_time: u64,
+    end_time: u64,
+}
+
+pub async fn add_session(name: &str, start_time: u64, end_time: u64) -> Result<i64, String> {
+    let args = AddSessionArgs {
+        name: name.to_string(),
+        start_time,
+        end_time,
+    };
+    let res = invoke("add_session", to_value(&args).expect("Serde should serialize the session")).await;
+
+    match res {
+        Ok(val) => serde_wasm_bindgen::from_value(val).map_err(|e| {
+            error!("add_session error: {:?}", e);
+            format!("{:?}", e)
+        }),
+        Err(err) => {
+            error!("add_session error: {:?}", err);
+
+            if err.is_string() {
+                Err(err.as_string().unwrap())
+            } else {
+                Err(format!("{:?}", err))
+            }
+        }
+    }
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct UpdateSessionArgs {
+    id: i64,
+    name: String,
+    start_time: u64,
+    end_time: Option<u64>,
+}
+
+pub async fn update_session(id: i64, name: &str, start_time: u64, end_time: Option<u64>) -> Result<(), String> {
+    let args = UpdateSessionArgs {
+        id,
+        name: name.to_string(),
+        start_time,
+        end_time,
+    };
+    let res = invoke("update_session", to_value(&args).expect("Serde should serialize the session")).await;
 
     if let Err(err) = res {
-        error!("export_activities error: {:?}", err);
-        return Err(format!("{:?}", err));
+        error!("update_session error: {:?}", err);
+
+        if err.is_string() {
+            return Err(err.as_string().unwrap());
+        } else {
+            return Err(format!("{:?}", err));
+        }
     }
 
     Ok(())
 }
+
+#[derive(serde::Serialize)]
+struct SetSessionTagsArgs {
+    id: i64,
+    tags: Vec<String>,
+}
+
+pub async fn set_session_tags(id: i64, tags: Vec<String>) -> Result<(), String> {
+    let args = SetSessionTagsArgs { id, tags };
+    let res = invoke("set_session_tags", to_value(&args).expect("Serde should serialize the tags")).await;
+
+    if let Err(err) = res {
+        error!("set_session_tags error: {:?}", err);
+
+        if err.is_string() {
+            return Err(err.as_string().unwrap());
+        } else {
+            return Err(format!("{:?}", err));
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(serde::Serialize)]
+struct SetSessionNotesArgs {
+    id: i64,
+    notes: Option<String>,
+}
+
+pub async fn set_session_notes(id: i64, notes: Option<String>) -> Result<(), String> {
+    let args = SetSessionNotesArgs { id, notes };
+    let res = invoke("set_session_notes", to_value(&args).expect("Serde should serialize the notes")).await;
+
+    if let Err(err) = res {
+        error!("set_session_notes error: {:?}", err);
+
+        if err.is_string() {
+            return Err(err.as_string().unwrap());
+        } else {
+            return Err(format!("{:?}", err));
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(serde::Serialize)]
+struct SetSessionBillableArgs {
+    id: i64,
+    billable: bool,
+}
+
+pub async fn set_session_billable(id: i64, billable: bool) -> Result<(), String> {
+    let args = SetSessionBillableArgs { id, billable };
+    let res = invoke("set_session_billable", to_value(&args).expect("Serde should serialize the billable flag")).await;
+
+    if let Err(err) = res {
+        error!("set_session_billable error: {:?}", err);
+
+        if err.is_string() {
+            return Err(err.as_string().unwrap());
+        } else {
+            return Err(format!("{:?}", err));
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn list_tags() -> Result<Vec<String>, ()> {
+    let res = invoke("list_tags", to_value(&()).expect("Serde should serialize ()")).await;
+
+    match res {
+        Ok(val) => serde_wasm_bindgen::from_value(val).map_err(|e| {
+            error!("list_tags error: {:?}", e);
+        }),
+        Err(e) => {
+            error!("list_tags error: {:?}", e);
+            Err(())
+        }
+    }
+}
+
+#[derive(serde::Serialize)]
+struct DeleteSessionArgs {
+    id: i64,
+}
+
+pub async fn delete_session(id: i64) -> Result<(), String> {
+    let args = DeleteSessionArgs { id };
+    let res = invoke("delete_session", to_value(&args).expect("Serde should serialize i64")).await;
+
+    if let Err(err) = res {
+        error!("delete_session error: {:?}", err);
+
+        if err.is_string() {
+            return Err(err.as_string().unwrap());
+        } else {
+            return Err(format!("{:?}", err));
+        }
+    }
+
+    Ok(())
+}
+
+/// A problem in the sessions that makes the time counted wrong
+#[derive(serde::Deserialize, Clone, Debug)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Issue {
+    /// The session ends before it starts
+    NegativeDuration { session: Session },
+    /// The later session starts before the end of the earlier one
+    Overlap { earlier: Session, later: Session },
+}
+
+pub async fn check_integrity() -> Result<Vec<Issue>, ()> {
+    let res = invoke("check_integrity", to_value(&()).expect("Serde should serialize ()")).await;
+
+    match res {
+        Ok(val) => serde_wasm_bindgen::from_value(val).map_err(|e| {
+            error!("check_integrity error: {:?}", e);
+        }),
+        Err(e) => {
+            error!("check_integrity error: {:?}", e);
+            Err(())
+        }
+    }
+}
+
+/// The ways to fix an overlap between two sessions
+#[derive(serde::Serialize, Clone, Copy, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Repair {
+    TrimEarlier,
+    TrimLater,
+    Split,
+}
+
+#[derive(serde::Serialize)]
+struct RepairOverlapArgs {
+    earlier: i64,
+    later: i64,
+    repair: Repair,
+}
+
+pub async fn repair_overlap(earlier: i64, later: i64, repair: Repair) -> Result<(), String> {
+    let args = RepairOverlapArgs { earlier, later, repair };
+    let res = invoke("repair_overlap", to_value(&args).expect("Serde should serialize the repair")).await;
+
+    if let Err(err) = res {
+        error!("repair_overlap error: {:?}", err);
+
+        if err.is_string() {
+            return Err(err.as_string().unwrap());
+        } else {
+            return Err(format!("{:?}", err));
+        }
+    }
+
+    Ok(())
+}
+
+/// A span of time in seconds since the epoch, the start is included and the end is excluded
+#[derive(serde::Deserialize, Clone, Copy, Debug, Default)]
+pub struct Period {
+    pub start: u64,
+    pub end: u64,
+}
+
+/// The logical day, week and month of the current time as computed by the backend
+#[derive(serde::Deserialize, Clone, Debug, Default)]
+pub struct Calendar {
+    pub day: Period,
+    pub week: Period,
+    pub month: Period,
+    /// The start of each hour from 00:00 of the date of the day to the end of the day included, past 24:00 when the
+    /// day starts later than midnight
+    pub hours: Vec<u64>,
+}
+
+pub async fn get_calendar() -> Result<Calendar, ()> {
+    let res = invoke("get_calendar", to_value(&()).expect("Serde should serialize ()")).await;
+
+    match res {
+        Ok(val) => serde_wasm_bindgen::from_value(val).map_err(|e| {
+            error!("get_calendar error: {:?}", e);
+        }),
+        Err(e) => {
+            error!("get_calendar error: {:?}", e);
+            Err(())
+        }
+    }
+}
+
+/// What each row of the export is
+#[derive(serde::Serialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportLayout {
+    /// A row per session
+    Sessions,
+    /// A row per group with the total time
+    #[default]
+    Totals,
+}
+
+/// How the durations are written in the export
+#[derive(serde::Serialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum DurationFormat {
+    /// Ex : 1:30
+    #[default]
+    HoursMinutes,
+    /// Ex : 1.50
+    Decimal,
+}
+
+/// A column of the sessions layout
+#[derive(serde::Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionColumn {
+    Activity,
+    Project,
+    Client,
+    Tags,
+    Start,
+    End,
+    Duration,
+    Notes,
+    Billable,
+}
+
+/// What to export and how, the sessions since the last clear are exported without a range
+#[derive(serde::Serialize, Clone, Debug, Default, PartialEq)]
+pub struct ExportOptions {
+    pub layout: ExportLayout,
+    pub grouping: Grouping,
+    pub tag: Option<String>,
+    pub from: Option<u64>,
+    pub to: Option<u64>,
+    pub duration_format: DurationFormat,
+    pub columns: Vec<SessionColumn>,
+}
+
+#[derive(serde::Serialize)]
+struct ExportActivitiesArgs {
+    options: ExportOptions,
+}
+
+pub async fn export_activities(options: ExportOptions)-> Result<(), String>{
+    let args = ExportActivitiesArgs { options };
+    let res = invoke("export_activities", to_value(&args).expect("Serde should serialize the export options")).await;
+
+    if let Err(err) = res {
+        error!("export_activities error: {:?}", err);
+
+        if err.is_string() {
+            return Err(err.as_string().unwrap());
+        } else {
+            return Err(format!("{:?}", err));
+        }
+    }
+
+    Ok(())
+}
+
+/// A line of an invoice, the billable time spent on an activity
+#[derive(serde::Deserialize, Clone, Debug, PartialEq)]
+pub struct InvoiceLine {
+    pub client: Option<String>,
+    pub project: String,
+    pub activity: String,
+    /// The billable time in seconds
+    pub seconds: u64,
+    /// The hourly rate in cents, None if neither the project nor the client has one
+    pub hourly_rate: Option<i64>,
+    /// The amount in cents
+    pub amount: i64,
+}
+
+/// The billable sessions of a time range turned into invoice lines
+#[derive(serde::Deserialize, Clone, Debug, PartialEq)]
+pub struct Invoice {
+    pub from: u64,
+    pub to: u64,
+    pub client: Option<String>,
+    pub currency: String,
+    pub lines: Vec<InvoiceLine>,
+    /// The total billable time in seconds
+    pub seconds: u64,
+    /// The total amount in cents
+    pub amount: i64,
+    /// The description of the rounding applied to the times
+    pub rounding: String,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct InvoiceArgs {
+    from: u64,
+    to: u64,
+    client_id: Option<i64>,
+}
+
+pub async fn get_invoice(from: u64, to: u64, client_id: Option<i64>) -> Result<Invoice, String> {
+    let args = InvoiceArgs { from, to, client_id };
+    let res = invoke("get_invoice", to_value(&args).expect("Serde should serialize the invoice range")).await;
+
+    match res {
+        Ok(val) => serde_wasm_bindgen::from_value(val).map_err(|e| {
+            error!("get_invoice error: {:?}", e);
+            format!("{:?}", e)
+        }),
+        Err(err) => {
+            error!("get_invoice error: {:?}", err);
+
+            if err.is_string() {
+                Err(err.as_string().unwrap())
+            } else {
+                Err(format!("{:?}", err))
+            }
+        }
+    }
+}
+
+pub async fn export_invoice(from: u64, to: u64, client_id: Option<i64>) -> Result<(), String> {
+    let args = InvoiceArgs { from, to, client_id };
+    let res = invoke("export_invoice", to_value(&args).expect("Serde should serialize the invoice range")).await;
+
+    if let Err(err) = res {
+        error!("export_invoice error: {:?}", err);
+
+        if err.is_string() {
+            return Err(err.as_string().unwrap());
+        } else {
+            return Err(format!("{:?}", err));
+        }
+    }
+
+    Ok(())
+}
+
+/// The local hours shown on the timeline and during which the reminders are sent, the end is excluded
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct WorkingHours {
+    pub start: u32,
+    pub end: u32,
+}
+
+impl Default for WorkingHours {
+    fn default() -> Self {
+        Self { start: 8, end: 19 }
+    }
+}
+
+pub async fn get_working_hours() -> Result<WorkingHours, ()> {
+    let res = invoke("get_working_hours", to_value(&()).expect("Serde should serialize ()")).await;
+
+    match res {
+        Ok(val) => serde_wasm_bindgen::from_value(val).map_err(|e| {
+            error!("get_working_hours error: {:?}", e);
+        }),
+        Err(e) => {
+            error!("get_working_hours error: {:?}", e);
+            Err(())
+        }
+    }
+}
+
+#[derive(serde::Serialize)]
+struct SetWorkingHoursArgs {
+    hours: WorkingHours,
+}
+
+pub async fn set_working_hours(hours: WorkingHours) -> Result<(), String> {
+    let args = SetWorkingHoursArgs { hours };
+    let res = invoke("set_working_hours", to_value(&args).expect("Serde should serialize the working hours")).await;
+
+    if let Err(err) = res {
+        error!("set_working_hours error: {:?}", err);
+
+        if err.is_string() {
+            return Err(err.as_string().unwrap());
+        } else {
+            return Err(format!("{:?}", err));
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn get_reminder_minutes() -> Result<u32, ()> {
+    let res = invoke("get_reminder_minutes", to_value(&()).expect("Serde should serialize ()")).await;
+
+    match res {
+        Ok(val) => serde_wasm_bindgen::from_value(val).map_err(|e| {
+            error!("get_reminder_minutes error: {:?}", e);
+        }),
+        Err(e) => {
+            error!("get_reminder_minutes error: {:?}", e);
+            Err(())
+        }
+    }
+}
+
+#[derive(serde::Serialize)]
+struct SetReminderMinutesArgs {
+    minutes: u32,
+}
+
+pub async fn set_reminder_minutes(minutes: u32) -> Result<(), String> {
+    let args = SetReminderMinutesArgs { minutes };
+    let res = invoke("set_reminder_minutes", to_value(&args).expect("Serde should serialize u32")).await;
+
+    if let Err(err) = res {
+        error!("set_reminder_minutes error: {:?}", err);
+
+        if err.is_string() {
+            return Err(err.as_string().unwrap());
+        } else {
+            return Err(format!("{:?}", err));
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn get_currency() -> Result<String, ()> {
+    let res = invoke("get_currency", to_value(&()).expect("Serde should serialize ()")).await;
+
+    match res {
+        Ok(val) => serde_wasm_bindgen::from_value(val).map_err(|e| {
+            error!("get_currency error: {:?}", e);
+        }),
+        Err(e) => {
+            error!("get_currency error: {:?}", e);
+            Err(())
+        }
+    }
+}
+
+#[derive(serde::Serialize)]
+struct SetCurrencyArgs {
+    currency: String,
+}
+
+pub async fn set_currency(currency: &str) -> Result<(), String> {
+    let args = SetCurrencyArgs { currency: currency.to_string() };
+    let res = invoke("set_currency", to_value(&args).expect("Serde should serialize &str")).await;
+
+    if let Err(err) = res {
+        error!("set_currency error: {:?}", err);
+
+        if err.is_string() {
+            return Err(err.as_string().unwrap());
+        } else {
+            return Err(format!("{:?}", err));
+        }
+    }
+
+    Ok(())
+}
+
+/// The local HTTP API, only reachable from this machine with the token as a bearer token
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct ApiSettings {
+    pub enabled: bool,
+    pub port: u16,
+    pub token: String,
+}
+
+pub async fn get_api_settings() -> Result<ApiSettings, ()> {
+    let res = invoke("get_api_settings", to_value(&()).expect("Serde should serialize ()")).await;
+
+    match res {
+        Ok(val) => serde_wasm_bindgen::from_value(val).map_err(|e| {
+            error!("get_api_settings error: {:?}", e);
+        }),
+        Err(e) => {
+            error!("get_api_settings error: {:?}", e);
+            Err(())
+        }
+    }
+}
+
+#[derive(serde::Serialize)]
+struct SetApiSettingsArgs {
+    settings: ApiSettings,
+}
+
+pub async fn set_api_settings(settings: ApiSettings) -> Result<(), String> {
+    let args = SetApiSettingsArgs { settings };
+    let res = invoke("set_api_settings", to_value(&args).expect("Serde should serialize the API settings")).await;
+
+    if let Err(err) = res {
+        error!("set_api_settings error: {:?}", err);
+
+        if err.is_string() {
+            return Err(err.as_string().unwrap());
+        } else {
+            return Err(format!("{:?}", err));
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn regenerate_api_token() -> Result<String, String> {
+    let res = invoke("regenerate_api_token", to_value(&()).expect("Serde should serialize ()")).await;
+
+    match res {
+        Ok(val) => Ok(val.as_string().unwrap_or_default()),
+        Err(err) => {
+            error!("regenerate_api_token error: {:?}", err);
+
+            if err.is_string() {
+                Err(err.as_string().unwrap())
+            } else {
+                Err(format!("{:?}", err))
+            }
+        }
+    }
+}
+
+pub async fn get_day_start_hour() -> Result<u32, ()> {
+    let res = invoke("get_day_start_hour", to_value(&()).expect("Serde should serialize ()")).await;
+
+    match res {
+        Ok(val) => serde_wasm_bindgen::from_value(val).map_err(|e| {
+            error!("get_day_start_hour error: {:?}", e);
+        }),
+        Err(e) => {
+            error!("get_day_start_hour error: {:?}", e);
+            Err(())
+        }
+    }
+}
+
+#[derive(serde::Serialize)]
+struct SetDayStartHourArgs {
+    hour: u32,
+}
+
+pub async fn set_day_start_hour(hour: u32) -> Result<(), String> {
+    let args = SetDayStartHourArgs { hour };
+    let res = invoke("set_day_start_hour", to_value(&args).expect("Serde should serialize u32")).await;
+
+    if let Err(err) = res {
+        error!("set_day_start_hour error: {:?}", err);
+
+        if err.is_string() {
+            return Err(err.as_string().unwrap());
+        } else {
+            return Err(format!("{:?}", err));
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn get_ask_notes_on_stop() -> Result<bool, ()> {
+    let res = invoke("get_ask_notes_on_stop", to_value(&()).expect("Serde should serialize ()")).await;
+
+    match res {
+        Ok(val) => serde_wasm_bindgen::from_value(val).map_err(|e| {
+            error!("get_ask_notes_on_stop error: {:?}", e);
+        }),
+        Err(e) => {
+            error!("get_ask_notes_on_stop error: {:?}", e);
+            Err(())
+        }
+    }
+}
+
+#[derive(serde::Serialize)]
+struct SetAskNotesOnStopArgs {
+    ask: bool,
+}
+
+pub async fn set_ask_notes_on_stop(ask: bool) -> Result<(), String> {
+    let args = SetAskNotesOnStopArgs { ask };
+    let res = invoke("set_ask_notes_on_stop", to_value(&args).expect("Serde should serialize bool")).await;
+
+    if let Err(err) = res {
+        error!("set_ask_notes_on_stop error: {:?}", err);
+
+        if err.is_string() {
+            return Err(err.as_string().unwrap());
+        } else {
+            return Err(format!("{:?}", err));
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn get_close_to_tray() -> Result<bool, ()> {
+    let res = invoke("get_close_to_tray", to_value(&()).expect("Serde should serialize ()")).await;
+
+    match res {
+        Ok(val) => serde_wasm_bindgen::from_value(val).map_err(|e| {
+            error!("get_close_to_tray error: {:?}", e);
+        }),
+        Err(e) => {
+            error!("get_close_to_tray error: {:?}", e);
+            Err(())
+        }
+    }
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SetCloseToTrayArgs {
+    close_to_tray: bool,
+}
+
+pub async fn set_close_to_tray(close_to_tray: bool) -> Result<(), String> {
+    let args = SetCloseToTrayArgs { close_to_tray };
+    let res = invoke("set_close_to_tray", to_value(&args).expect("Serde should serialize bool")).await;
+
+    if let Err(err) = res {
+        error!("set_close_to_tray error: {:?}", err);
+
+        if err.is_string() {
+            return Err(err.as_string().unwrap());
+        } else {
+            return Err(format!("{:?}", err));
+        }
+    }
+
+    Ok(())
+}
+
+/// A client the projects are done for
+#[derive(serde::Deserialize, Clone, Debug, PartialEq)]
+pub struct Client {
+    pub id: i64,
+    pub name: String,
+    /// The hourly rate in cents
+    pub hourly_rate: Option<i64>,
+}
+
+pub async fn list_clients() -> Result<Vec<Client>, ()> {
+    let res = invoke("list_clients", to_value(&()).expect("Serde should serialize ()")).await;
+
+    match res {
+        Ok(val) => serde_wasm_bindgen::from_value(val).map_err(|e| {
+            error!("list_clients error: {:?}", e);
+        }),
+        Err(e) => {
+            error!("list_clients error: {:?}", e);
+            Err(())
+        }
+    }
+}
+
+#[derive(serde::Serialize)]
+struct AddClientArgs {
+    name: String,
+}
+
+pub async fn add_client(name: &str) -> Result<i64, String> {
+    let args = AddClientArgs { name: name.to_string() };
+    let res = invoke("add_client", to_value(&args).expect("Serde should serialize &str")).await;
+
+    match res {
+        Ok(val) => serde_wasm_bindgen::from_value(val).map_err(|e| {
+            error!("add_client error: {:?}", e);
+            format!("{:?}", e)
+        }),
+        Err(err) => {
+            error!("add_client error: {:?}", err);
+
+            if err.is_string() {
+                Err(err.as_string().unwrap())
+            } else {
+                Err(format!("{:?}", err))
+            }
+        }
+    }
+}
+
+/// A project grouping activities
+#[derive(serde::Deserialize, Clone, Debug, PartialEq)]
+pub struct Project {
+    pub id: i64,
+    pub name: String,
+    pub client_id: Option<i64>,
+    pub client: Option<String>,
+    /// The hourly rate in cents, overrides the rate of the client
+    pub hourly_rate: Option<i64>,
+}
+
+impl Project {
+    /// The name of the project prefixed by its client
+    pub fn label(&self) -> String {
+        match &self.client {
+            Some(client) => format!("{} › {}", client, self.name),
+            None => self.name.clone(),
+        }
+    }
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SetRateArgs {
+    id: i64,
+    hourly_rate: Option<i64>,
+}
+
+pub async fn set_client_rate(id: i64, hourly_rate: Option<i64>) -> Result<(), String> {
+    let args = SetRateArgs { id, hourly_rate };
+    let res = invoke("set_client_rate", to_value(&args).expect("Serde should serialize the rate")).await;
+
+    if let Err(err) = res {
+        error!("set_client_rate error: {:?}", err);
+
+        if err.is_string() {
+            return Err(err.as_string().unwrap());
+        } else {
+            return Err(format!("{:?}", err));
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn list_projects() -> Result<Vec<Project>, ()> {
+    let res = invoke("list_projects", to_value(&()).expect("Serde should serialize ()")).await;
+
+    match res {
+        Ok(val) => serde_wasm_bindgen::from_value(val).map_err(|e| {
+            error!("list_projects error: {:?}", e);
+        }),
+        Err(e) => {
+            error!("list_projects error: {:?}", e);
+            Err(())
+        }
+    }
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AddProjectArgs {
+    name: String,
+    client_id: Option<i64>,
+}
+
+pub async fn add_project(name: &str, client_id: Option<i64>) -> Result<i64, String> {
+    let args = AddProjectArgs { name: name.to_string(), client_id };
+    let res = invoke("add_project", to_value(&args).expect("Serde should serialize the project")).await;
+
+    match res {
+        Ok(val) => serde_wasm_bindgen::from_value(val).map_err(|e| {
+            error!("add_project error: {:?}", e);
+            format!("{:?}", e)
+        }),
+        Err(err) => {
+            error!("add_project error: {:?}", err);
+
+            if err.is_string() {
+                Err(err.as_string().unwrap())
+            } else {
+                Err(format!("{:?}", err))
+            }
+        }
+    }
+}
+
+pub async fn set_project_rate(id: i64, hourly_rate: Option<i64>) -> Result<(), String> {
+    let args = SetRateArgs { id, hourly_rate };
+    let res = invoke("set_project_rate", to_value(&args).expect("Serde should serialize the rate")).await;
+
+    if let Err(err) = res {
+        error!("set_project_rate error: {:?}", err);
+
+        if err.is_string() {
+            return Err(err.as_string().unwrap());
+        } else {
+            return Err(format!("{:?}", err));
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn activity_projects() -> Result<Vec<(String, i64)>, ()> {
+    let res = invoke("activity_projects", to_value(&()).expect("Serde should serialize ()")).await;
+
+    match res {
+        Ok(val) => serde_wasm_bindgen::from_value(val).map_err(|e| {
+            error!("activity_projects error: {:?}", e);
+        }),
+        Err(e) => {
+            error!("activity_projects error: {:?}", e);
+            Err(())
+        }
+    }
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SetActivityProjectArgs {
+    name: String,
+    project_id: i64,
+}
+
+pub async fn set_activity_project(name: &str, project_id: i64) -> Result<(), String> {
+    let args = SetActivityProjectArgs { name: name.to_string(), project_id };
+    let res = invoke("set_activity_project", to_value(&args).expect("Serde should serialize the assignment")).await;
+
+    if let Err(err) = res {
+        error!("set_activity_project error: {:?}", err);
+
+        if err.is_string() {
+            return Err(err.as_string().unwrap());
+        } else {
+            return Err(format!("{:?}", err));
+        }
+    }
+
+    Ok(())
+}
+
+/// The time that can be spent on an activity each day and each week in seconds
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Budget {
+    pub name: String,
+    pub daily: Option<u64>,
+    pub weekly: Option<u64>,
+}
+
+/// The budgets of an activity along with the time spent on it during the current day and week in seconds
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+pub struct BudgetUsage {
+    pub name: String,
+    pub daily: Option<u64>,
+    pub weekly: Option<u64>,
+    pub today: u64,
+    pub this_week: u64,
+}
+
+/// Sent by the backend when a budget is about to be exceeded or is exceeded
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct BudgetAlert {
+    pub name: String,
+    /// "daily" or "weekly"
+    pub period: String,
+    pub budget: u64,
+    pub used: u64,
+    pub exceeded: bool,
+}
+
+pub async fn list_budgets() -> Result<Vec<Budget>, ()> {
+    let res = invoke("list_budgets", to_value(&()).expect("Serde should serialize ()")).await;
+
+    match res {
+        Ok(val) => serde_wasm_bindgen::from_value(val).map_err(|e| {
+            error!("list_budgets error: {:?}", e);
+        }),
+        Err(e) => {
+            error!("list_budgets error: {:?}", e);
+            Err(())
+        }
+    }
+}
+
+#[derive(serde::Serialize)]
+struct SetBudgetArgs {
+    budget: Budget,
+}
+
+pub async fn set_budget(budget: Budget) -> Result<(), String> {
+    let args = SetBudgetArgs { budget };
+    let res = invoke("set_budget", to_value(&args).expect("Serde should serialize the budget")).await;
+
+    if let Err(err) = res {
+        error!("set_budget error: {:?}", err);
+
+        if err.is_string() {
+            return Err(err.as_string().unwrap());
+        } else {
+            return Err(format!("{:?}", err));
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn get_budget_usage() -> Result<Vec<BudgetUsage>, ()> {
+    let res = invoke("get_budget_usage", to_value(&()).expect("Serde should serialize ()")).await;
+
+    match res {
+        Ok(val) => serde_wasm_bindgen::from_value(val).map_err(|e| {
+            error!("get_budget_usage error: {:?}", e);
+        }),
+        Err(e) => {
+            error!("get_budget_usage error: {:?}", e);
+            Err(())
+        }
+    }
+}
+
+/// How the durations are rounded to the increment
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RoundingMode {
+    #[default]
+    None,
+    Nearest,
+    Up,
+    Down,
+}
+
+/// Whether each session or only the totals are rounded
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RoundingScope {
+    Session,
+    #[default]
+    Total,
+}
+
+/// The rounding of the durations in the exports, and in the statistics if `statistics` is set
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Rounding {
+    pub mode: RoundingMode,
+    /// The increment in minutes
+    pub increment: u32,
+    pub scope: RoundingScope,
+    pub statistics: bool,
+}
+
+impl Default for Rounding {
+    fn default() -> Self {
+        Self { mode: RoundingMode::None, increment: 15, scope: RoundingScope::Total, statistics: false }
+    }
+}
+
+impl Rounding {
+    /// Round a duration in seconds to the increment, same as the backend
+    pub fn round(&self, seconds: u64) -> u64 {
+        let increment = self.increment as u64 * 60;
+        if increment == 0 {
+            return seconds;
+        }
+
+        match self.mode {
+            RoundingMode::None => seconds,
+            RoundingMode::Nearest => (seconds + increment / 2) / increment * increment,
+            RoundingMode::Up => seconds.div_ceil(increment) * increment,
+            RoundingMode::Down => seconds / increment * increment,
+        }
+    }
+
+    /// Round the duration of a session, it is only rounded when each session is
+    pub fn round_session(&self, seconds: u64) -> u64 {
+        match self.scope {
+            RoundingScope::Session => self.round(seconds),
+            RoundingScope::Total => seconds,
+        }
+    }
+
+    /// Round a sum of sessions, it is only rounded when the totals are
+    pub fn round_total(&self, seconds: u64) -> u64 {
+        match self.scope {
+            RoundingScope::Session => seconds,
+            RoundingScope::Total => self.round(seconds),
+        }
+    }
+
+    /// Describe the rounding, Ex : "Rounded up to 15 minutes per session"
+    pub fn describe(&self) -> String {
+        let mode = match self.mode {
+            RoundingMode::None => return "Not rounded".to_string(),
+            RoundingMode::Nearest => "Rounded to the nearest",
+            RoundingMode::Up => "Rounded up to",
+            RoundingMode::Down => "Rounded down to",
+        };
+        let scope = match self.scope {
+            RoundingScope::Session => "per session",
+            RoundingScope::Total => "on the totals",
+        };
+        format!("{} {} minutes {}", mode, self.increment, scope)
+    }
+}
+
+pub async fn get_rounding() -> Result<Rounding, ()> {
+    let res = invoke("get_rounding", to_value(&()).expect("Serde should serialize ()")).await;
+
+    match res {
+        Ok(val) => serde_wasm_bindgen::from_value(val).map_err(|e| {
+            error!("get_rounding error: {:?}", e);
+        }),
+        Err(e) => {
+            error!("get_rounding error: {:?}", e);
+            Err(())
+        }
+    }
+}
+
+#[derive(serde::Serialize)]
+struct SetRoundingArgs {
+    rounding: Rounding,
+}
+
+pub async fn set_rounding(rounding: Rounding) -> Result<(), String> {
+    let args = SetRoundingArgs { rounding };
+    let res = invoke("set_rounding", to_value(&args).expect("Serde should serialize the rounding")).await;
+
+    if let Err(err) = res {
+        error!("set_rounding error: {:?}", err);
+
+        if err.is_string() {
+            return Err(err.as_string().unwrap());
+        } else {
+            return Err(format!("{:?}", err));
+        }
+    }
+
+    Ok(())
+}
+
+/// A file picked to be imported, its first row and a few rows after it to map the columns
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+pub struct ImportFile {
+    pub path: String,
+    pub columns: Vec<String>,
+    pub sample: Vec<Vec<String>>,
+}
+
+/// The index of the columns holding each field of the sessions, starting at 0
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize)]
+pub struct ColumnMapping {
+    pub name: usize,
+    pub start: usize,
+    pub end: Option<usize>,
+    pub duration: Option<usize>,
+    pub tags: Option<usize>,
+    pub notes: Option<usize>,
+}
+
+/// How to read the file to import
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize)]
+pub struct ImportOptions {
+    pub has_header: bool,
+    pub mapping: ColumnMapping,
+}
+
+/// A session read from the file to import
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+pub struct ImportedSession {
+    pub name: String,
+    pub start_time: u64,
+    pub end_time: u64,
+    pub tags: Vec<String>,
+    pub notes: Option<String>,
+}
+
+/// A row of the file that can't be imported
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+pub struct RejectedRow {
+    /// The line in the file, starting at 1
+    pub line: u64,
+    pub reason: String,
+}
+
+/// The sessions read from a file and the rows that can't be imported
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+pub struct ImportPreview {
+    pub sessions: Vec<ImportedSession>,
+    pub rejected: Vec<RejectedRow>,
+}
+
+/// The result of an import
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+pub struct ImportReport {
+    pub imported: usize,
+    pub rejected: Vec<RejectedRow>,
+}
+
+pub async fn open_import() -> Result<ImportFile, String> {
+    let res = invoke("open_import", to_value(&()).expect("Serde should serialize ()")).await;
+
+    match res {
+        Ok(val) => serde_wasm_bindgen::from_value(val).map_err(|e| {
+            error!("open_import error: {:?}", e);
+            format!("{:?}", e)
+        }),
+        Err(err) => {
+            error!("open_import error: {:?}", err);
+
+            if err.is_string() {
+                Err(err.as_string().unwrap())
+            } else {
+                Err(format!("{:?}", err))
+            }
+        }
+    }
+}
+
+#[derive(serde::Serialize)]
+struct ImportArgs<'a> {
+    path: &'a str,
+    options: ImportOptions,
+}
+
+pub async fn preview_import(path: &str, options: ImportOptions) -> Result<ImportPreview, String> {
+    let args = ImportArgs { path, options };
+    let res = invoke("preview_import", to_value(&args).expect("Serde should serialize the import options")).await;
+
+    match res {
+        Ok(val) => serde_wasm_bindgen::from_value(val).map_err(|e| {
+            error!("preview_import error: {:?}", e);
+            format!("{:?}", e)
+        }),
+        Err(err) => {
+            error!("preview_import error: {:?}", err);
+
+            if err.is_string() {
+                Err(err.as_string().unwrap())
+            } else {
+                Err(format!("{:?}", err))
+            }
+        }
+    }
+}
+
+pub async fn import_sessions(path: &str, options: ImportOptions) -> Result<ImportReport, String> {
+    let args = ImportArgs { path, options };
+    let res = invoke("import_sessions", to_value(&args).expect("Serde should serialize the import options")).await;
+
+    match res {
+        Ok(val) => serde_wasm_bindgen::from_value(val).map_err(|e| {
+            error!("import_sessions error: {:?}", e);
+            format!("{:?}", e)
+        }),
+        Err(err) => {
+            error!("import_sessions error: {:?}", err);
+
+            if err.is_string() {
+                Err(err.as_string().unwrap())
+            } else {
+                Err(format!("{:?}", err))
+            }
+        }
+    }
+}
+
+pub async fn export_dump() -> Result<(), String> {
+    let res = invoke("export_dump", to_value(&()).expect("Serde should serialize ()")).await;
+
+    if let Err(err) = res {
+        error!("export_dump error: {:?}", err);
+
+        if err.is_string() {
+            return Err(err.as_string().unwrap());
+        } else {
+            return Err(format!("{:?}", err));
+        }
+    }
+
+    Ok(())
+}
+
+/// How a backup is restored
+#[derive(serde::Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum RestoreMode {
+    /// Add the backup to the current data
+    Merge,
+    /// Delete the current data first
+    Replace,
+}
+
+#[derive(serde::Serialize)]
+struct RestoreDumpArgs {
+    mode: RestoreMode,
+}
+
+pub async fn restore_dump(mode: RestoreMode) -> Result<usize, String> {
+    let args = RestoreDumpArgs { mode };
+    let res = invoke("restore_dump", to_value(&args).expect("Serde should serialize the restore mode")).await;
+
+    match res {
+        Ok(val) => serde_wasm_bindgen::from_value(val).map_err(|e| {
+            error!("restore_dump error: {:?}", e);
+            format!("{:?}", e)
+        }),
+        Err(err) => {
+            error!("restore_dump error: {:?}", err);
+
+            if err.is_string() {
+                Err(err.as_string().unwrap())
+            } else {
+                Err(format!("{:?}", err))
+            }
+        }
+    }
+}
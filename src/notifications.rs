@@ -8,6 +8,7 @@ use crate::components::pure_html::icons;
 #[derive(Clone, Debug)]
 pub enum Message {
     Success(String),
+    Warning(String),
     Error(String),
 }
 
@@ -26,6 +27,12 @@ impl Messages {
         });
     }
 
+    pub fn warning(&self, message: String) {
+        self.messages.update(|messages| {
+            messages.push((Message::Warning(message), SystemTime::now()));
+        });
+    }
+
     pub fn error(&self, message: String) {
         self.messages.update(|messages| {
             messages.push((Message::Error(message), SystemTime::now()));
@@ -80,6 +87,14 @@ pub fn Notifications() -> impl IntoView {
                         </div>
                     }
                 },
+                Message::Warning(message) => {
+                    view!{
+                        <div role="alert" class="alert alert-warning">
+                            {icons::error()}
+                        <span>{message}</span>
+                        </div>
+                    }
+                },
                 Message::Error(message) => {
                     view!{
                         <div role="alert" class="alert alert-error">